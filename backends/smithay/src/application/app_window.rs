@@ -0,0 +1,179 @@
+use mctk_core::raw_handle::RawWaylandHandle;
+use mctk_core::resources::Resources;
+use mctk_core::types::PixelSize;
+use raw_window_handle::{
+    HasRawDisplayHandle, HasRawWindowHandle, RawDisplayHandle, RawWindowHandle,
+};
+use smithay_client_toolkit::reexports::calloop::channel::Sender;
+use std::time::Instant;
+
+use crate::xdg_shell::xdg_window::XdgWindowMessage;
+use crate::{WindowEvent, WindowMessage};
+
+use super::sctk_application::AppMessage;
+
+/// A surface opened on a shared [`SctkApplication`][super::sctk_application::SctkApplication].
+///
+/// Root components can downcast the window passed to `RootComponent::root` to an `AppWindow`
+/// to get the id of their surface and a sender for messages to other surfaces.
+pub struct AppWindow {
+    id: String,
+    width: u32,
+    height: u32,
+    scale_factor: f32,
+    handle: Option<RawWaylandHandle>,
+    window_tx: Sender<WindowMessage>,
+    app_tx: Sender<AppMessage>,
+    resources: Resources,
+}
+unsafe impl Send for AppWindow {}
+unsafe impl Sync for AppWindow {}
+
+impl AppWindow {
+    pub(crate) fn new(
+        id: String,
+        width: u32,
+        height: u32,
+        scale_factor: f32,
+        window_tx: Sender<WindowMessage>,
+        app_tx: Sender<AppMessage>,
+        resources: Resources,
+    ) -> Self {
+        Self {
+            id,
+            width,
+            height,
+            scale_factor,
+            handle: None,
+            window_tx,
+            app_tx,
+            resources,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn sender(&self) -> Sender<WindowMessage> {
+        self.window_tx.clone()
+    }
+
+    pub fn app_sender(&self) -> Sender<AppMessage> {
+        self.app_tx.clone()
+    }
+
+    /// Ignored by the application if the surface is not an xdg window
+    fn send_xdg_window_message(&self, message: XdgWindowMessage) {
+        let _ = self.app_tx.send(AppMessage::XdgWindow {
+            window_id: self.id.clone(),
+            message,
+        });
+    }
+}
+
+impl mctk_core::window::Window for AppWindow {
+    fn logical_size(&self) -> PixelSize {
+        PixelSize {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn physical_size(&self) -> PixelSize {
+        self.logical_size() // This should transform to device size
+    }
+
+    fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    fn redraw(&self) {
+        let _ = self.window_tx.send(WindowMessage::RedrawRequested);
+    }
+
+    fn next_frame(&self) {
+        let _ = self.window_tx.send(WindowMessage::RequestNextFrame);
+    }
+
+    fn schedule_timer(&self, deadline: Instant) {
+        let _ = self
+            .window_tx
+            .send(WindowMessage::ScheduleTimer { deadline });
+    }
+
+    fn resources(&self) -> Resources {
+        self.resources.clone()
+    }
+
+    fn set_size(&mut self, width: u32, height: u32) {
+        self.width = width;
+        self.height = height;
+    }
+
+    fn exit(&mut self) {
+        let _ = self.window_tx.send(WindowMessage::WindowEvent {
+            event: WindowEvent::CloseRequested,
+        });
+    }
+
+    fn set_title(&self, title: String) {
+        self.send_xdg_window_message(XdgWindowMessage::SetTitle { title });
+    }
+
+    fn set_app_id(&self, app_id: String) {
+        self.send_xdg_window_message(XdgWindowMessage::SetAppId { app_id });
+    }
+
+    fn maximize(&self) {
+        self.send_xdg_window_message(XdgWindowMessage::Maximize);
+    }
+
+    fn unmaximize(&self) {
+        self.send_xdg_window_message(XdgWindowMessage::Unmaximize);
+    }
+
+    fn fullscreen(&self) {
+        self.send_xdg_window_message(XdgWindowMessage::Fullscreen);
+    }
+
+    fn unfullscreen(&self) {
+        self.send_xdg_window_message(XdgWindowMessage::Unfullscreen);
+    }
+
+    fn minimize(&self) {
+        self.send_xdg_window_message(XdgWindowMessage::Minimize);
+    }
+
+    fn set_min_size(&self, size: Option<(u32, u32)>) {
+        self.send_xdg_window_message(XdgWindowMessage::SetMinSize { size });
+    }
+
+    fn set_max_size(&self, size: Option<(u32, u32)>) {
+        self.send_xdg_window_message(XdgWindowMessage::SetMaxSize { size });
+    }
+
+    fn set_wayland_handle(&mut self, wayland_handle: RawWaylandHandle) {
+        self.handle = Some(wayland_handle);
+    }
+
+    fn has_handle(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+unsafe impl HasRawWindowHandle for AppWindow {
+    fn raw_window_handle(&self) -> RawWindowHandle {
+        self.handle.unwrap().raw_window_handle()
+    }
+}
+
+unsafe impl HasRawDisplayHandle for AppWindow {
+    fn raw_display_handle(&self) -> RawDisplayHandle {
+        self.handle.unwrap().raw_display_handle()
+    }
+}
@@ -0,0 +1,2 @@
+pub mod app_window;
+pub mod sctk_application;
@@ -0,0 +1,1254 @@
+use crate::{
+    input::{
+        handle_window_event,
+        keyboard::KeyboardEvent,
+        pointer::{convert_button, MouseEvent, Point, ScrollDelta},
+        touch::{Position, TouchEvent, TouchPoint},
+    },
+    insert_timer,
+    layer_shell::layer_surface::LayerOptions,
+    new_raw_wayland_handle,
+    xdg_shell::{
+        xdg_surface::apply_xdg_window_message,
+        xdg_window::{configured_size, XdgWindowMessage, XdgWindowState},
+    },
+    WindowEvent, WindowInfo, WindowMessage, WindowOptions,
+};
+use ahash::AHashMap;
+use anyhow::Context;
+use mctk_core::component::{self, Component, RootComponent};
+use mctk_core::input::Input;
+use mctk_core::reexports::cosmic_text;
+use mctk_core::resources::Resources;
+use mctk_core::types::AssetParams;
+use mctk_core::ui::UI;
+use smithay_client_toolkit::{
+    activation::{ActivationHandler, ActivationState, RequestData},
+    compositor::{CompositorHandler, CompositorState},
+    delegate_activation, delegate_compositor, delegate_keyboard, delegate_layer, delegate_output,
+    delegate_pointer, delegate_registry, delegate_seat, delegate_touch, delegate_xdg_shell,
+    delegate_xdg_window,
+    output::{OutputHandler, OutputState},
+    reexports::{
+        calloop::{
+            self,
+            channel::{Event, Sender},
+            EventLoop, LoopHandle, RegistrationToken,
+        },
+        calloop_wayland_source::WaylandSource,
+        client::{
+            globals::registry_queue_init,
+            protocol::{
+                wl_keyboard::{self, WlKeyboard},
+                wl_output::{self, WlOutput},
+                wl_pointer::{self, AxisSource, WlPointer},
+                wl_seat::WlSeat,
+                wl_surface::WlSurface,
+            },
+            Connection, Proxy, QueueHandle,
+        },
+        protocols::ext::session_lock::v1::client::{
+            ext_session_lock_manager_v1::ExtSessionLockManagerV1,
+            ext_session_lock_surface_v1::{self, ExtSessionLockSurfaceV1},
+            ext_session_lock_v1::{self, ExtSessionLockV1},
+        },
+    },
+    registry::{ProvidesRegistryState, RegistryState},
+    registry_handlers,
+    seat::{
+        keyboard::{KeyEvent, KeyboardHandler, Keysym, Modifiers},
+        pointer::{PointerEvent, PointerEventKind, PointerHandler},
+        touch::TouchHandler,
+        Capability, SeatHandler, SeatState,
+    },
+    shell::{
+        wlr_layer::{self, LayerShell, LayerShellHandler, LayerSurface},
+        xdg::{
+            window::{Window, WindowConfigure, WindowDecorations, WindowHandler},
+            XdgShell,
+        },
+        WaylandSurface,
+    },
+};
+use std::any::Any;
+use std::collections::HashMap;
+use wayland_client::{
+    protocol::{
+        wl_display::WlDisplay,
+        wl_touch::{self, WlTouch},
+    },
+    Dispatch,
+};
+
+use super::app_window::AppWindow;
+
+/// Fonts, assets and svgs shared by every surface of the application.
+#[derive(Default)]
+pub struct ApplicationParams {
+    pub fonts: cosmic_text::fontdb::Database,
+    pub assets: HashMap<String, AssetParams>,
+    pub svgs: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub enum SurfaceKind {
+    #[default]
+    Xdg,
+    Layer(LayerOptions),
+    Lock,
+}
+
+#[derive(Default)]
+pub struct SurfaceParams {
+    /// `window_info.id` identifies the surface, it must be unique in the application
+    pub window_info: WindowInfo,
+    pub window_opts: WindowOptions,
+    pub kind: SurfaceKind,
+}
+
+/// App level messages, can be sent from any surface using [`AppWindow::app_sender`]
+#[derive(Debug)]
+pub enum AppMessage {
+    Send {
+        window_id: String,
+        message: component::Message,
+    },
+    ReconfigureLayerOpts {
+        window_id: String,
+        opts: LayerOptions,
+    },
+    XdgWindow {
+        window_id: String,
+        message: XdgWindowMessage,
+    },
+    Close {
+        window_id: String,
+    },
+    Unlock,
+    Exit,
+}
+unsafe impl Send for AppMessage {}
+
+enum SurfaceRole {
+    Xdg(Window),
+    Layer(LayerSurface),
+    Lock {
+        wl_surface: WlSurface,
+        lock_surface: ExtSessionLockSurfaceV1,
+    },
+}
+
+impl SurfaceRole {
+    fn wl_surface(&self) -> &WlSurface {
+        match self {
+            SurfaceRole::Xdg(window) => window.wl_surface(),
+            SurfaceRole::Layer(layer) => layer.wl_surface(),
+            SurfaceRole::Lock { wl_surface, .. } => wl_surface,
+        }
+    }
+
+    fn commit(&self) {
+        match self {
+            SurfaceRole::Xdg(window) => window.commit(),
+            SurfaceRole::Layer(layer) => layer.commit(),
+            SurfaceRole::Lock { wl_surface, .. } => wl_surface.commit(),
+        }
+    }
+}
+
+struct AppSurface {
+    id: String,
+    role: SurfaceRole,
+    window_tx: Sender<WindowMessage>,
+    token: Option<RegistrationToken>,
+    width: u32,
+    height: u32,
+    scale_factor: f32,
+    initial_configure_sent: bool,
+    toplevel_state: Option<XdgWindowState>,
+}
+
+/// A wayland connection and event loop shared by several xdg, layer or lock surfaces.
+///
+/// Every surface opened with [`SctkApplication::open_surface`] gets its own root component
+/// and `UI`, while the seat, outputs, fonts and assets are shared between them.
+pub struct SctkApplication {
+    conn: Connection,
+    queue_handle: QueueHandle<SctkApplication>,
+    loop_handle: LoopHandle<'static, SctkApplication>,
+    wl_display: WlDisplay,
+    registry_state: RegistryState,
+    seat_state: SeatState,
+    output_state: OutputState,
+    compositor: CompositorState,
+    xdg_shell: Option<XdgShell>,
+    xdg_activation: Option<ActivationState>,
+    layer_shell: Option<LayerShell>,
+    session_lock_manager: Option<ExtSessionLockManagerV1>,
+    session_lock: Option<ExtSessionLockV1>,
+    surfaces: Vec<AppSurface>,
+    resources: Resources,
+    app_tx: Sender<AppMessage>,
+    pub is_exited: bool,
+    keyboard: Option<wl_keyboard::WlKeyboard>,
+    keyboard_focus: Option<WlSurface>,
+    keyboard_modifiers: Modifiers,
+    pointer: Option<wl_pointer::WlPointer>,
+    touch: Option<wl_touch::WlTouch>,
+    seat: Option<WlSeat>,
+    touch_map: AHashMap<i32, TouchPoint>,
+    /// Seat and serial of the last button press or touch down, used to start moves and resizes
+    last_grab: Option<(WlSeat, u32)>,
+}
+
+impl SctkApplication {
+    pub fn new(params: ApplicationParams) -> anyhow::Result<(Self, EventLoop<'static, Self>)> {
+        let ApplicationParams {
+            fonts,
+            assets,
+            svgs,
+        } = params;
+
+        let conn = Connection::connect_to_env().expect("failed to connect to wayland");
+        let wl_display = conn.display();
+        let event_loop = EventLoop::<Self>::try_new()?;
+
+        let (globals, event_queue) =
+            registry_queue_init::<Self>(&conn).context("failed to init registry queue")?;
+
+        let queue_handle = event_queue.handle();
+
+        let loop_handle = event_loop.handle();
+        WaylandSource::new(conn.clone(), event_queue)
+            .insert(loop_handle.clone())
+            .expect("failed to insert wayland source into event loop");
+
+        let compositor = CompositorState::bind(&globals, &queue_handle)
+            .context("wl_compositor not availible")?;
+
+        // shells are optional, opening a surface of a missing kind fails instead
+        let xdg_shell = XdgShell::bind(&globals, &queue_handle).ok();
+        let xdg_activation = ActivationState::bind(&globals, &queue_handle).ok();
+        let layer_shell = LayerShell::bind(&globals, &queue_handle).ok();
+        let session_lock_manager = globals
+            .bind::<ExtSessionLockManagerV1, _, _>(
+                &queue_handle,
+                core::ops::RangeInclusive::new(1, 1),
+                (),
+            )
+            .ok();
+
+        // insert source for app level messages
+        let (app_tx, app_rx) = calloop::channel::channel();
+        let _ = loop_handle.insert_source(app_rx, move |event, _, state| {
+            let _ = match event {
+                calloop::channel::Event::Msg(msg) => {
+                    if let Err(e) = state.handle_app_message(msg) {
+                        tracing::warn!("failed to handle app message: {:#}", e);
+                    }
+                }
+                calloop::channel::Event::Closed => {}
+            };
+        });
+
+        let state = SctkApplication {
+            conn,
+            queue_handle: queue_handle.clone(),
+            loop_handle,
+            wl_display,
+            registry_state: RegistryState::new(&globals),
+            seat_state: SeatState::new(&globals, &queue_handle),
+            output_state: OutputState::new(&globals, &queue_handle),
+            compositor,
+            xdg_shell,
+            xdg_activation,
+            layer_shell,
+            session_lock_manager,
+            session_lock: None,
+            surfaces: vec![],
+            resources: Resources::new(fonts, assets, svgs),
+            app_tx,
+            is_exited: false,
+            keyboard: None,
+            keyboard_focus: None,
+            keyboard_modifiers: Modifiers::default(),
+            pointer: None,
+            touch: None,
+            seat: None,
+            touch_map: AHashMap::new(),
+            last_grab: None,
+        };
+
+        Ok((state, event_loop))
+    }
+
+    /// Opens a new surface with `A` as its root component, returns the sender of the surface.
+    pub fn open_surface<A, B>(
+        &mut self,
+        params: SurfaceParams,
+        app_params: B,
+    ) -> anyhow::Result<Sender<WindowMessage>>
+    where
+        A: 'static + RootComponent<B> + Component + Default + Send + Sync,
+        B: 'static + Any + Clone,
+    {
+        let SurfaceParams {
+            window_info,
+            window_opts,
+            kind,
+        } = params;
+        let WindowInfo { id, title, .. } = window_info;
+        let WindowOptions {
+            height,
+            width,
+            scale_factor,
+        } = window_opts;
+
+        if self.surfaces.iter().any(|s| s.id == id) {
+            anyhow::bail!("surface with id {} is already open", id);
+        }
+
+        let qh = self.queue_handle.clone();
+        let wl_surface = self.compositor.create_surface(&qh);
+
+        let role = match kind {
+            SurfaceKind::Xdg => {
+                let xdg_shell = self.xdg_shell.as_ref().context("xdg shell not availible")?;
+                let xdg_window =
+                    xdg_shell.create_window(wl_surface, WindowDecorations::RequestServer, &qh);
+
+                // set xdg shell props
+                xdg_window.set_app_id(id.clone());
+                xdg_window.set_title(title);
+                xdg_window.set_min_size(Some((width, height)));
+                xdg_window.commit();
+
+                // To request focus, we first need to request a token
+                if let Some(activation) = self.xdg_activation.as_ref() {
+                    activation.request_token(
+                        &qh,
+                        RequestData {
+                            seat_and_serial: None,
+                            surface: Some(xdg_window.wl_surface().clone()),
+                            app_id: Some(id.clone()),
+                        },
+                    )
+                }
+
+                SurfaceRole::Xdg(xdg_window)
+            }
+            SurfaceKind::Layer(layer_opts) => {
+                let layer_shell = self
+                    .layer_shell
+                    .as_ref()
+                    .context("layer shell not availible")?;
+                let LayerOptions {
+                    anchor,
+                    layer,
+                    keyboard_interactivity,
+                    namespace,
+                    zone,
+                } = layer_opts;
+                let layer =
+                    layer_shell.create_layer_surface(&qh, wl_surface, layer, namespace, None);
+
+                // set layer shell props
+                layer.set_keyboard_interactivity(keyboard_interactivity);
+                layer.set_size(width, height);
+                layer.set_anchor(anchor);
+                layer.set_exclusive_zone(zone);
+                layer.commit();
+
+                SurfaceRole::Layer(layer)
+            }
+            SurfaceKind::Lock => {
+                let session_lock_manager = self
+                    .session_lock_manager
+                    .as_ref()
+                    .context("compositor does not implement ext session lock manager (v1)")?;
+                let output = self
+                    .output_state
+                    .outputs()
+                    .next()
+                    .context("no output availible for the lock surface")?;
+
+                // all lock surfaces share one lock
+                let session_lock = self
+                    .session_lock
+                    .get_or_insert_with(|| session_lock_manager.lock(&qh, ()));
+                let lock_surface = session_lock.get_lock_surface(&wl_surface, &output, &qh, ());
+
+                SurfaceRole::Lock {
+                    wl_surface,
+                    lock_surface,
+                }
+            }
+        };
+
+        let (window_tx, window_rx) = calloop::channel::channel();
+
+        let mut ui: UI<AppWindow, A, B> = UI::new(
+            AppWindow::new(
+                id.clone(),
+                width,
+                height,
+                scale_factor,
+                window_tx.clone(),
+                self.app_tx.clone(),
+                self.resources.clone(),
+            ),
+            app_params,
+        );
+
+        // insert handle
+        let surface_id = id.clone();
+        let timer_tx = window_tx.clone();
+        let token = self
+            .loop_handle
+            .insert_source(
+                window_rx,
+                move |ev: Event<WindowMessage>, &mut _, state: &mut SctkApplication| {
+                    let _ = match ev {
+                        calloop::channel::Event::Msg(event) => match event {
+                            WindowMessage::Configure {
+                                width,
+                                height,
+                                wayland_handle,
+                            } => {
+                                ui.configure(width, height, wayland_handle);
+                                ui.draw();
+                            }
+                            WindowMessage::Send { message } => {
+                                ui.update(message);
+                                ui.draw();
+                            }
+                            WindowMessage::Resize { width, height } => {
+                                state.resize_surface(&surface_id, width, height);
+                                ui.resize(width, height);
+                                ui.draw();
+                            }
+                            WindowMessage::MainEventsCleared => {
+                                ui.draw();
+                            }
+                            WindowMessage::RedrawRequested => {
+                                ui.render();
+                            }
+                            WindowMessage::RequestNextFrame => {
+                                state.next_frame(&surface_id);
+                            }
+                            WindowMessage::ScheduleTimer { deadline } => {
+                                insert_timer(&state.loop_handle, deadline, timer_tx.clone());
+                            }
+                            WindowMessage::TimerExpired => {
+                                ui.handle_input(&Input::TimerExpired);
+                            }
+                            WindowMessage::CompositorFrame => {
+                                ui.handle_input(&Input::Timer);
+                            }
+                            WindowMessage::WindowEvent { event } => {
+                                handle_window_event(&mut ui, event);
+                                if let WindowEvent::CloseRequested = event {
+                                    state.close_surface(&surface_id);
+                                }
+                            }
+                        },
+                        calloop::channel::Event::Closed => {}
+                    };
+                },
+            )
+            .map_err(|_| anyhow::anyhow!("failed to insert surface source into event loop"))?;
+
+        self.surfaces.push(AppSurface {
+            id,
+            role,
+            window_tx: window_tx.clone(),
+            token: Some(token),
+            width,
+            height,
+            scale_factor,
+            initial_configure_sent: false,
+            toplevel_state: None,
+        });
+
+        Ok(window_tx)
+    }
+
+    pub fn sender(&self) -> Sender<AppMessage> {
+        self.app_tx.clone()
+    }
+
+    /// Returns the sender of an open surface
+    pub fn window_sender(&self, window_id: &str) -> Option<Sender<WindowMessage>> {
+        self.surfaces
+            .iter()
+            .find(|s| s.id == window_id)
+            .map(|s| s.window_tx.clone())
+    }
+
+    /// The fonts, images and SVGs shared by the surfaces, to add SVGs or load images at runtime.
+    pub fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    pub fn window_ids(&self) -> Vec<String> {
+        self.surfaces.iter().map(|s| s.id.clone()).collect()
+    }
+
+    fn handle_app_message(&mut self, msg: AppMessage) -> anyhow::Result<()> {
+        match msg {
+            AppMessage::Send { window_id, message } => {
+                let surface = self
+                    .surfaces
+                    .iter()
+                    .find(|s| s.id == window_id)
+                    .with_context(|| format!("no surface with id {}", window_id))?;
+                let _ = surface.window_tx.send(WindowMessage::Send { message });
+            }
+            AppMessage::ReconfigureLayerOpts { window_id, opts } => {
+                self.update_layer_opts(&window_id, opts);
+            }
+            AppMessage::XdgWindow { window_id, message } => {
+                let grab = self.last_grab.clone();
+                if let Some(AppSurface {
+                    role: SurfaceRole::Xdg(window),
+                    ..
+                }) = self.surface_mut(&window_id)
+                {
+                    let grab = grab.as_ref().map(|(seat, serial)| (seat, *serial));
+                    apply_xdg_window_message(window, message, grab);
+                }
+            }
+            AppMessage::Close { window_id } => {
+                if let Some(surface) = self.surfaces.iter().find(|s| s.id == window_id) {
+                    let _ = surface.window_tx.send(WindowMessage::WindowEvent {
+                        event: WindowEvent::CloseRequested,
+                    });
+                }
+            }
+            AppMessage::Unlock => {
+                self.unlock_and_destroy();
+            }
+            AppMessage::Exit => {
+                if self.surfaces.is_empty() {
+                    self.is_exited = true;
+                }
+                for surface in self.surfaces.iter() {
+                    let _ = surface.window_tx.send(WindowMessage::WindowEvent {
+                        event: WindowEvent::CloseRequested,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn surface_mut(&mut self, window_id: &str) -> Option<&mut AppSurface> {
+        self.surfaces.iter_mut().find(|s| s.id == window_id)
+    }
+
+    fn surface_by_wl(&self, wl_surface: &WlSurface) -> Option<&AppSurface> {
+        self.surfaces
+            .iter()
+            .find(|s| s.role.wl_surface() == wl_surface)
+    }
+
+    fn send_window_event(&self, wl_surface: &WlSurface, event: WindowEvent) {
+        if let Some(surface) = self.surface_by_wl(wl_surface) {
+            let _ = surface.window_tx.send(WindowMessage::WindowEvent { event });
+        }
+    }
+
+    fn send_initial_configure(&mut self, index: usize) {
+        let qh = self.queue_handle.clone();
+        let surface = &mut self.surfaces[index];
+        if surface.initial_configure_sent {
+            return;
+        }
+
+        let wl_surface = surface.role.wl_surface().clone();
+        let wayland_handle = new_raw_wayland_handle(&self.wl_display, &wl_surface);
+        let _ = surface.window_tx.send(WindowMessage::Configure {
+            width: surface.width,
+            height: surface.height,
+            wayland_handle,
+        });
+        surface.initial_configure_sent = true;
+
+        // request next frame
+        wl_surface.frame(&qh, wl_surface.clone());
+    }
+
+    fn resize_surface(&mut self, window_id: &str, width: u32, height: u32) {
+        let Some(surface) = self.surface_mut(window_id) else {
+            return;
+        };
+        // sizes configured by the compositor are already applied
+        if (width, height) == (surface.width, surface.height) {
+            return;
+        }
+        surface.width = width;
+        surface.height = height;
+
+        match &surface.role {
+            SurfaceRole::Xdg(window) => window.set_min_size(Some((width, height))),
+            SurfaceRole::Layer(layer) => layer.set_size(width, height),
+            // size of lock surfaces is set by the compositor
+            SurfaceRole::Lock { .. } => return,
+        };
+        surface.role.commit();
+    }
+
+    fn update_layer_opts(&mut self, window_id: &str, layer_opts: LayerOptions) {
+        let Some(surface) = self.surface_mut(window_id) else {
+            return;
+        };
+
+        if let SurfaceRole::Layer(layer) = &surface.role {
+            layer.set_keyboard_interactivity(layer_opts.keyboard_interactivity);
+            layer.set_anchor(layer_opts.anchor);
+            layer.set_exclusive_zone(layer_opts.zone);
+            layer.set_layer(layer_opts.layer);
+            layer.commit();
+        }
+    }
+
+    fn next_frame(&mut self, window_id: &str) {
+        let qh = self.queue_handle.clone();
+        let Some(surface) = self.surface_mut(window_id) else {
+            return;
+        };
+
+        // request next frame
+        let wl_surface = surface.role.wl_surface();
+        wl_surface.frame(&qh, wl_surface.clone());
+        surface.role.commit();
+    }
+
+    /// Destroys the surface, its `UI` is dropped once the current dispatch is done.
+    fn close_surface(&mut self, window_id: &str) {
+        let Some(index) = self.surfaces.iter().position(|s| s.id == window_id) else {
+            return;
+        };
+        let surface = self.surfaces.remove(index);
+
+        if self.keyboard_focus.as_ref() == Some(surface.role.wl_surface()) {
+            self.keyboard_focus = None;
+        }
+        self.touch_map
+            .retain(|_, tp| &tp.surface != surface.role.wl_surface());
+
+        match surface.role {
+            SurfaceRole::Xdg(window) => drop(window),
+            SurfaceRole::Layer(layer) => drop(layer),
+            SurfaceRole::Lock {
+                wl_surface,
+                lock_surface,
+            } => {
+                lock_surface.destroy();
+                wl_surface.destroy();
+            }
+        }
+
+        // the source can't be removed while it is being dispatched
+        if let Some(token) = surface.token {
+            self.loop_handle.insert_idle(move |state| {
+                state.loop_handle.remove(token);
+            });
+        }
+
+        if self.surfaces.is_empty() {
+            self.is_exited = true;
+        }
+    }
+
+    fn unlock_and_destroy(&mut self) {
+        let Some(session_lock) = self.session_lock.take() else {
+            return;
+        };
+        session_lock.unlock_and_destroy();
+
+        // send roundtrip to wait for reply from server
+        let _ = self.conn.roundtrip();
+
+        self.close_lock_surfaces();
+    }
+
+    fn close_lock_surfaces(&self) {
+        for surface in self.surfaces.iter() {
+            if let SurfaceRole::Lock { .. } = surface.role {
+                let _ = surface.window_tx.send(WindowMessage::WindowEvent {
+                    event: WindowEvent::CloseRequested,
+                });
+            }
+        }
+    }
+}
+
+impl CompositorHandler for SctkApplication {
+    fn scale_factor_changed(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        surface: &WlSurface,
+        new_scale_factor: i32,
+    ) {
+        if let Some(surface) = self
+            .surfaces
+            .iter_mut()
+            .find(|s| s.role.wl_surface() == surface)
+        {
+            surface.scale_factor = new_scale_factor as f32;
+        }
+    }
+
+    fn frame(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        surface: &WlSurface,
+        _time: u32,
+    ) {
+        if let Some(surface) = self.surface_by_wl(surface) {
+            let _ = surface.window_tx.send(WindowMessage::CompositorFrame);
+        }
+    }
+
+    fn transform_changed(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlSurface,
+        _: wl_output::Transform,
+    ) {
+        // TODO handle transform change
+    }
+}
+
+impl OutputHandler for SctkApplication {
+    fn output_state(&mut self) -> &mut OutputState {
+        &mut self.output_state
+    }
+
+    fn new_output(&mut self, _: &Connection, _: &QueueHandle<Self>, _: WlOutput) {}
+
+    fn update_output(&mut self, _: &Connection, _: &QueueHandle<Self>, _: WlOutput) {}
+
+    fn output_destroyed(&mut self, _: &Connection, _: &QueueHandle<Self>, _: WlOutput) {}
+}
+
+impl WindowHandler for SctkApplication {
+    fn request_close(&mut self, _: &Connection, _: &QueueHandle<Self>, window: &Window) {
+        self.send_window_event(window.wl_surface(), WindowEvent::CloseRequested);
+    }
+
+    fn configure(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        window: &Window,
+        configure: WindowConfigure,
+        _serial: u32,
+    ) {
+        if let Some(index) = self
+            .surfaces
+            .iter()
+            .position(|s| s.role.wl_surface() == window.wl_surface())
+        {
+            let surface = &mut self.surfaces[index];
+            if let Some((width, height)) =
+                configured_size(&configure, (surface.width, surface.height))
+            {
+                surface.width = width;
+                surface.height = height;
+                if surface.initial_configure_sent {
+                    let _ = surface
+                        .window_tx
+                        .send(WindowMessage::Resize { width, height });
+                }
+            }
+            self.send_initial_configure(index);
+
+            // send the toplevel states to the root component if they changed
+            let toplevel_state = XdgWindowState::from(&configure);
+            let surface = &mut self.surfaces[index];
+            if surface.toplevel_state != Some(toplevel_state) {
+                surface.toplevel_state = Some(toplevel_state);
+                let _ = surface.window_tx.send(WindowMessage::Send {
+                    message: Box::new(toplevel_state),
+                });
+            }
+        }
+    }
+}
+
+impl LayerShellHandler for SctkApplication {
+    fn closed(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, layer: &LayerSurface) {
+        self.send_window_event(layer.wl_surface(), WindowEvent::CloseRequested);
+    }
+
+    fn configure(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        layer: &LayerSurface,
+        _: wlr_layer::LayerSurfaceConfigure,
+        _serial: u32,
+    ) {
+        if let Some(index) = self
+            .surfaces
+            .iter()
+            .position(|s| s.role.wl_surface() == layer.wl_surface())
+        {
+            self.send_initial_configure(index);
+        }
+    }
+}
+
+impl ActivationHandler for SctkApplication {
+    type RequestData = RequestData;
+
+    fn new_token(&mut self, token: String, data: &Self::RequestData) {
+        if let (Some(activation), Some(surface)) = (self.xdg_activation.as_ref(), &data.surface) {
+            activation.activate::<SctkApplication>(surface, token);
+        }
+    }
+}
+
+impl SeatHandler for SctkApplication {
+    fn seat_state(&mut self) -> &mut SeatState {
+        &mut self.seat_state
+    }
+
+    fn new_seat(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _seat: WlSeat) {}
+
+    fn new_capability(
+        &mut self,
+        _conn: &Connection,
+        qh: &QueueHandle<Self>,
+        seat: WlSeat,
+        capability: Capability,
+    ) {
+        if capability == Capability::Keyboard && self.keyboard.is_none() {
+            let keyboard = self.seat_state.get_keyboard(qh, &seat, None).unwrap();
+            self.keyboard = Some(keyboard);
+        }
+        if capability == Capability::Pointer && self.pointer.is_none() {
+            let pointer = self.seat_state.get_pointer(qh, &seat).unwrap();
+            self.pointer = Some(pointer);
+            self.seat = Some(seat.clone());
+        }
+        if capability == Capability::Touch && self.touch.is_none() {
+            let touch = self.seat_state.get_touch(qh, &seat).unwrap();
+            self.touch = Some(touch);
+            self.seat = Some(seat.clone());
+        }
+    }
+
+    fn remove_capability(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _seat: WlSeat,
+        capability: Capability,
+    ) {
+        if capability == Capability::Keyboard {
+            if let Some(keyboard) = self.keyboard.take() {
+                keyboard.release();
+            }
+        }
+        if capability == Capability::Pointer {
+            if let Some(pointer) = self.pointer.take() {
+                pointer.release();
+            }
+        }
+        if capability == Capability::Touch {
+            if let Some(touch) = self.touch.take() {
+                touch.release();
+            }
+        }
+    }
+
+    fn remove_seat(&mut self, _conn: &Connection, _qh: &QueueHandle<Self>, _seat: WlSeat) {}
+}
+
+impl KeyboardHandler for SctkApplication {
+    fn enter(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _keyboard: &WlKeyboard,
+        surface: &WlSurface,
+        _serial: u32,
+        _raw: &[u32],
+        _: &[Keysym],
+    ) {
+        self.keyboard_focus = Some(surface.clone());
+        self.send_window_event(surface, WindowEvent::Focused);
+    }
+
+    fn leave(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _keyboard: &WlKeyboard,
+        surface: &WlSurface,
+        _serial: u32,
+    ) {
+        if self.keyboard_focus.as_ref() == Some(surface) {
+            self.keyboard_focus = None;
+        }
+        self.send_window_event(surface, WindowEvent::Unfocused);
+    }
+
+    fn press_key(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _keyboard: &WlKeyboard,
+        _serial: u32,
+        event: KeyEvent,
+    ) {
+        if let Some(surface) = self.keyboard_focus.as_ref() {
+            let key = event.keysym;
+            self.send_window_event(
+                surface,
+                WindowEvent::Keyboard(KeyboardEvent::KeyPressed { key }),
+            );
+        }
+    }
+
+    fn release_key(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _keyboard: &WlKeyboard,
+        _serial: u32,
+        event: KeyEvent,
+    ) {
+        if let Some(surface) = self.keyboard_focus.as_ref() {
+            let key = event.keysym;
+            self.send_window_event(
+                surface,
+                WindowEvent::Keyboard(KeyboardEvent::KeyReleased { key }),
+            );
+        }
+    }
+
+    fn update_modifiers(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _keyboard: &WlKeyboard,
+        _serial: u32,
+        modifiers: Modifiers,
+    ) {
+        self.keyboard_modifiers = modifiers;
+    }
+}
+
+impl PointerHandler for SctkApplication {
+    fn pointer_frame(
+        &mut self,
+        _conn: &Connection,
+        _qh: &QueueHandle<Self>,
+        _pointer: &WlPointer,
+        events: &[PointerEvent],
+    ) {
+        for event in events {
+            let scale_factor = match self.surface_by_wl(&event.surface) {
+                Some(surface) => surface.scale_factor,
+                None => continue,
+            };
+
+            let window_event = match event.kind {
+                PointerEventKind::Enter { .. } => WindowEvent::Mouse(MouseEvent::CursorEntered),
+                PointerEventKind::Leave { .. } => WindowEvent::Mouse(MouseEvent::CursorLeft),
+                PointerEventKind::Motion { .. } => WindowEvent::Mouse(MouseEvent::CursorMoved {
+                    position: Point {
+                        x: event.position.0 as f32,
+                        y: event.position.1 as f32,
+                    },
+                    scale_factor,
+                }),
+                PointerEventKind::Press { button, serial, .. } => {
+                    if let Some(seat) = self.seat.clone() {
+                        self.last_grab = Some((seat, serial));
+                    }
+                    if let Some(button) = convert_button(button) {
+                        WindowEvent::Mouse(MouseEvent::ButtonPressed { button })
+                    } else {
+                        continue;
+                    }
+                }
+                PointerEventKind::Release { button, .. } => {
+                    if let Some(button) = convert_button(button) {
+                        WindowEvent::Mouse(MouseEvent::ButtonReleased { button })
+                    } else {
+                        continue;
+                    }
+                }
+                PointerEventKind::Axis {
+                    horizontal,
+                    vertical,
+                    source,
+                    time: _,
+                } => {
+                    let delta = match source {
+                        Some(AxisSource::Wheel) | Some(AxisSource::WheelTilt) => {
+                            ScrollDelta::Lines {
+                                x: horizontal.discrete as f32,
+                                y: vertical.discrete as f32,
+                            }
+                        }
+                        Some(AxisSource::Finger) | Some(AxisSource::Continuous) => {
+                            ScrollDelta::Pixels {
+                                x: horizontal.absolute as f32,
+                                y: vertical.absolute as f32,
+                            }
+                        }
+                        _ => continue,
+                    };
+                    WindowEvent::Mouse(MouseEvent::WheelScrolled { delta })
+                }
+            };
+
+            self.send_window_event(&event.surface, window_event);
+        }
+    }
+}
+
+impl TouchHandler for SctkApplication {
+    fn down(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlTouch,
+        serial: u32,
+        time: u32,
+        surface: WlSurface,
+        id: i32,
+        position: (f64, f64),
+    ) {
+        let scale_factor = match self.surface_by_wl(&surface) {
+            Some(s) => s.scale_factor,
+            None => return,
+        };
+        if let Some(seat) = self.seat.clone() {
+            self.last_grab = Some((seat, serial));
+        }
+        let position = Position {
+            x: position.0 as f32,
+            y: position.1 as f32,
+        };
+
+        // insert the touch point
+        self.touch_map.insert(
+            id,
+            TouchPoint {
+                surface: surface.clone(),
+                position,
+            },
+        );
+
+        self.send_window_event(
+            &surface,
+            WindowEvent::Touch(TouchEvent::Down {
+                id,
+                time,
+                position,
+                scale_factor,
+            }),
+        );
+    }
+
+    fn up(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlTouch,
+        _: u32,
+        time: u32,
+        id: i32,
+    ) {
+        let touch_point = match self.touch_map.remove(&id) {
+            Some(touch_point) => touch_point,
+            None => return,
+        };
+        let scale_factor = match self.surface_by_wl(&touch_point.surface) {
+            Some(s) => s.scale_factor,
+            None => return,
+        };
+
+        self.send_window_event(
+            &touch_point.surface,
+            WindowEvent::Touch(TouchEvent::Up {
+                id,
+                time,
+                position: touch_point.position,
+                scale_factor,
+            }),
+        );
+    }
+
+    fn motion(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlTouch,
+        time: u32,
+        id: i32,
+        position: (f64, f64),
+    ) {
+        let position = Position {
+            x: position.0 as f32,
+            y: position.1 as f32,
+        };
+        let touch_point = match self.touch_map.get_mut(&id) {
+            Some(touch_point) => touch_point,
+            None => return,
+        };
+        touch_point.position = position;
+        let surface = touch_point.surface.clone();
+
+        let scale_factor = match self.surface_by_wl(&surface) {
+            Some(s) => s.scale_factor,
+            None => return,
+        };
+
+        self.send_window_event(
+            &surface,
+            WindowEvent::Touch(TouchEvent::Motion {
+                id,
+                time,
+                position,
+                scale_factor,
+            }),
+        );
+    }
+
+    fn shape(
+        &mut self,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+        _: &WlTouch,
+        _: i32,
+        _: f64,
+        _: f64,
+    ) {
+        // blank
+    }
+
+    fn orientation(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlTouch, _: i32, _: f64) {
+        // blank
+    }
+
+    fn cancel(&mut self, _: &Connection, _: &QueueHandle<Self>, _: &WlTouch) {
+        for (id, touch_point) in self.touch_map.drain().collect::<Vec<_>>() {
+            let scale_factor = match self.surface_by_wl(&touch_point.surface) {
+                Some(s) => s.scale_factor,
+                None => continue,
+            };
+            self.send_window_event(
+                &touch_point.surface,
+                WindowEvent::Touch(TouchEvent::Cancel {
+                    id,
+                    position: touch_point.position,
+                    scale_factor,
+                }),
+            );
+        }
+    }
+}
+
+impl ProvidesRegistryState for SctkApplication {
+    fn registry(&mut self) -> &mut RegistryState {
+        &mut self.registry_state
+    }
+
+    registry_handlers!(OutputState, SeatState);
+}
+
+delegate_compositor!(SctkApplication);
+delegate_output!(SctkApplication);
+delegate_seat!(SctkApplication);
+delegate_keyboard!(SctkApplication);
+delegate_pointer!(SctkApplication);
+delegate_touch!(SctkApplication);
+delegate_layer!(SctkApplication);
+delegate_xdg_shell!(SctkApplication);
+delegate_xdg_window!(SctkApplication);
+delegate_activation!(SctkApplication);
+delegate_registry!(SctkApplication);
+
+/* Session Lock binds */
+impl Dispatch<ExtSessionLockManagerV1, ()> for SctkApplication {
+    fn event(
+        _: &mut Self,
+        _: &ExtSessionLockManagerV1,
+        _: <ExtSessionLockManagerV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ExtSessionLockV1, ()> for SctkApplication {
+    fn event(
+        state: &mut Self,
+        _: &ExtSessionLockV1,
+        event: <ExtSessionLockV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        match event {
+            ext_session_lock_v1::Event::Locked => {}
+            ext_session_lock_v1::Event::Finished => {
+                // the compositor refused the lock or ended it, close the lock surfaces
+                if let Some(session_lock) = state.session_lock.take() {
+                    session_lock.destroy();
+                }
+                state.close_lock_surfaces();
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Dispatch<ExtSessionLockSurfaceV1, ()> for SctkApplication {
+    fn event(
+        state: &mut Self,
+        lock_surface: &ExtSessionLockSurfaceV1,
+        event: <ExtSessionLockSurfaceV1 as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let ext_session_lock_surface_v1::Event::Configure {
+            serial,
+            width,
+            height,
+        } = event
+        {
+            lock_surface.ack_configure(serial);
+
+            let index = state.surfaces.iter().position(|s| match &s.role {
+                SurfaceRole::Lock {
+                    lock_surface: surface,
+                    ..
+                } => surface == lock_surface,
+                _ => false,
+            });
+
+            if let Some(index) = index {
+                let surface = &mut state.surfaces[index];
+                if !surface.initial_configure_sent {
+                    surface.width = width;
+                    surface.height = height;
+                }
+                state.send_initial_configure(index);
+            }
+        }
+    }
+}
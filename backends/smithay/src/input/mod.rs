@@ -1,3 +1,125 @@
+use mctk_core::component::{Component, RootComponent};
+use mctk_core::input::{Button, Input, Motion, MouseButton, TouchAction};
+use mctk_core::ui::UI;
+use std::any::Any;
+
+use crate::WindowEvent;
+use keyboard::{keysym_to_key, KeyboardEvent};
+use pointer::{MouseEvent, ScrollDelta};
+use touch::TouchEvent;
+
 pub mod keyboard;
 pub mod pointer;
 pub mod touch;
+
+/// Maps a [`WindowEvent`] from the compositor to the matching [`Input`] of the `ui`.
+pub(crate) fn handle_window_event<W, A, B>(ui: &mut UI<W, A, B>, event: WindowEvent)
+where
+    W: 'static + mctk_core::window::Window,
+    A: 'static + RootComponent<B> + Component + Default + Send + Sync,
+    B: 'static + Any + Clone,
+{
+    match event {
+        WindowEvent::CloseRequested => {
+            ui.handle_input(&Input::Exit);
+        }
+        WindowEvent::Focused => {
+            ui.handle_input(&Input::Focus(true));
+        }
+        WindowEvent::Unfocused => {
+            ui.handle_input(&Input::Focus(false));
+        }
+        WindowEvent::Mouse(m_event) => match m_event {
+            MouseEvent::CursorEntered => {
+                ui.handle_input(&Input::MouseEnterWindow);
+            }
+            MouseEvent::CursorLeft => {
+                ui.handle_input(&Input::MouseLeaveWindow);
+            }
+            MouseEvent::CursorMoved {
+                position,
+                scale_factor,
+            } => {
+                ui.handle_input(&Input::Motion(Motion::Mouse {
+                    x: position.x / scale_factor,
+                    y: position.y / scale_factor,
+                }));
+            }
+            MouseEvent::ButtonPressed { button } => match button {
+                pointer::Button::Left => {
+                    ui.handle_input(&Input::Press(Button::Mouse(MouseButton::Left)))
+                }
+                pointer::Button::Right => {
+                    ui.handle_input(&Input::Press(Button::Mouse(MouseButton::Right)))
+                }
+                pointer::Button::Middle => {
+                    ui.handle_input(&Input::Press(Button::Mouse(MouseButton::Middle)))
+                }
+            },
+            MouseEvent::ButtonReleased { button } => match button {
+                pointer::Button::Left => {
+                    ui.handle_input(&Input::Release(Button::Mouse(MouseButton::Left)))
+                }
+                pointer::Button::Right => {
+                    ui.handle_input(&Input::Release(Button::Mouse(MouseButton::Right)))
+                }
+                pointer::Button::Middle => {
+                    ui.handle_input(&Input::Release(Button::Mouse(MouseButton::Middle)))
+                }
+            },
+            MouseEvent::WheelScrolled { delta } => {
+                let scroll = match delta {
+                    ScrollDelta::Lines { x, y } => Motion::Scroll {
+                        x: x * -30.0,
+                        y: y * -30.0,
+                    },
+                    ScrollDelta::Pixels { x, y } => Motion::Scroll { x: -x, y: -y },
+                };
+                ui.handle_input(&Input::Motion(scroll));
+            }
+        },
+        WindowEvent::Keyboard(k_ev) => match k_ev {
+            KeyboardEvent::KeyPressed { key } => {
+                ui.handle_input(&Input::Press(Button::Keyboard(keysym_to_key(key))));
+                ui.handle_input(&Input::Text(keysym_to_key(key).to_string()));
+            }
+            KeyboardEvent::KeyReleased { key } => {
+                ui.handle_input(&Input::Release(Button::Keyboard(keysym_to_key(key))));
+            }
+        },
+        WindowEvent::Touch(t_ev) => match t_ev {
+            TouchEvent::Up {
+                position,
+                scale_factor,
+                ..
+            } => ui.handle_input(&Input::Touch(TouchAction::Up {
+                x: position.x / scale_factor,
+                y: position.y / scale_factor,
+            })),
+            TouchEvent::Down {
+                position,
+                scale_factor,
+                ..
+            } => ui.handle_input(&Input::Touch(TouchAction::Down {
+                x: position.x / scale_factor,
+                y: position.y / scale_factor,
+            })),
+            TouchEvent::Motion {
+                position,
+                scale_factor,
+                ..
+            } => ui.handle_input(&Input::Touch(TouchAction::Moved {
+                x: position.x / scale_factor,
+                y: position.y / scale_factor,
+            })),
+            TouchEvent::Cancel {
+                position,
+                scale_factor,
+                ..
+            } => ui.handle_input(&Input::Touch(TouchAction::Cancel {
+                x: position.x / scale_factor,
+                y: position.y / scale_factor,
+            })),
+        },
+    }
+}
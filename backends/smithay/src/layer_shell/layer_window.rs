@@ -1,12 +1,12 @@
 use layer_surface::{LayerOptions, LayerShellSctkWindow};
 use mctk_core::component::{self, Component, RootComponent};
-use mctk_core::input::{Button, Input, Motion, MouseButton, TouchAction};
+use mctk_core::input::Input;
 use mctk_core::raw_handle::RawWaylandHandle;
 use mctk_core::reexports::cosmic_text;
+use mctk_core::resources::Resources;
 use mctk_core::types::AssetParams;
 use mctk_core::types::PixelSize;
 use mctk_core::ui::UI;
-use pointer::{MouseEvent, ScrollDelta};
 use raw_window_handle::{
     HasRawDisplayHandle, HasRawWindowHandle, RawDisplayHandle, RawWindowHandle,
 };
@@ -14,12 +14,12 @@ use smithay_client_toolkit::reexports::calloop::channel::{Channel, Event, Sender
 use smithay_client_toolkit::reexports::calloop::{self, EventLoop};
 use std::any::Any;
 use std::collections::HashMap;
+use std::time::Instant;
 
-use crate::input::keyboard::{keysym_to_key, KeyboardEvent};
-use crate::input::touch::TouchEvent;
 use crate::WindowInfo;
 use crate::{
-    input::pointer, layer_shell::layer_surface, WindowEvent, WindowMessage, WindowOptions,
+    input::handle_window_event, insert_timer, layer_shell::layer_surface, WindowEvent,
+    WindowMessage, WindowOptions,
 };
 
 pub struct LayerWindow {
@@ -28,9 +28,7 @@ pub struct LayerWindow {
     scale_factor: f32,
     handle: Option<RawWaylandHandle>,
     window_tx: Sender<WindowMessage>,
-    fonts: cosmic_text::fontdb::Database,
-    assets: HashMap<String, AssetParams>,
-    svgs: HashMap<String, String>,
+    resources: Resources,
     layer_tx: Option<Sender<LayerWindowMessage>>,
 }
 unsafe impl Send for LayerWindow {}
@@ -99,9 +97,7 @@ impl LayerWindow {
                 handle: None,
                 scale_factor: app_window.scale_factor,
                 window_tx: window_tx.clone(),
-                fonts,
-                assets,
-                svgs,
+                resources: Resources::new(fonts, assets, svgs),
                 layer_tx,
             },
             app_params,
@@ -109,6 +105,8 @@ impl LayerWindow {
 
         // insert handle
         let handle = event_loop.handle();
+        let timer_handle = handle.clone();
+        let timer_tx = window_tx.clone();
         let _ = handle.insert_source(
             window_rx,
             move |ev: Event<WindowMessage>, &mut _, app_window| {
@@ -125,7 +123,7 @@ impl LayerWindow {
                             }
                             WindowMessage::Send { message } => {
                                 ui.update(message);
-                                ui.draw(); // TODO: make this conditional
+                                ui.draw();
                             }
                             WindowMessage::Resize { width, height } => {
                                 app_window.resize(width, height);
@@ -142,123 +140,19 @@ impl LayerWindow {
                             WindowMessage::RequestNextFrame => {
                                 app_window.next_frame();
                             }
+                            WindowMessage::ScheduleTimer { deadline } => {
+                                insert_timer(&timer_handle, deadline, timer_tx.clone());
+                            }
+                            WindowMessage::TimerExpired => {
+                                ui.handle_input(&Input::TimerExpired);
+                            }
                             WindowMessage::CompositorFrame => {
                                 ui.handle_input(&Input::Timer);
                             }
-                            WindowMessage::WindowEvent { event: w_ev } => {
-                                // println!("window_event::{:?}", w_ev);
-                                match w_ev {
-                                    WindowEvent::CloseRequested => {
-                                        ui.handle_input(&Input::Exit);
-                                        app_window.close();
-                                    }
-                                    WindowEvent::Focused => {
-                                        ui.handle_input(&Input::Focus(true));
-                                    }
-                                    WindowEvent::Unfocused => {
-                                        ui.handle_input(&Input::Focus(false));
-                                    }
-                                    WindowEvent::Mouse(m_event) => match m_event {
-                                        MouseEvent::CursorEntered => {
-                                            ui.handle_input(&Input::MouseEnterWindow);
-                                        }
-                                        MouseEvent::CursorLeft => {
-                                            ui.handle_input(&Input::MouseLeaveWindow);
-                                        }
-                                        MouseEvent::CursorMoved {
-                                            position,
-                                            scale_factor,
-                                        } => {
-                                            ui.handle_input(&Input::Motion(Motion::Mouse {
-                                                x: position.x as f32 / scale_factor as f32,
-                                                y: position.y as f32 / scale_factor as f32,
-                                            }));
-                                        }
-                                        MouseEvent::ButtonPressed { button } => match button {
-                                            pointer::Button::Left => ui.handle_input(
-                                                &Input::Press(Button::Mouse(MouseButton::Left)),
-                                            ),
-                                            pointer::Button::Right => ui.handle_input(
-                                                &Input::Press(Button::Mouse(MouseButton::Right)),
-                                            ),
-                                            pointer::Button::Middle => ui.handle_input(
-                                                &Input::Press(Button::Mouse(MouseButton::Middle)),
-                                            ),
-                                        },
-                                        MouseEvent::ButtonReleased { button } => match button {
-                                            pointer::Button::Left => ui.handle_input(
-                                                &Input::Release(Button::Mouse(MouseButton::Left)),
-                                            ),
-                                            pointer::Button::Right => ui.handle_input(
-                                                &Input::Release(Button::Mouse(MouseButton::Right)),
-                                            ),
-                                            pointer::Button::Middle => ui.handle_input(
-                                                &Input::Release(Button::Mouse(MouseButton::Middle)),
-                                            ),
-                                        },
-                                        MouseEvent::WheelScrolled { delta } => {
-                                            let scroll = match delta {
-                                                ScrollDelta::Lines { x, y } => Motion::Scroll {
-                                                    x: x * -30.0,
-                                                    y: y * -30.0,
-                                                },
-                                                ScrollDelta::Pixels { x, y } => Motion::Scroll {
-                                                    x: -x as f32,
-                                                    y: -y as f32,
-                                                },
-                                            };
-                                            ui.handle_input(&Input::Motion(scroll));
-                                        }
-                                    },
-                                    WindowEvent::Keyboard(k_ev) => match k_ev {
-                                        KeyboardEvent::KeyPressed { key } => {
-                                            ui.handle_input(&Input::Press(Button::Keyboard(
-                                                keysym_to_key(key),
-                                            )));
-                                            ui.handle_input(&Input::Text(
-                                                keysym_to_key(key).to_string(),
-                                            ));
-                                        }
-                                        KeyboardEvent::KeyReleased { key } => {
-                                            ui.handle_input(&Input::Release(Button::Keyboard(
-                                                keysym_to_key(key),
-                                            )));
-                                        }
-                                    },
-                                    WindowEvent::Touch(t_ev) => match t_ev {
-                                        TouchEvent::Up {
-                                            position,
-                                            scale_factor,
-                                            ..
-                                        } => ui.handle_input(&Input::Touch(TouchAction::Up {
-                                            x: position.x / scale_factor,
-                                            y: position.y / scale_factor,
-                                        })),
-                                        TouchEvent::Down {
-                                            position,
-                                            scale_factor,
-                                            ..
-                                        } => ui.handle_input(&Input::Touch(TouchAction::Down {
-                                            x: position.x / scale_factor,
-                                            y: position.y / scale_factor,
-                                        })),
-                                        TouchEvent::Motion {
-                                            position,
-                                            scale_factor,
-                                            ..
-                                        } => ui.handle_input(&Input::Touch(TouchAction::Moved {
-                                            x: position.x / scale_factor,
-                                            y: position.y / scale_factor,
-                                        })),
-                                        TouchEvent::Cancel {
-                                            position,
-                                            scale_factor,
-                                            ..
-                                        } => ui.handle_input(&Input::Touch(TouchAction::Cancel {
-                                            x: position.x / scale_factor,
-                                            y: position.y / scale_factor,
-                                        })),
-                                    },
+                            WindowMessage::WindowEvent { event } => {
+                                handle_window_event(&mut ui, event);
+                                if let WindowEvent::CloseRequested = event {
+                                    app_window.close();
                                 }
                             }
                         }
@@ -301,16 +195,14 @@ impl mctk_core::window::Window for LayerWindow {
         let _ = self.window_tx.send(WindowMessage::RequestNextFrame);
     }
 
-    fn fonts(&self) -> cosmic_text::fontdb::Database {
-        self.fonts.clone()
-    }
-
-    fn assets(&self) -> HashMap<String, AssetParams> {
-        self.assets.clone()
+    fn schedule_timer(&self, deadline: Instant) {
+        let _ = self
+            .window_tx
+            .send(WindowMessage::ScheduleTimer { deadline });
     }
 
-    fn svgs(&self) -> HashMap<String, String> {
-        self.svgs.clone()
+    fn resources(&self) -> Resources {
+        self.resources.clone()
     }
 
     fn set_size(&mut self, width: u32, height: u32) {
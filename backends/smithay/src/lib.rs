@@ -1,3 +1,4 @@
+pub mod application;
 pub mod input;
 pub mod layer_shell;
 pub mod session_lock;
@@ -11,6 +12,10 @@ use mctk_core::raw_handle::RawWaylandHandle;
 use raw_window_handle::{
     RawDisplayHandle, RawWindowHandle, WaylandDisplayHandle, WaylandWindowHandle,
 };
+use smithay_client_toolkit::reexports::calloop::channel::Sender;
+use smithay_client_toolkit::reexports::calloop::timer::{TimeoutAction, Timer};
+use smithay_client_toolkit::reexports::calloop::LoopHandle;
+use std::time::Instant;
 use wayland_client::protocol::wl_display::WlDisplay;
 use wayland_client::protocol::wl_surface::WlSurface;
 use wayland_client::Proxy;
@@ -45,6 +50,10 @@ pub enum WindowMessage {
     MainEventsCleared,
     RedrawRequested,
     RequestNextFrame,
+    ScheduleTimer {
+        deadline: Instant,
+    },
+    TimerExpired,
     Resize {
         width: u32,
         height: u32,
@@ -67,6 +76,18 @@ pub enum WindowEvent {
     Touch(TouchEvent),
 }
 
+/// Sends [`WindowMessage::TimerExpired`] to the window once `deadline` is reached.
+pub(crate) fn insert_timer<D>(
+    loop_handle: &LoopHandle<'static, D>,
+    deadline: Instant,
+    window_tx: Sender<WindowMessage>,
+) {
+    let _ = loop_handle.insert_source(Timer::from_deadline(deadline), move |_, _, _| {
+        let _ = window_tx.send(WindowMessage::TimerExpired);
+        TimeoutAction::Drop
+    });
+}
+
 pub fn new_raw_wayland_handle(wl_display: &WlDisplay, wl_surface: &WlSurface) -> RawWaylandHandle {
     let wayland_handle = {
         let mut handle = WaylandDisplayHandle::empty();
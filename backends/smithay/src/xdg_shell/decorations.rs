@@ -0,0 +1,102 @@
+use mctk_core::component::{Component, Message};
+use mctk_core::style::Styled;
+use mctk_core::ui::Decorate;
+use mctk_core::widgets::{DecorationMessage, Div, TitleBar, WindowFrame};
+use mctk_core::{lay, node, rect, size, size_pct};
+use smithay_client_toolkit::reexports::calloop::channel::Sender;
+use std::sync::{Arc, RwLock};
+
+use crate::{WindowEvent, WindowMessage};
+
+use super::xdg_window::{XdgWindowMessage, XdgWindowState};
+
+/// Turns the [`DecorationMessage`]s of the title bar and frame into requests to the compositor.
+/// Other messages go on to the root component.
+#[derive(Debug)]
+struct DecorationHandler {
+    maximized: bool,
+    window_tx: Sender<WindowMessage>,
+    xdg_window_tx: Option<Sender<XdgWindowMessage>>,
+}
+
+impl Component for DecorationHandler {
+    fn update(&mut self, msg: Message) -> Vec<Message> {
+        let Some(message) = msg.downcast_ref::<DecorationMessage>() else {
+            return vec![msg];
+        };
+        let message = match message {
+            DecorationMessage::Move => XdgWindowMessage::Move,
+            DecorationMessage::Resize { edge } => XdgWindowMessage::Resize { edge: *edge },
+            DecorationMessage::Minimize => XdgWindowMessage::Minimize,
+            DecorationMessage::ToggleMaximize if self.maximized => XdgWindowMessage::Unmaximize,
+            DecorationMessage::ToggleMaximize => XdgWindowMessage::Maximize,
+            DecorationMessage::Close => {
+                // same as a close requested by the compositor
+                let _ = self.window_tx.send(WindowMessage::WindowEvent {
+                    event: WindowEvent::CloseRequested,
+                });
+                return vec![];
+            }
+        };
+        if let Some(xdg_window_tx) = self.xdg_window_tx.as_ref() {
+            let _ = xdg_window_tx.send(message);
+        }
+        vec![]
+    }
+}
+
+/// Draws a client-side [`TitleBar`] and resizable [`WindowFrame`] titled `title` around the
+/// view of the root component of an [`XdgWindow`][super::xdg_window::XdgWindow], for
+/// compositors that leave the decorations to the client.
+///
+/// Nothing is drawn around it while `toplevel_state` says the compositor draws the decorations
+/// itself, or that the window is fullscreen. The view keeps its place in the tree either way,
+/// so it keeps its state when the decorations come and go.
+pub(crate) fn decorate(
+    title: String,
+    toplevel_state: Arc<RwLock<XdgWindowState>>,
+    window_tx: Sender<WindowMessage>,
+    xdg_window_tx: Option<Sender<XdgWindowMessage>>,
+) -> Decorate {
+    Arc::new(move |view| {
+        let toplevel_state = *toplevel_state.read().unwrap();
+        let show = !toplevel_state.server_decorations && !toplevel_state.fullscreen;
+        let maximized = toplevel_state.maximized;
+
+        let frame = WindowFrame::new().resizable(show && !maximized && !toplevel_state.is_tiled());
+        let handle_width = frame.handle_width();
+        let mut frame = node!(
+            frame,
+            lay![
+                size_pct: [100.0],
+                direction: Column,
+                axis_alignment: Stretch,
+                cross_alignment: Stretch,
+                padding: [handle_width],
+            ]
+        );
+        if show {
+            let title_bar = TitleBar::new(title.clone()).maximized(maximized);
+            let title_bar_height = title_bar.style_val("height").unwrap().f32();
+            frame = frame.push(node!(title_bar, lay![size: [Auto, title_bar_height]]));
+        }
+        let content = node!(
+            Div::new(),
+            lay![
+                direction: Column,
+                cross_alignment: Stretch,
+            ]
+        )
+        .push(view);
+
+        node!(
+            DecorationHandler {
+                maximized,
+                window_tx: window_tx.clone(),
+                xdg_window_tx: xdg_window_tx.clone(),
+            },
+            lay![size_pct: [100.0]]
+        )
+        .push(frame.push(content))
+    })
+}
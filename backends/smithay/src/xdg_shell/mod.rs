@@ -1,2 +1,3 @@
+pub mod decorations;
 pub mod xdg_surface;
 pub mod xdg_window;
@@ -33,7 +33,7 @@ use smithay_client_toolkit::{
             },
             Connection, QueueHandle,
         },
-        protocols::xdg::shell::client::xdg_surface::XdgSurface,
+        protocols::xdg::shell::client::{xdg_surface::XdgSurface, xdg_toplevel},
     },
     registry::{ProvidesRegistryState, RegistryState},
     registry_handlers,
@@ -56,7 +56,9 @@ use wayland_client::protocol::{
     wl_touch::{self, WlTouch},
 };
 
-use super::xdg_window::XdgWindowMessage;
+use mctk_core::widgets::ResizeEdge;
+
+use super::xdg_window::{configured_size, XdgWindowMessage, XdgWindowState};
 
 pub struct XdgShellSctkWindow {
     queue_handle: QueueHandle<XdgShellSctkWindow>,
@@ -75,9 +77,15 @@ pub struct XdgShellSctkWindow {
     keyboard_modifiers: Modifiers,
     pointer: Option<wl_pointer::WlPointer>,
     touch: Option<wl_touch::WlTouch>,
+    seat: Option<WlSeat>,
     touch_map: AHashMap<i32, TouchPoint>,
+    /// Seat and serial of the last button press or touch down, used to start moves and resizes
+    last_grab: Option<(WlSeat, u32)>,
     initial_configure_sent: bool,
+    toplevel_state: Option<XdgWindowState>,
     pub scale_factor: f32,
+    /// The compositor can draw the decorations, otherwise the client has to
+    pub decoration_manager: bool,
 }
 
 impl XdgShellSctkWindow {
@@ -112,6 +120,11 @@ impl XdgShellSctkWindow {
 
         let xdg_shell =
             XdgShell::bind(&globals, &queue_handle).context("layer shell not availible")?;
+        let decoration_manager = globals.contents().with_list(|globals| {
+            globals
+                .iter()
+                .any(|global| global.interface == "zxdg_decoration_manager_v1")
+        });
 
         // If the compositor supports xdg-activation it probably wants us to use it to get focus
         let xdg_activation = ActivationState::bind(&globals, &queue_handle).ok();
@@ -143,7 +156,7 @@ impl XdgShellSctkWindow {
             let _ = loop_handle.insert_source(xdg_window_rx.unwrap(), move |event, _, state| {
                 let _ = match event {
                     calloop::channel::Event::Msg(msg) => {
-                        match msg {};
+                        state.handle_xdg_window_message(msg);
                     }
                     calloop::channel::Event::Closed => {}
                 };
@@ -167,9 +180,13 @@ impl XdgShellSctkWindow {
             keyboard_modifiers: Modifiers::default(),
             pointer: None,
             touch: None,
+            seat: None,
             touch_map: AHashMap::new(),
+            last_grab: None,
             initial_configure_sent: false,
+            toplevel_state: None,
             scale_factor,
+            decoration_manager,
         };
 
         Ok((state, event_loop))
@@ -208,6 +225,12 @@ impl XdgShellSctkWindow {
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
+        // sizes configured by the compositor are already applied
+        if (width, height) == (self.width, self.height) {
+            return;
+        }
+        self.width = width;
+        self.height = height;
         let window = &mut self.xdg_window;
 
         window.set_min_size(Some((width, height)));
@@ -228,6 +251,72 @@ impl XdgShellSctkWindow {
     pub fn close(&mut self) {
         self.is_exited = true;
     }
+
+    pub fn handle_xdg_window_message(&mut self, msg: XdgWindowMessage) {
+        let grab = self
+            .last_grab
+            .as_ref()
+            .map(|(seat, serial)| (seat, *serial));
+        apply_xdg_window_message(&self.xdg_window, msg, grab);
+    }
+
+    /// Sends the toplevel states to the root component if they changed
+    pub fn send_toplevel_state(&mut self, toplevel_state: XdgWindowState) {
+        if self.toplevel_state == Some(toplevel_state) {
+            return;
+        }
+        self.toplevel_state = Some(toplevel_state);
+        let _ = &self.window_tx.send(WindowMessage::Send {
+            message: Box::new(toplevel_state),
+        });
+    }
+}
+
+/// `grab` is the seat and serial of the input event starting a move or resize, these are
+/// ignored without one.
+pub(crate) fn apply_xdg_window_message(
+    window: &Window,
+    msg: XdgWindowMessage,
+    grab: Option<(&WlSeat, u32)>,
+) {
+    match msg {
+        XdgWindowMessage::SetTitle { title } => window.set_title(title),
+        XdgWindowMessage::SetAppId { app_id } => window.set_app_id(app_id),
+        XdgWindowMessage::Maximize => window.set_maximized(),
+        XdgWindowMessage::Unmaximize => window.unset_maximized(),
+        XdgWindowMessage::Fullscreen => window.set_fullscreen(None),
+        XdgWindowMessage::Unfullscreen => window.unset_fullscreen(),
+        XdgWindowMessage::Minimize => window.set_minimized(),
+        XdgWindowMessage::SetMinSize { size } => window.set_min_size(size),
+        XdgWindowMessage::SetMaxSize { size } => window.set_max_size(size),
+        XdgWindowMessage::RequestDecorations { mode } => window.request_decoration_mode(mode),
+        XdgWindowMessage::Move => {
+            if let Some((seat, serial)) = grab {
+                window.move_(seat, serial);
+            }
+        }
+        XdgWindowMessage::Resize { edge } => {
+            if let Some((seat, serial)) = grab {
+                window.resize(seat, serial, to_xdg_resize_edge(edge));
+            }
+        }
+    };
+
+    // size limits are double buffered
+    window.commit();
+}
+
+pub(crate) fn to_xdg_resize_edge(edge: ResizeEdge) -> xdg_toplevel::ResizeEdge {
+    match edge {
+        ResizeEdge::Top => xdg_toplevel::ResizeEdge::Top,
+        ResizeEdge::Bottom => xdg_toplevel::ResizeEdge::Bottom,
+        ResizeEdge::Left => xdg_toplevel::ResizeEdge::Left,
+        ResizeEdge::Right => xdg_toplevel::ResizeEdge::Right,
+        ResizeEdge::TopLeft => xdg_toplevel::ResizeEdge::TopLeft,
+        ResizeEdge::TopRight => xdg_toplevel::ResizeEdge::TopRight,
+        ResizeEdge::BottomLeft => xdg_toplevel::ResizeEdge::BottomLeft,
+        ResizeEdge::BottomRight => xdg_toplevel::ResizeEdge::BottomRight,
+    }
 }
 
 impl CompositorHandler for XdgShellSctkWindow {
@@ -291,6 +380,14 @@ impl WindowHandler for XdgShellSctkWindow {
         _serial: u32,
     ) {
         println!("Window configured to: {:?}", configure);
+        if let Some((width, height)) = configured_size(&configure, (self.width, self.height)) {
+            self.width = width;
+            self.height = height;
+            if self.initial_configure_sent {
+                let _ = self.window_tx.send(WindowMessage::Resize { width, height });
+            }
+        }
+
         if !self.initial_configure_sent {
             self.send_configure_event(self.width, self.height);
             self.initial_configure_sent = true;
@@ -300,6 +397,8 @@ impl WindowHandler for XdgShellSctkWindow {
                 .wl_surface()
                 .frame(qh, self.xdg_window.wl_surface().clone());
         }
+
+        self.send_toplevel_state(XdgWindowState::from(&configure));
     }
 }
 
@@ -335,10 +434,12 @@ impl SeatHandler for XdgShellSctkWindow {
         if capability == Capability::Pointer && self.pointer.is_none() {
             let pointer = self.seat_state.get_pointer(qh, &seat).unwrap();
             self.pointer = Some(pointer);
+            self.seat = Some(seat.clone());
         }
         if capability == Capability::Touch && self.touch.is_none() {
             let touch = self.seat_state.get_touch(qh, &seat).unwrap();
             self.touch = Some(touch);
+            self.seat = Some(seat.clone());
         }
     }
 
@@ -465,7 +566,10 @@ impl PointerHandler for XdgShellSctkWindow {
                     },
                     scale_factor: self.scale_factor,
                 }),
-                PointerEventKind::Press { button, .. } => {
+                PointerEventKind::Press { button, serial, .. } => {
+                    if let Some(seat) = self.seat.clone() {
+                        self.last_grab = Some((seat, serial));
+                    }
                     if let Some(button) = convert_button(button) {
                         WindowEvent::Mouse(MouseEvent::ButtonPressed { button })
                     } else {
@@ -519,7 +623,7 @@ impl TouchHandler for XdgShellSctkWindow {
         _: &Connection,
         _: &QueueHandle<Self>,
         _: &WlTouch,
-        _: u32,
+        serial: u32,
         time: u32,
         surface: WlSurface,
         id: i32,
@@ -528,6 +632,9 @@ impl TouchHandler for XdgShellSctkWindow {
         if self.xdg_window.wl_surface() != &surface {
             return;
         }
+        if let Some(seat) = self.seat.clone() {
+            self.last_grab = Some((seat, serial));
+        }
         let scale_factor = self.scale_factor;
 
         // insert the touch point
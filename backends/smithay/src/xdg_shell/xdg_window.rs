@@ -1,37 +1,40 @@
 use mctk_core::component::{self, Component, RootComponent};
-use mctk_core::input::{Button, Input, Motion, MouseButton, TouchAction};
+use mctk_core::input::Input;
 use mctk_core::raw_handle::RawWaylandHandle;
 use mctk_core::reexports::cosmic_text;
+use mctk_core::resources::Resources;
 use mctk_core::types::AssetParams;
 use mctk_core::types::PixelSize;
 use mctk_core::ui::UI;
-use pointer::{MouseEvent, ScrollDelta};
+use mctk_core::widgets::ResizeEdge;
 use raw_window_handle::{
     HasRawDisplayHandle, HasRawWindowHandle, RawDisplayHandle, RawWindowHandle,
 };
 use smithay_client_toolkit::reexports::calloop::channel::{Channel, Event, Sender};
 use smithay_client_toolkit::reexports::calloop::{self, EventLoop};
+use smithay_client_toolkit::shell::xdg::window::{DecorationMode, WindowConfigure};
 use std::any::Any;
 use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
-use crate::input::keyboard::{keysym_to_key, KeyboardEvent};
-use crate::input::touch::TouchEvent;
 use crate::WindowInfo;
 use crate::{
-    input::pointer, layer_shell::layer_surface, WindowEvent, WindowMessage, WindowOptions,
+    input::handle_window_event, insert_timer, layer_shell::layer_surface, WindowEvent,
+    WindowMessage, WindowOptions,
 };
 
+use super::decorations;
 use super::xdg_surface::XdgShellSctkWindow;
 
 pub struct XdgWindow {
+    title: String,
     width: u32,
     height: u32,
     scale_factor: f32,
     handle: Option<RawWaylandHandle>,
     window_tx: Sender<WindowMessage>,
-    fonts: cosmic_text::fontdb::Database,
-    assets: HashMap<String, AssetParams>,
-    svgs: HashMap<String, String>,
+    resources: Resources,
     xdg_window_tx: Option<Sender<XdgWindowMessage>>,
 }
 unsafe impl Send for XdgWindow {}
@@ -49,7 +52,87 @@ pub struct XdgWindowParams {
 }
 
 #[derive(Debug)]
-pub enum XdgWindowMessage {}
+pub enum XdgWindowMessage {
+    SetTitle {
+        title: String,
+    },
+    SetAppId {
+        app_id: String,
+    },
+    Maximize,
+    Unmaximize,
+    Fullscreen,
+    Unfullscreen,
+    Minimize,
+    SetMinSize {
+        size: Option<(u32, u32)>,
+    },
+    SetMaxSize {
+        size: Option<(u32, u32)>,
+    },
+    /// `None` lets the compositor pick the decoration mode
+    RequestDecorations {
+        mode: Option<DecorationMode>,
+    },
+    /// Start an interactive move, must follow a pointer button press or touch down
+    Move,
+    /// Start an interactive resize, must follow a pointer button press or touch down
+    Resize {
+        edge: ResizeEdge,
+    },
+}
+
+/// Current toplevel states of the window, sent to the root component as a message
+/// whenever the compositor changes them.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct XdgWindowState {
+    pub maximized: bool,
+    pub fullscreen: bool,
+    pub activated: bool,
+    pub resizing: bool,
+    pub tiled_left: bool,
+    pub tiled_right: bool,
+    pub tiled_top: bool,
+    pub tiled_bottom: bool,
+    /// The compositor draws the decorations, otherwise the client should draw them
+    pub server_decorations: bool,
+}
+
+impl XdgWindowState {
+    pub fn is_tiled(&self) -> bool {
+        self.tiled_left || self.tiled_right || self.tiled_top || self.tiled_bottom
+    }
+}
+
+impl From<&WindowConfigure> for XdgWindowState {
+    fn from(configure: &WindowConfigure) -> Self {
+        Self {
+            maximized: configure.is_maximized(),
+            fullscreen: configure.is_fullscreen(),
+            activated: configure.is_activated(),
+            resizing: configure.is_resizing(),
+            tiled_left: configure.is_tiled_left(),
+            tiled_right: configure.is_tiled_right(),
+            tiled_top: configure.is_tiled_top(),
+            tiled_bottom: configure.is_tiled_bottom(),
+            server_decorations: configure.decoration_mode == DecorationMode::Server,
+        }
+    }
+}
+
+/// The size the compositor configured the window to, if it differs from `current`. A missing width
+/// or height is left to the client, which keeps the current one.
+pub(crate) fn configured_size(
+    configure: &WindowConfigure,
+    current: (u32, u32),
+) -> Option<(u32, u32)> {
+    let (width, height) = configure.new_size;
+    let size = (
+        width.map_or(current.0, |width| width.get()),
+        height.map_or(current.1, |height| height.get()),
+    );
+    (size != current).then_some(size)
+}
 
 impl XdgWindow {
     pub fn open_blocking<A, B>(
@@ -75,185 +158,111 @@ impl XdgWindow {
         } = params;
 
         let (window_tx, window_rx) = calloop::channel::channel();
+        let title = window_info.title.clone();
 
-        let (app_window, event_loop) =
-            XdgShellSctkWindow::new(window_tx.clone(), window_opts, window_info, xdg_window_rx)
-                .expect("failed to create application");
+        // window controls need a channel, create one if the app didn't pass its own
+        let (xdg_window_tx, xdg_window_rx) = match (xdg_window_tx, xdg_window_rx) {
+            (xdg_window_tx, Some(xdg_window_rx)) => (xdg_window_tx, xdg_window_rx),
+            (_, None) => {
+                let (xdg_window_tx, xdg_window_rx) = calloop::channel::channel();
+                (Some(xdg_window_tx), xdg_window_rx)
+            }
+        };
+
+        let (app_window, event_loop) = XdgShellSctkWindow::new(
+            window_tx.clone(),
+            window_opts,
+            window_info,
+            Some(xdg_window_rx),
+        )
+        .expect("failed to create application");
 
         // let (app_window, event_loop) =
         //     SessionLockWindow::new(window_tx.clone(), window_opts)
         //         .expect("failed to create application");
 
+        // Client-side decorations, until the compositor says it draws them
+        let toplevel_state = Arc::new(RwLock::new(XdgWindowState {
+            server_decorations: app_window.decoration_manager,
+            ..Default::default()
+        }));
+        let decorate = decorations::decorate(
+            title.clone(),
+            toplevel_state.clone(),
+            window_tx.clone(),
+            xdg_window_tx.clone(),
+        );
+
         let mut ui: UI<XdgWindow, A, B> = UI::new(
             XdgWindow {
+                title,
                 width: app_window.width,
                 height: app_window.height,
                 handle: None,
                 scale_factor: app_window.scale_factor,
                 window_tx: window_tx.clone(),
-                fonts,
-                assets,
-                svgs,
+                resources: Resources::new(fonts, assets, svgs),
                 xdg_window_tx,
             },
             app_params,
         );
+        ui.set_decorations(decorate);
 
         // insert handle
         let handle = event_loop.handle();
+        let timer_handle = handle.clone();
+        let timer_tx = window_tx.clone();
         let _ = handle.insert_source(
             window_rx,
             move |ev: Event<WindowMessage>, &mut _, app_window| {
                 let _ = match ev {
-                    calloop::channel::Event::Msg(event) => {
-                        match event {
-                            WindowMessage::Configure {
-                                width,
-                                height,
-                                wayland_handle,
-                            } => {
-                                ui.configure(width, height, wayland_handle);
-                                ui.draw();
-                            }
-                            WindowMessage::Send { message } => {
-                                ui.update(message);
-                                ui.draw();
-                            }
-                            WindowMessage::Resize { width, height } => {
-                                app_window.resize(width, height);
-                                ui.resize(width, height);
-                                ui.draw();
-                            }
-                            WindowMessage::MainEventsCleared => {
-                                ui.draw();
-                            }
-                            WindowMessage::RedrawRequested => {
-                                ui.render();
-                            }
-                            WindowMessage::RequestNextFrame => {
-                                app_window.next_frame();
-                            }
-                            WindowMessage::CompositorFrame => {
-                                ui.handle_input(&Input::Timer);
+                    calloop::channel::Event::Msg(event) => match event {
+                        WindowMessage::Configure {
+                            width,
+                            height,
+                            wayland_handle,
+                        } => {
+                            ui.configure(width, height, wayland_handle);
+                            ui.draw();
+                        }
+                        WindowMessage::Send { message } => {
+                            if let Some(state) = message.downcast_ref::<XdgWindowState>() {
+                                *toplevel_state.write().unwrap() = *state;
+                                ui.decorations_changed();
                             }
-                            WindowMessage::WindowEvent { event: w_ev } => {
-                                // println!("window_event::{:?}", w_ev);
-                                match w_ev {
-                                    WindowEvent::CloseRequested => {
-                                        ui.handle_input(&Input::Exit);
-                                        app_window.close();
-                                    }
-                                    WindowEvent::Focused => {
-                                        ui.handle_input(&Input::Focus(true));
-                                    }
-                                    WindowEvent::Unfocused => {
-                                        ui.handle_input(&Input::Focus(false));
-                                    }
-                                    WindowEvent::Mouse(m_event) => match m_event {
-                                        MouseEvent::CursorEntered => {
-                                            ui.handle_input(&Input::MouseEnterWindow);
-                                        }
-                                        MouseEvent::CursorLeft => {
-                                            ui.handle_input(&Input::MouseLeaveWindow);
-                                        }
-                                        MouseEvent::CursorMoved {
-                                            position,
-                                            scale_factor,
-                                        } => {
-                                            ui.handle_input(&Input::Motion(Motion::Mouse {
-                                                x: position.x as f32 / scale_factor as f32,
-                                                y: position.y as f32 / scale_factor as f32,
-                                            }));
-                                        }
-                                        MouseEvent::ButtonPressed { button } => match button {
-                                            pointer::Button::Left => ui.handle_input(
-                                                &Input::Press(Button::Mouse(MouseButton::Left)),
-                                            ),
-                                            pointer::Button::Right => ui.handle_input(
-                                                &Input::Press(Button::Mouse(MouseButton::Right)),
-                                            ),
-                                            pointer::Button::Middle => ui.handle_input(
-                                                &Input::Press(Button::Mouse(MouseButton::Middle)),
-                                            ),
-                                        },
-                                        MouseEvent::ButtonReleased { button } => match button {
-                                            pointer::Button::Left => ui.handle_input(
-                                                &Input::Release(Button::Mouse(MouseButton::Left)),
-                                            ),
-                                            pointer::Button::Right => ui.handle_input(
-                                                &Input::Release(Button::Mouse(MouseButton::Right)),
-                                            ),
-                                            pointer::Button::Middle => ui.handle_input(
-                                                &Input::Release(Button::Mouse(MouseButton::Middle)),
-                                            ),
-                                        },
-                                        MouseEvent::WheelScrolled { delta } => {
-                                            let scroll = match delta {
-                                                ScrollDelta::Lines { x, y } => Motion::Scroll {
-                                                    x: x * -30.0,
-                                                    y: y * -30.0,
-                                                },
-                                                ScrollDelta::Pixels { x, y } => Motion::Scroll {
-                                                    x: -x as f32,
-                                                    y: -y as f32,
-                                                },
-                                            };
-                                            ui.handle_input(&Input::Motion(scroll));
-                                        }
-                                    },
-                                    WindowEvent::Keyboard(k_ev) => match k_ev {
-                                        KeyboardEvent::KeyPressed { key } => {
-                                            ui.handle_input(&Input::Press(Button::Keyboard(
-                                                keysym_to_key(key),
-                                            )));
-                                            ui.handle_input(&Input::Text(
-                                                keysym_to_key(key).to_string(),
-                                            ));
-                                        }
-                                        KeyboardEvent::KeyReleased { key } => {
-                                            ui.handle_input(&Input::Release(Button::Keyboard(
-                                                keysym_to_key(key),
-                                            )));
-                                        }
-                                    },
-                                    WindowEvent::Touch(t_ev) => match t_ev {
-                                        TouchEvent::Up {
-                                            position,
-                                            scale_factor,
-                                            ..
-                                        } => ui.handle_input(&Input::Touch(TouchAction::Up {
-                                            x: position.x / scale_factor,
-                                            y: position.y / scale_factor,
-                                        })),
-                                        TouchEvent::Down {
-                                            position,
-                                            scale_factor,
-                                            ..
-                                        } => ui.handle_input(&Input::Touch(TouchAction::Down {
-                                            x: position.x / scale_factor,
-                                            y: position.y / scale_factor,
-                                        })),
-                                        TouchEvent::Motion {
-                                            position,
-                                            scale_factor,
-                                            ..
-                                        } => ui.handle_input(&Input::Touch(TouchAction::Moved {
-                                            x: position.x / scale_factor,
-                                            y: position.y / scale_factor,
-                                        })),
-                                        TouchEvent::Cancel {
-                                            position,
-                                            scale_factor,
-                                            ..
-                                        } => ui.handle_input(&Input::Touch(TouchAction::Cancel {
-                                            x: position.x / scale_factor,
-                                            y: position.y / scale_factor,
-                                        })),
-                                    },
-                                }
+                            ui.update(message);
+                            ui.draw();
+                        }
+                        WindowMessage::Resize { width, height } => {
+                            app_window.resize(width, height);
+                            ui.resize(width, height);
+                            ui.draw();
+                        }
+                        WindowMessage::MainEventsCleared => {
+                            ui.draw();
+                        }
+                        WindowMessage::RedrawRequested => {
+                            ui.render();
+                        }
+                        WindowMessage::RequestNextFrame => {
+                            app_window.next_frame();
+                        }
+                        WindowMessage::ScheduleTimer { deadline } => {
+                            insert_timer(&timer_handle, deadline, timer_tx.clone());
+                        }
+                        WindowMessage::TimerExpired => {
+                            ui.handle_input(&Input::TimerExpired);
+                        }
+                        WindowMessage::CompositorFrame => {
+                            ui.handle_input(&Input::Timer);
+                        }
+                        WindowMessage::WindowEvent { event } => {
+                            handle_window_event(&mut ui, event);
+                            if let WindowEvent::CloseRequested = event {
+                                app_window.close();
                             }
                         }
-                    }
+                    },
                     calloop::channel::Event::Closed => {}
                 };
             },
@@ -265,6 +274,21 @@ impl XdgWindow {
     pub fn sender(&self) -> Option<Sender<XdgWindowMessage>> {
         self.xdg_window_tx.clone()
     }
+
+    /// Title the window was opened with
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub(crate) fn window_sender(&self) -> Sender<WindowMessage> {
+        self.window_tx.clone()
+    }
+
+    fn send_xdg_window_message(&self, message: XdgWindowMessage) {
+        if let Some(xdg_window_tx) = self.xdg_window_tx.as_ref() {
+            let _ = xdg_window_tx.send(message);
+        }
+    }
 }
 
 impl mctk_core::window::Window for XdgWindow {
@@ -292,16 +316,14 @@ impl mctk_core::window::Window for XdgWindow {
         let _ = self.window_tx.send(WindowMessage::RequestNextFrame);
     }
 
-    fn fonts(&self) -> cosmic_text::fontdb::Database {
-        self.fonts.clone()
-    }
-
-    fn assets(&self) -> HashMap<String, AssetParams> {
-        self.assets.clone()
+    fn schedule_timer(&self, deadline: Instant) {
+        let _ = self
+            .window_tx
+            .send(WindowMessage::ScheduleTimer { deadline });
     }
 
-    fn svgs(&self) -> HashMap<String, String> {
-        self.svgs.clone()
+    fn resources(&self) -> Resources {
+        self.resources.clone()
     }
 
     fn set_size(&mut self, width: u32, height: u32) {
@@ -315,6 +337,42 @@ impl mctk_core::window::Window for XdgWindow {
         });
     }
 
+    fn set_title(&self, title: String) {
+        self.send_xdg_window_message(XdgWindowMessage::SetTitle { title });
+    }
+
+    fn set_app_id(&self, app_id: String) {
+        self.send_xdg_window_message(XdgWindowMessage::SetAppId { app_id });
+    }
+
+    fn maximize(&self) {
+        self.send_xdg_window_message(XdgWindowMessage::Maximize);
+    }
+
+    fn unmaximize(&self) {
+        self.send_xdg_window_message(XdgWindowMessage::Unmaximize);
+    }
+
+    fn fullscreen(&self) {
+        self.send_xdg_window_message(XdgWindowMessage::Fullscreen);
+    }
+
+    fn unfullscreen(&self) {
+        self.send_xdg_window_message(XdgWindowMessage::Unfullscreen);
+    }
+
+    fn minimize(&self) {
+        self.send_xdg_window_message(XdgWindowMessage::Minimize);
+    }
+
+    fn set_min_size(&self, size: Option<(u32, u32)>) {
+        self.send_xdg_window_message(XdgWindowMessage::SetMinSize { size });
+    }
+
+    fn set_max_size(&self, size: Option<(u32, u32)>) {
+        self.send_xdg_window_message(XdgWindowMessage::SetMaxSize { size });
+    }
+
     fn set_wayland_handle(&mut self, wayland_handle: RawWaylandHandle) {
         self.handle = Some(wayland_handle);
     }
@@ -5,6 +5,7 @@ use mctk_core::component::{Component, RootComponent};
 use mctk_core::input::{Button, Input, Motion, MouseButton};
 use mctk_core::reexports::cosmic_text;
 use mctk_core::reexports::smithay_client_toolkit::reexports::calloop::channel::Sender;
+use mctk_core::resources::Resources;
 use mctk_core::types::AssetParams;
 use mctk_core::types::PixelSize;
 use mctk_core::ui::UI;
@@ -20,9 +21,7 @@ use winit::{
 
 pub struct Window {
     winit_window: winit::window::Window,
-    fonts: cosmic_text::fontdb::Database,
-    assets: HashMap<String, AssetParams>,
-    svgs: HashMap<String, String>,
+    resources: Resources,
 }
 unsafe impl Send for Window {}
 unsafe impl Sync for Window {}
@@ -49,9 +48,7 @@ impl Window {
         let mut ui: UI<Window, A, B> = UI::new(
             Window {
                 winit_window: window,
-                fonts,
-                assets,
-                svgs,
+                resources: Resources::new(fonts, assets, svgs),
             },
             app_params,
         );
@@ -146,16 +143,8 @@ impl mctk_core::window::Window for Window {
 
     fn exit(&mut self) {}
 
-    fn fonts(&self) -> cosmic_text::fontdb::Database {
-        self.fonts.clone()
-    }
-
-    fn assets(&self) -> HashMap<String, AssetParams> {
-        self.assets.clone()
-    }
-
-    fn svgs(&self) -> HashMap<String, String> {
-        self.svgs.clone()
+    fn resources(&self) -> Resources {
+        self.resources.clone()
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
@@ -0,0 +1,951 @@
+//! Tweens, easing curves and springs for animating values over time.
+//!
+//! Components keep an [`Animated`] value in their state and register for
+//! [`Register::AnimationFrame`][crate::event::Register::AnimationFrame] while it
+//! [`is_animating`][Animated::is_animating]. Nodes that receive animation frames are marked
+//! dirty on every frame, so the value is sampled again in `view` and `render`, without an
+//! [`on_tick`][crate::Component#method.on_tick] handler:
+//!
+//! ```ignore
+//! fn register(&mut self) -> Vec<event::Register> {
+//!     if self.state_ref().offset.is_animating() {
+//!         vec![event::Register::AnimationFrame]
+//!     } else {
+//!         vec![]
+//!     }
+//! }
+//!
+//! fn render_hash(&self, hasher: &mut ComponentHasher) {
+//!     self.state_ref().offset.hash(hasher);
+//! }
+//!
+//! fn on_click(&mut self, _event: &mut event::Event<event::Click>) {
+//!     self.state_mut()
+//!         .offset
+//!         .animate_to(Point::new(100., 0.), Transition::Spring(Spring::default()));
+//! }
+//! ```
+//!
+//! Nodes animate changes of their layout, and being added or removed by `view`, with the
+//! `animate`, `enter` and `exit` fields of [`lay!`][crate::lay]:
+//!
+//! ```ignore
+//! node!(
+//!     Div::new(),
+//!     lay![
+//!         size: [100, 40],
+//!         animate: Transition::Spring(Spring::default()),
+//!         enter: NodeTransition::fade(Duration::from_millis(200)),
+//!         exit: NodeTransition::slide(Duration::from_millis(200), -40.0, 0.0).opacity(0.0),
+//!     ]
+//! )
+//! ```
+
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
+
+use crate::types::*;
+
+/// A value that can be animated.
+pub trait Animatable: Copy + fmt::Debug {
+    /// The value at `t` between `self` (`0.0`) and `to` (`1.0`). `t` may go past either end,
+    /// to overshoot.
+    fn interpolate(&self, to: &Self, t: f32) -> Self;
+
+    /// How far apart the values are, used to carry the velocity of a spring over to a new target.
+    fn distance(&self, to: &Self) -> f32;
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+impl Animatable for f32 {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        lerp(*self, *to, t)
+    }
+
+    fn distance(&self, to: &Self) -> f32 {
+        (to - self).abs()
+    }
+}
+
+impl Animatable for Point {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Point::new(lerp(self.x, to.x, t), lerp(self.y, to.y, t))
+    }
+
+    fn distance(&self, to: &Self) -> f32 {
+        self.dist(*to)
+    }
+}
+
+impl Animatable for Scale {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Scale::new(
+            lerp(self.width, to.width, t),
+            lerp(self.height, to.height, t),
+        )
+    }
+
+    fn distance(&self, to: &Self) -> f32 {
+        Point::new(self.width, self.height).dist(Point::new(to.width, to.height))
+    }
+}
+
+impl Animatable for Color {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Color::rgba(
+            lerp(self.r, to.r, t),
+            lerp(self.g, to.g, t),
+            lerp(self.b, to.b, t),
+            lerp(self.a, to.a, t),
+        )
+    }
+
+    fn distance(&self, to: &Self) -> f32 {
+        ((to.r - self.r).powi(2)
+            + (to.g - self.g).powi(2)
+            + (to.b - self.b).powi(2)
+            + (to.a - self.a).powi(2))
+        .sqrt()
+    }
+}
+
+impl Animatable for AABB {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        AABB {
+            pos: Pos {
+                x: lerp(self.pos.x, to.pos.x, t),
+                y: lerp(self.pos.y, to.pos.y, t),
+                z: lerp(self.pos.z, to.pos.z, t),
+            },
+            bottom_right: self.bottom_right.interpolate(&to.bottom_right, t),
+        }
+    }
+
+    fn distance(&self, to: &Self) -> f32 {
+        Point::new(self.pos.x, self.pos.y).dist(Point::new(to.pos.x, to.pos.y))
+            + self.bottom_right.dist(to.bottom_right)
+    }
+}
+
+impl Animatable for Transform {
+    fn interpolate(&self, to: &Self, t: f32) -> Self {
+        Transform {
+            translate: self.translate.interpolate(&to.translate, t),
+            scale: self.scale.interpolate(&to.scale, t),
+            rotate: lerp(self.rotate, to.rotate, t),
+            origin: self.origin.interpolate(&to.origin, t),
+        }
+    }
+
+    fn distance(&self, to: &Self) -> f32 {
+        self.translate.dist(to.translate)
+            + self.scale.dist(to.scale)
+            + (to.rotate - self.rotate).abs()
+            + self.origin.dist(to.origin)
+    }
+}
+
+/// Easing curves for [`Animation::tween`]. The named curves follow their CSS and easings.net namesakes.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    #[default]
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    BackOut,
+    BounceOut,
+    /// A CSS `cubic-bezier(x1, y1, x2, y2)` curve
+    CubicBezier(f32, f32, f32, f32),
+}
+
+impl Easing {
+    /// Maps the linear progress `t` (`0.0..=1.0`) onto the curve.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Self::Linear => t,
+            Self::Ease => cubic_bezier(0.25, 0.1, 0.25, 1.0, t),
+            Self::EaseIn => cubic_bezier(0.42, 0.0, 1.0, 1.0, t),
+            Self::EaseOut => cubic_bezier(0.0, 0.0, 0.58, 1.0, t),
+            Self::EaseInOut => cubic_bezier(0.42, 0.0, 0.58, 1.0, t),
+            Self::QuadIn => t * t,
+            Self::QuadOut => 1.0 - (1.0 - t).powi(2),
+            Self::QuadInOut if t < 0.5 => 2.0 * t * t,
+            Self::QuadInOut => 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0,
+            Self::CubicIn => t.powi(3),
+            Self::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Self::CubicInOut if t < 0.5 => 4.0 * t.powi(3),
+            Self::CubicInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+            Self::BackOut => {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.0;
+                1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2)
+            }
+            Self::BounceOut => bounce_out(t),
+            Self::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+        }
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    let n1 = 7.5625;
+    let d1 = 2.75;
+    if t < 1.0 / d1 {
+        n1 * t * t
+    } else if t < 2.0 / d1 {
+        let t = t - 1.5 / d1;
+        n1 * t * t + 0.75
+    } else if t < 2.5 / d1 {
+        let t = t - 2.25 / d1;
+        n1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / d1;
+        n1 * t * t + 0.984375
+    }
+}
+
+/// Solves the curve for `x` and returns its `y`, like browsers do for CSS timing functions.
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    // Polynomial coefficients, with the end points fixed at (0, 0) and (1, 1)
+    let cx = 3.0 * x1;
+    let bx = 3.0 * (x2 - x1) - cx;
+    let ax = 1.0 - cx - bx;
+    let cy = 3.0 * y1;
+    let by = 3.0 * (y2 - y1) - cy;
+    let ay = 1.0 - cy - by;
+
+    let sample_x = |t: f32| ((ax * t + bx) * t + cx) * t;
+    let sample_y = |t: f32| ((ay * t + by) * t + cy) * t;
+    let sample_dx = |t: f32| (3.0 * ax * t + 2.0 * bx) * t + cx;
+
+    // Newton's method converges in a few steps for most curves
+    let mut t = x;
+    for _ in 0..8 {
+        let error = sample_x(t) - x;
+        if error.abs() < 1e-6 {
+            return sample_y(t);
+        }
+        let dx = sample_dx(t);
+        if dx.abs() < 1e-6 {
+            break;
+        }
+        t -= error / dx;
+    }
+
+    // Fall back to bisection
+    let (mut low, mut high) = (0.0, 1.0);
+    t = x;
+    while low < high {
+        let sampled = sample_x(t);
+        if (sampled - x).abs() < 1e-6 {
+            break;
+        }
+        if x > sampled {
+            low = t;
+        } else {
+            high = t;
+        }
+        t = (high - low) / 2.0 + low;
+        if high - low < 1e-6 {
+            break;
+        }
+    }
+    sample_y(t)
+}
+
+/// Physical parameters of a damped spring, for [`Animation::spring`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+    pub mass: f32,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Self::new(170.0, 26.0)
+    }
+}
+
+impl Spring {
+    /// A spring with a mass of `1.0`.
+    pub fn new(stiffness: f32, damping: f32) -> Self {
+        Self {
+            stiffness,
+            damping,
+            mass: 1.0,
+        }
+    }
+
+    pub fn gentle() -> Self {
+        Self::new(120.0, 14.0)
+    }
+
+    pub fn wobbly() -> Self {
+        Self::new(180.0, 12.0)
+    }
+
+    pub fn stiff() -> Self {
+        Self::new(210.0, 20.0)
+    }
+
+    pub fn mass(mut self, mass: f32) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Progress from `0.0` to `1.0` and its velocity after `t` seconds, starting at `velocity`.
+    fn solve(&self, velocity: f32, t: f32) -> (f32, f32) {
+        let mass = self.mass.max(f32::EPSILON);
+        let omega = (self.stiffness / mass).sqrt();
+        let zeta = self.damping / (2.0 * (self.stiffness * mass).sqrt());
+        // displacement from the rest position
+        let x0 = -1.0;
+
+        let (x, v) = if (zeta - 1.0).abs() < 1e-4 {
+            let b = velocity + omega * x0;
+            let e = (-omega * t).exp();
+            ((x0 + b * t) * e, (b - omega * (x0 + b * t)) * e)
+        } else if zeta < 1.0 {
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            let a = x0;
+            let b = (velocity + zeta * omega * a) / omega_d;
+            let e = (-zeta * omega * t).exp();
+            let (sin, cos) = (omega_d * t).sin_cos();
+            (
+                e * (a * cos + b * sin),
+                e * ((omega_d * b - zeta * omega * a) * cos
+                    - (zeta * omega * b + omega_d * a) * sin),
+            )
+        } else {
+            let s = (zeta * zeta - 1.0).sqrt();
+            let r1 = -omega * (zeta - s);
+            let r2 = -omega * (zeta + s);
+            let c2 = (velocity - r1 * x0) / (r2 - r1);
+            let c1 = x0 - c2;
+            (
+                c1 * (r1 * t).exp() + c2 * (r2 * t).exp(),
+                r1 * c1 * (r1 * t).exp() + r2 * c2 * (r2 * t).exp(),
+            )
+        };
+        (1.0 + x, v)
+    }
+
+    /// How long until the spring comes to rest, capped at 10 seconds.
+    fn settle_time(&self, velocity: f32) -> Duration {
+        const STEP: f32 = 1.0 / 120.0;
+        const REST: f32 = 1e-3;
+        let mut t = 0.0;
+        while t < 10.0 {
+            let (x, v) = self.solve(velocity, t);
+            if (1.0 - x).abs() < REST && v.abs() < REST {
+                break;
+            }
+            t += STEP;
+        }
+        Duration::from_secs_f32(t)
+    }
+}
+
+/// How [`Animated#animate_to`][Animated#method.animate_to] gets to its new target.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    Tween { duration: Duration, easing: Easing },
+    Spring(Spring),
+}
+
+impl Transition {
+    pub fn tween(duration: Duration) -> Self {
+        Self::Tween {
+            duration,
+            easing: Easing::default(),
+        }
+    }
+}
+
+/// An animation of a value, sampled by the time elapsed since it started.
+#[derive(Debug, Clone)]
+pub enum Animation<T: Animatable> {
+    Tween {
+        from: T,
+        to: T,
+        duration: Duration,
+        easing: Easing,
+    },
+    Spring {
+        from: T,
+        to: T,
+        spring: Spring,
+        /// Initial velocity, in progress (`0.0..=1.0`) per second
+        velocity: f32,
+        /// Cached, as it is solved numerically
+        settle_time: Duration,
+    },
+    Sequence(Vec<Animation<T>>),
+    Delay(Duration, Box<Animation<T>>),
+}
+
+impl<T: Animatable> Animation<T> {
+    pub fn tween(from: T, to: T, duration: Duration) -> Self {
+        Self::Tween {
+            from,
+            to,
+            duration,
+            easing: Easing::default(),
+        }
+    }
+
+    pub fn spring(from: T, to: T, spring: Spring) -> Self {
+        Self::spring_with_velocity(from, to, spring, 0.0)
+    }
+
+    fn spring_with_velocity(from: T, to: T, spring: Spring, velocity: f32) -> Self {
+        Self::Spring {
+            from,
+            to,
+            spring,
+            velocity,
+            settle_time: spring.settle_time(velocity),
+        }
+    }
+
+    /// Plays the animations one after the other.
+    pub fn sequence(animations: Vec<Self>) -> Self {
+        Self::Sequence(animations)
+    }
+
+    /// Delays each animation by `step` more than the previous one, to play them staggered
+    /// on several values.
+    pub fn stagger(animations: Vec<Self>, step: Duration) -> Vec<Self> {
+        animations
+            .into_iter()
+            .enumerate()
+            .map(|(i, animation)| animation.delay(step * i as u32))
+            .collect()
+    }
+
+    /// Sets the easing of a tween, other animations are left as is.
+    pub fn easing(self, easing: Easing) -> Self {
+        match self {
+            Self::Tween {
+                from, to, duration, ..
+            } => Self::Tween {
+                from,
+                to,
+                duration,
+                easing,
+            },
+            Self::Delay(delay, animation) => Self::Delay(delay, Box::new(animation.easing(easing))),
+            animation => animation,
+        }
+    }
+
+    /// Starts the animation after `delay`, holding its initial value until then.
+    pub fn delay(self, delay: Duration) -> Self {
+        if delay.is_zero() {
+            self
+        } else {
+            Self::Delay(delay, Box::new(self))
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            Self::Tween { duration, .. } => *duration,
+            Self::Spring { settle_time, .. } => *settle_time,
+            Self::Sequence(animations) => animations.iter().map(|a| a.duration()).sum(),
+            Self::Delay(delay, animation) => *delay + animation.duration(),
+        }
+    }
+
+    /// The value at the start of the animation.
+    pub fn from(&self) -> T {
+        match self {
+            Self::Tween { from, .. } | Self::Spring { from, .. } => *from,
+            Self::Sequence(animations) => animations.first().expect("Empty sequence").from(),
+            Self::Delay(_, animation) => animation.from(),
+        }
+    }
+
+    /// The value the animation ends at.
+    pub fn to(&self) -> T {
+        match self {
+            Self::Tween { to, .. } | Self::Spring { to, .. } => *to,
+            Self::Sequence(animations) => animations.last().expect("Empty sequence").to(),
+            Self::Delay(_, animation) => animation.to(),
+        }
+    }
+
+    /// The value after `elapsed`.
+    pub fn sample(&self, elapsed: Duration) -> T {
+        match self {
+            Self::Tween {
+                from,
+                to,
+                duration,
+                easing,
+            } => {
+                let t = if duration.is_zero() {
+                    1.0
+                } else {
+                    elapsed.as_secs_f32() / duration.as_secs_f32()
+                };
+                from.interpolate(to, easing.apply(t))
+            }
+            Self::Spring {
+                from,
+                to,
+                spring,
+                velocity,
+                settle_time,
+            } => {
+                if elapsed >= *settle_time {
+                    *to
+                } else {
+                    from.interpolate(to, spring.solve(*velocity, elapsed.as_secs_f32()).0)
+                }
+            }
+            Self::Sequence(animations) => {
+                let mut elapsed = elapsed;
+                for animation in animations.iter() {
+                    let duration = animation.duration();
+                    if elapsed < duration {
+                        return animation.sample(elapsed);
+                    }
+                    elapsed -= duration;
+                }
+                self.to()
+            }
+            Self::Delay(delay, animation) => animation.sample(elapsed.saturating_sub(*delay)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Running<T: Animatable> {
+    animation: Animation<T>,
+    started_at: Instant,
+}
+
+/// A value that animates towards its target. Keep it in the state of a Component.
+///
+/// Hashing an `Animated` value changes on every frame while it is animating, so it can be
+/// hashed in [`render_hash`][crate::Component#method.render_hash] or
+/// [`props_hash`][crate::Component#method.props_hash].
+#[derive(Debug, Clone)]
+pub struct Animated<T: Animatable> {
+    /// The target, or the current value when not animating
+    value: T,
+    running: Option<Running<T>>,
+    /// Changes every time the value or its animation is replaced
+    generation: u64,
+}
+
+impl<T: Animatable + Default> Default for Animated<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Animatable> Animated<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            running: None,
+            generation: 0,
+        }
+    }
+
+    /// The current value.
+    pub fn get(&self) -> T {
+        self.value_at(Instant::now())
+    }
+
+    pub fn value_at(&self, now: Instant) -> T {
+        match self.running.as_ref() {
+            Some(running) if now < running.started_at + running.animation.duration() => running
+                .animation
+                .sample(now.saturating_duration_since(running.started_at)),
+            _ => self.value,
+        }
+    }
+
+    /// The value the animation ends at.
+    pub fn target(&self) -> T {
+        self.value
+    }
+
+    /// Jumps to `value`, stopping the animation.
+    pub fn set(&mut self, value: T) {
+        self.value = value;
+        self.running = None;
+        self.generation += 1;
+    }
+
+    /// Animates from the current value to `to`. A spring keeps the velocity of the
+    /// animation it interrupts.
+    pub fn animate_to(&mut self, to: T, transition: Transition) {
+        let now = Instant::now();
+        let from = self.value_at(now);
+        let animation = match transition {
+            Transition::Tween { duration, easing } => {
+                Animation::tween(from, to, duration).easing(easing)
+            }
+            Transition::Spring(spring) => {
+                Animation::spring_with_velocity(from, to, spring, self.velocity_towards(to, now))
+            }
+        };
+        self.play_at(animation, now);
+    }
+
+    /// Plays `animation` from its start, the value ends at the end of the animation.
+    pub fn play(&mut self, animation: Animation<T>) {
+        self.play_at(animation, Instant::now());
+    }
+
+    fn play_at(&mut self, animation: Animation<T>, now: Instant) {
+        self.value = animation.to();
+        self.running = Some(Running {
+            animation,
+            started_at: now,
+        });
+        self.generation += 1;
+    }
+
+    /// Stops at the current value.
+    pub fn stop(&mut self) {
+        let value = self.get();
+        self.set(value);
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.running
+            .as_ref()
+            .map(|r| r.started_at.elapsed() < r.animation.duration())
+            .unwrap_or(false)
+    }
+
+    /// Current velocity towards `to`, in progress per second of an animation to `to`.
+    fn velocity_towards(&self, to: T, now: Instant) -> f32 {
+        const H: Duration = Duration::from_millis(1);
+        if !self.is_animating() {
+            return 0.0;
+        }
+        let current = self.value_at(now);
+        let distance = current.distance(&to);
+        if distance < f32::EPSILON {
+            return 0.0;
+        }
+        let next = self.value_at(now + H);
+        let speed = (distance - next.distance(&to)) / H.as_secs_f32();
+        speed / distance
+    }
+}
+
+impl<T: Animatable> Hash for Animated<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.generation.hash(state);
+        if let Some(running) = self.running.as_ref() {
+            let elapsed = running.started_at.elapsed();
+            if elapsed < running.animation.duration() {
+                elapsed.hash(state);
+            }
+        }
+    }
+}
+
+/// How a node animates when `view` adds it to the tree or removes it, see
+/// [`Layout::enter`][crate::layout::Layout] and [`Layout::exit`][crate::layout::Layout].
+/// The node animates between its layout and this opacity and offset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NodeTransition {
+    pub opacity: f32,
+    /// In logical pixels
+    pub offset: Point,
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl NodeTransition {
+    pub fn fade(duration: Duration) -> Self {
+        Self {
+            opacity: 0.0,
+            offset: Point::default(),
+            duration,
+            easing: Easing::default(),
+        }
+    }
+
+    pub fn slide(duration: Duration, x: f32, y: f32) -> Self {
+        Self {
+            opacity: 1.0,
+            offset: Point::new(x, y),
+            duration,
+            easing: Easing::default(),
+        }
+    }
+
+    pub fn opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity;
+        self
+    }
+
+    pub fn offset(mut self, x: f32, y: f32) -> Self {
+        self.offset = Point::new(x, y);
+        self
+    }
+
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    fn tween(&self) -> Transition {
+        Transition::Tween {
+            duration: self.duration,
+            easing: self.easing,
+        }
+    }
+}
+
+/// The layout animations of a Node, carried over from one view to the next.
+#[derive(Debug, Default)]
+pub(crate) struct LayoutAnimation {
+    /// Position and size relative to the parent, when [`Layout::animate`][crate::layout::Layout] is set
+    aabb: Option<Animated<AABB>>,
+    /// `0.0` when hidden by the transition, `1.0` when shown
+    presence: Option<(NodeTransition, Animated<f32>)>,
+    exiting: bool,
+    /// Where the node was laid out last
+    last: AABB,
+    /// Where an exiting node stays
+    pinned: Option<AABB>,
+}
+
+impl LayoutAnimation {
+    pub(crate) fn enter(&mut self, transition: NodeTransition) {
+        let mut progress = Animated::new(0.0);
+        progress.animate_to(1.0, transition.tween());
+        self.presence = Some((transition, progress));
+        self.exiting = false;
+    }
+
+    pub(crate) fn exit(&mut self, transition: NodeTransition) {
+        let mut progress = Animated::new(self.progress());
+        progress.animate_to(0.0, transition.tween());
+        self.presence = Some((transition, progress));
+        self.exiting = true;
+        self.pinned = Some(self.last);
+    }
+
+    /// Added back to the tree while exiting
+    pub(crate) fn revive(&mut self) {
+        if let Some((transition, progress)) = self.presence.as_mut() {
+            progress.animate_to(1.0, transition.tween());
+        }
+        self.exiting = false;
+        self.pinned = None;
+    }
+
+    pub(crate) fn is_exiting(&self) -> bool {
+        self.exiting
+    }
+
+    pub(crate) fn has_exited(&self) -> bool {
+        self.exiting && !self.is_running()
+    }
+
+    pub(crate) fn is_running(&self) -> bool {
+        self.aabb
+            .as_ref()
+            .map(|a| a.is_animating())
+            .unwrap_or(false)
+            || self
+                .presence
+                .as_ref()
+                .map(|(_, p)| p.is_animating())
+                .unwrap_or(false)
+    }
+
+    fn progress(&self) -> f32 {
+        self.presence.as_ref().map(|(_, p)| p.get()).unwrap_or(1.0)
+    }
+
+    pub(crate) fn opacity(&self) -> f32 {
+        match self.presence.as_ref() {
+            Some((transition, progress)) => transition.opacity.interpolate(&1.0, progress.get()),
+            None => 1.0,
+        }
+    }
+
+    /// The AABB to lay the node out at, relative to its parent, given the one the layout resolved.
+    pub(crate) fn apply(
+        &mut self,
+        animate: Option<Transition>,
+        target: AABB,
+        scale_factor: f32,
+    ) -> AABB {
+        let target = self.pinned.unwrap_or(target);
+        let mut aabb = match (animate, self.aabb.as_mut()) {
+            (Some(transition), Some(animated)) => {
+                if animated.target() != target {
+                    animated.animate_to(target, transition);
+                }
+                animated.get()
+            }
+            (Some(_), None) => {
+                self.aabb = Some(Animated::new(target));
+                target
+            }
+            (None, _) => {
+                self.aabb = None;
+                target
+            }
+        };
+        self.last = target;
+
+        if let Some((transition, progress)) = self.presence.as_ref() {
+            let hidden = 1.0 - progress.get();
+            let offset = Point::new(
+                transition.offset.x * hidden * scale_factor,
+                transition.offset.y * hidden * scale_factor,
+            );
+            aabb.pos.x += offset.x;
+            aabb.pos.y += offset.y;
+            aabb.bottom_right += offset;
+        }
+        aabb
+    }
+}
+
+impl Hash for LayoutAnimation {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.aabb.hash(state);
+        if let Some((_, progress)) = self.presence.as_ref() {
+            progress.hash(state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_easing_end_points() {
+        for easing in [
+            Easing::Linear,
+            Easing::Ease,
+            Easing::EaseInOut,
+            Easing::CubicOut,
+            Easing::BackOut,
+            Easing::BounceOut,
+            Easing::CubicBezier(0.3, 1.5, 0.7, -0.5),
+        ] {
+            assert!(easing.apply(0.0).abs() < 1e-3, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-3, "{:?}", easing);
+        }
+        assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-3);
+        assert!(Easing::EaseIn.apply(0.25) < 0.25);
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+    }
+
+    #[test]
+    fn test_tween_sequence() {
+        let ms = Duration::from_millis;
+        let animation = Animation::sequence(vec![
+            Animation::tween(0.0, 10.0, ms(100)).easing(Easing::Linear),
+            Animation::tween(10.0, 0.0, ms(100)).easing(Easing::Linear),
+        ])
+        .delay(ms(50));
+
+        assert_eq!(animation.duration(), ms(250));
+        assert_eq!(animation.sample(ms(0)), 0.0);
+        assert!((animation.sample(ms(100)) - 5.0).abs() < 1e-3);
+        assert!((animation.sample(ms(200)) - 5.0).abs() < 1e-3);
+        assert_eq!(animation.sample(ms(300)), 0.0);
+
+        let staggered = Animation::stagger(
+            vec![
+                Animation::tween(0.0, 1.0, ms(100)),
+                Animation::tween(0.0, 1.0, ms(100)),
+            ],
+            ms(30),
+        );
+        assert_eq!(staggered[1].duration(), ms(130));
+    }
+
+    #[test]
+    fn test_spring_settles() {
+        for spring in [
+            Spring::default(),
+            Spring::wobbly(),
+            Spring::new(100.0, 40.0),
+        ] {
+            let animation = Animation::spring(Point::new(0., 0.), Point::new(100., 50.), spring);
+            let duration = animation.duration();
+            assert!(duration > Duration::ZERO && duration < Duration::from_secs(10));
+            assert_eq!(animation.sample(Duration::ZERO), Point::new(0., 0.));
+            assert_eq!(animation.sample(duration), Point::new(100., 50.));
+        }
+
+        // under-damped springs overshoot
+        let animation = Animation::spring(0.0, 1.0, Spring::wobbly());
+        let max = (0..100)
+            .map(|i| animation.sample(Duration::from_millis(i * 10)))
+            .fold(0.0, f32::max);
+        assert!(max > 1.0);
+    }
+
+    #[test]
+    fn test_layout_animation() {
+        let aabb = |x: f32| AABB::new(Pos::new(x, 0.0, 0.0), Scale::new(10.0, 10.0));
+        let transition = Some(Transition::tween(Duration::from_secs(60)));
+        let mut animation = LayoutAnimation::default();
+
+        assert_eq!(animation.apply(transition, aabb(0.0), 1.0), aabb(0.0));
+        // moved by the layout
+        assert!(animation.apply(transition, aabb(100.0), 1.0).pos.x < 1.0);
+        assert!(animation.is_running());
+
+        let mut animation = LayoutAnimation::default();
+        animation.apply(None, aabb(0.0), 1.0);
+        animation.exit(NodeTransition::slide(Duration::from_secs(60), 0.0, 20.0).opacity(0.0));
+        assert!(animation.is_exiting() && !animation.has_exited());
+        // stays where it was while exiting
+        let exiting = animation.apply(None, aabb(50.0), 2.0);
+        assert_eq!(exiting.pos.x, 0.0);
+        assert!(exiting.pos.y < 1.0);
+        assert!(animation.opacity() > 0.99);
+
+        animation.revive();
+        assert!(!animation.is_exiting());
+    }
+
+    #[test]
+    fn test_animated() {
+        let mut value = Animated::new(Color::BLACK);
+        assert!(!value.is_animating());
+
+        value.animate_to(Color::WHITE, Transition::tween(Duration::from_secs(60)));
+        assert!(value.is_animating());
+        assert_eq!(value.target(), Color::WHITE);
+        assert_ne!(value.get(), Color::WHITE);
+
+        value.set(Color::RED);
+        assert!(!value.is_animating());
+        assert_eq!(value.get(), Color::RED);
+    }
+}
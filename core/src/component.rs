@@ -190,6 +190,13 @@ pub trait Component: fmt::Debug {
         None
     }
 
+    /// Return a `Some` value to clip the Component and its children to a shape. `aabb` is the [`AABB`] of the current Node.
+    ///
+    /// Clipped Nodes are drawn onto an offscreen layer first, and what falls outside of the shape is erased. Events are not clipped.
+    fn clip(&self, _aabb: AABB) -> Option<Clip> {
+        None
+    }
+
     /// Should only be overridden by scrollable containers. Used to limit the bounds of the scrollable area.
     /// Should return an [`AABB`] that is inside the bounds of the input `aabb` which belongs to the current Node. `inner_scale` is the size of its child Nodes.
     ///
@@ -244,9 +251,12 @@ pub trait Component: fmt::Debug {
     fn on_focus(&mut self, _event: &mut Event<event::Focus>) {}
     /// Handle blue events. This event occurs when this component loses its focus, either by another component gaining focus, or [`Event#blur`][crate::Event#method.blur] being called on an event belonging to this component.
     fn on_blur(&mut self, _event: &mut Event<event::Blur>) {}
-    /// Handle tick events, which occur regularly on a short interval
-    /// (window backend dependent). This can be used to create animated effects.
+    /// Handle tick events, which occur on every frame while the [`Component#register`][crate::Component#method.register] method returns [`Register::AnimationFrame`][crate::event::Register]. This can be used to create animated effects.
     fn on_tick(&mut self, _event: &mut Event<event::Tick>) {}
+    /// Handle timer events. These are sent when a timer returned from the [`Component#register`][crate::Component#method.register] method as [`Register::Timer`][crate::event::Register] expires.
+    fn on_timer(&mut self, _event: &mut Event<event::Timer>) {}
+    /// Handle image load events. These are sent when an image finishes decoding in the background, while the [`Component#register`][crate::Component#method.register] method returns [`Register::ImageLoad`][crate::event::Register].
+    fn on_image_load(&mut self, _event: &mut Event<event::ImageLoad>) {}
     /// Handle key down events. These events will only be sent if this component is focused or the [`Component#register`][crate::Component#method.register] method returns [`Register::KeyDown`][crate::event::Register].
     fn on_key_down(&mut self, _event: &mut Event<event::KeyDown>) {}
     /// Handle key up events. These events will only be sent if this component is focused or the [`Component#register`][crate::Component#method.register] method returns [`Register::KeyUp`][crate::event::Register].
@@ -1,7 +1,7 @@
 //! Types that relate to event handling.
 
 use std::collections::HashSet;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use super::input::{Key, MouseButton};
 use super::types::*;
@@ -82,10 +82,51 @@ impl EventInput for Focus {}
 pub struct Blur;
 impl EventInput for Blur {}
 
-/// [`EventInput`] type for tick events.
+/// [`EventInput`] type for tick events, sent on every frame to the Components that registered [`Register::AnimationFrame`].
 #[derive(Debug)]
 pub struct Tick;
-impl EventInput for Tick {}
+impl EventInput for Tick {
+    fn matching_registrations(&self, registrations: &[crate::node::Registration]) -> Vec<u64> {
+        registrations
+            .iter()
+            .filter_map(|(r, node_id)| match r {
+                Register::AnimationFrame => Some(*node_id),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// [`EventInput`] type for timer events, sent when a timer registered with [`Register::Timer`] expires.
+#[derive(Debug)]
+pub struct Timer {
+    /// The name the timer was registered with.
+    pub name: &'static str,
+}
+impl EventInput for Timer {}
+
+/// [`EventInput`] type for image load events, sent to the Components that registered [`Register::ImageLoad`]
+/// when an image finishes decoding in the background.
+#[derive(Debug, Clone)]
+pub struct ImageLoad {
+    /// The name the image was loaded as.
+    pub name: String,
+    /// The error, if it could not be loaded.
+    pub result: Result<(), String>,
+    /// How long each frame is shown for, when it was loaded as an animation.
+    pub frame_delays: Vec<Duration>,
+}
+impl EventInput for ImageLoad {
+    fn matching_registrations(&self, registrations: &[crate::node::Registration]) -> Vec<u64> {
+        registrations
+            .iter()
+            .filter_map(|(r, node_id)| match r {
+                Register::ImageLoad => Some(*node_id),
+                _ => None,
+            })
+            .collect()
+    }
+}
 
 /// [`EventInput`] type for mouse motion events.
 #[derive(Debug)]
@@ -332,7 +373,40 @@ pub enum Register {
     KeyDown,
     KeyUp,
     KeyPress,
-    // Maybe TODO: Include Tick?
+    /// Receive a [`Tick`] on every frame, for as long as this is registered. The Component is redrawn on every frame,
+    /// so that its [`Animated`][crate::animation::Animated] values are sampled again.
+    AnimationFrame,
+    /// Receive a [`Timer`] event once `interval` has elapsed, and then every `interval` if `repeat`.
+    ///
+    /// The timer starts when it is first registered and is cancelled once the Component stops registering
+    /// it, or is removed from the tree. Timers are identified by their `name` within a Component.
+    Timer {
+        name: &'static str,
+        interval: Duration,
+        repeat: bool,
+    },
+    /// Receive an [`ImageLoad`] event whenever an image finishes loading in the background.
+    ImageLoad,
+}
+
+impl Register {
+    /// A timer that expires once, after `after`.
+    pub fn timeout(name: &'static str, after: Duration) -> Self {
+        Self::Timer {
+            name,
+            interval: after,
+            repeat: false,
+        }
+    }
+
+    /// A timer that expires every `interval`.
+    pub fn interval(name: &'static str, interval: Duration) -> Self {
+        Self::Timer {
+            name,
+            interval,
+            repeat: true,
+        }
+    }
 }
 
 impl Scalable for Scroll {
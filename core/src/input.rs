@@ -282,7 +282,10 @@ pub enum Input {
     Menu(i32),
     MouseLeaveWindow,
     MouseEnterWindow,
+    /// A frame callback from the compositor
     Timer,
+    /// The deadline passed to [`Window#schedule_timer`][crate::window::Window#method.schedule_timer] was reached
+    TimerExpired,
     Exit,
     Drag(Drag),
     Touch(TouchAction),
@@ -2,6 +2,7 @@
 //! All [`Nodes`](crate::Node) have a [`Layout`] attached, and this module is responsible for assigning a [`LayoutResult`] -- an absolution position and size --
 //! to the Node, during the draw phase. All [`Layout`] creation functionality -- and thus the entire user-facing interface -- is exposed through the less-verbose [`lay!`][crate::lay] macro.
 //!
+use femtovg::CompositeOperation;
 use std::ops::{Add, AddAssign, Div, DivAssign, Sub, SubAssign};
 // use mctk_core::size;
 
@@ -286,7 +287,7 @@ impl std::fmt::Debug for Rect {
 }
 
 impl Rect {
-    const ZERO: Self = Self {
+    pub(crate) const ZERO: Self = Self {
         left: Dimension::Px(0.0),
         right: Dimension::Px(0.0),
         top: Dimension::Px(0.0),
@@ -557,6 +558,21 @@ pub struct Layout {
     pub min_size: Size,
     pub z_index: Option<f64>,
     pub z_index_increment: f64,
+    /// Opacity of the node and its children. Below `1.0`, they are drawn onto an offscreen
+    /// layer first, which is composited with this opacity.
+    pub opacity: f32,
+    /// How the node and its children are blended with what is drawn below them. Other than
+    /// [`CompositeOperation::SourceOver`], they are drawn onto an offscreen layer first.
+    pub composite_operation: CompositeOperation,
+    /// Moves, scales and rotates the node and its children where they are drawn and hit tested.
+    pub transform: Option<crate::types::Transform>,
+    /// Animate the node from where it was laid out last to where it is laid out now.
+    pub animate: Option<crate::animation::Transition>,
+    /// How the node appears when `view` adds it to an existing parent.
+    pub enter: Option<crate::animation::NodeTransition>,
+    /// How the node disappears when `view` removes it. It stays in place, out of the flow
+    /// of its siblings and without receiving events, until the transition ends.
+    pub exit: Option<crate::animation::NodeTransition>,
     pub debug: Option<String>,
 }
 
@@ -579,6 +595,12 @@ impl Default for Layout {
             },
             z_index: None,
             z_index_increment: 0.0,
+            opacity: 1.0,
+            composite_operation: CompositeOperation::SourceOver,
+            transform: None,
+            animate: None,
+            enter: None,
+            exit: None,
             debug: None,
         }
     }
@@ -1205,6 +1227,46 @@ macro_rules! lay {
         lay!(@ { } -> ( $($result)* z_index : Some($z_index .into()), ))
     );
 
+    // Transform
+    ( @ { $(,)* transform : $transform:expr, $($rest:tt)* } -> ($($result:tt)*) ) => (
+        lay!(@ { $($rest)* } -> (
+            $($result)*
+                transform : Some($transform),
+        ))
+    );
+    ( @ { $(,)* transform : $transform:expr} -> ($($result:tt)*) ) => (
+        lay!(@ { } -> ( $($result)* transform : Some($transform), ))
+    );
+
+    // Layout animations
+    ( @ { $(,)* animate : $animate:expr, $($rest:tt)* } -> ($($result:tt)*) ) => (
+        lay!(@ { $($rest)* } -> (
+            $($result)*
+                animate : Some($animate),
+        ))
+    );
+    ( @ { $(,)* animate : $animate:expr} -> ($($result:tt)*) ) => (
+        lay!(@ { } -> ( $($result)* animate : Some($animate), ))
+    );
+    ( @ { $(,)* enter : $enter:expr, $($rest:tt)* } -> ($($result:tt)*) ) => (
+        lay!(@ { $($rest)* } -> (
+            $($result)*
+                enter : Some($enter),
+        ))
+    );
+    ( @ { $(,)* enter : $enter:expr} -> ($($result:tt)*) ) => (
+        lay!(@ { } -> ( $($result)* enter : Some($enter), ))
+    );
+    ( @ { $(,)* exit : $exit:expr, $($rest:tt)* } -> ($($result:tt)*) ) => (
+        lay!(@ { $($rest)* } -> (
+            $($result)*
+                exit : Some($exit),
+        ))
+    );
+    ( @ { $(,)* exit : $exit:expr} -> ($($result:tt)*) ) => (
+        lay!(@ { } -> ( $($result)* exit : Some($exit), ))
+    );
+
     // Debug
     ( @ { $(,)* debug : $debug:expr, $($rest:tt)* } -> ($($result:tt)*) ) => (
         lay!(@ { $($rest)* } -> (
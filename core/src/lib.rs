@@ -1,3 +1,4 @@
+pub mod animation;
 pub mod component;
 pub mod event;
 pub mod font_cache;
@@ -6,6 +7,7 @@ pub mod pointer;
 pub mod raw_handle;
 pub mod renderables;
 pub mod renderer;
+pub mod resources;
 pub mod style;
 pub mod ui;
 pub mod window;
@@ -41,3 +43,5 @@ pub use node::*;
 
 pub mod input;
 pub use input::*;
+
+mod timer;
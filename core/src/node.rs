@@ -2,12 +2,16 @@ use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::sync::atomic::{AtomicU64, Ordering};
 
+use crate::animation::LayoutAnimation;
 use crate::event::{self, Event, EventInput};
 use crate::font_cache::FontCache;
 use crate::renderables::Renderable;
 use crate::renderer::Caches;
+use crate::style::StyleTransitions;
 use crate::types::*;
+use crate::ui::Decorate;
 use crate::{component::*, widgets};
+use femtovg::{CompositeOperation, Transform2D};
 // use crate::font_cache::FontCache;
 use crate::layout::*;
 // use crate::render::{Caches, Renderable};
@@ -98,6 +102,14 @@ pub struct Node {
     pub(crate) props_hash: u64,
     pub(crate) render_hash: u64,
     pub(crate) key: u64,
+    pub(crate) style_transitions: StyleTransitions,
+    pub(crate) layout_animation: LayoutAnimation,
+    /// Opacity the node and its children are composited with
+    pub(crate) opacity: f32,
+    /// [`Layout::transform`] in physical pixels
+    pub(crate) transform: Option<Transform2D>,
+    /// See [`Component#clip`][crate::Component#method.clip]
+    pub(crate) clip_shape: Option<Clip>,
 }
 
 impl fmt::Debug for Node {
@@ -150,6 +162,11 @@ impl Node {
             props_hash: u64::max_value(),
             render_hash: u64::max_value(),
             clip: None,
+            style_transitions: Default::default(),
+            layout_animation: Default::default(),
+            opacity: 1.0,
+            transform: None,
+            clip_shape: None,
         }
     }
 
@@ -165,10 +182,17 @@ impl Node {
         self
     }
 
-    pub(crate) fn view(
+    pub(crate) fn view(&mut self, prev: Option<&mut Self>, registrations: &mut Vec<Registration>) {
+        self.view_decorated(prev, registrations, None)
+    }
+
+    /// Like [`view`][Node::view], with the Node viewed by the component passed through `decorate`,
+    /// which puts it inside of the decorations of the window.
+    pub(crate) fn view_decorated(
         &mut self,
         mut prev: Option<&mut Self>,
         registrations: &mut Vec<Registration>,
+        decorate: Option<&Decorate>,
     ) {
         // TODO: skip non-visible (out of frame) nodes
         // Set up state and props
@@ -178,6 +202,11 @@ impl Node {
             if let Some(state) = prev.component.take_state() {
                 self.component.replace_state(state);
             }
+            self.style_transitions = std::mem::take(&mut prev.style_transitions);
+            self.layout_animation = std::mem::take(&mut prev.layout_animation);
+            if self.layout_animation.is_exiting() {
+                self.layout_animation.revive();
+            }
 
             self.component.props_hash(&mut hasher);
             self.props_hash = hasher.finish();
@@ -193,7 +222,12 @@ impl Node {
         }
 
         // Create children
-        if let Some(mut child) = self.component.view() {
+        let component = &self.component;
+        let view = self.style_transitions.with_active(|| component.view());
+        if let Some(mut child) = view.map(|child| match decorate {
+            Some(decorate) => decorate(child),
+            None => child,
+        }) {
             if let Some(indexes) = self.component.container() {
                 // Pull out the children that were pushed onto this node, since we need to moves
                 // them to the correct position.
@@ -226,11 +260,14 @@ impl Node {
         if let Some(prev) = prev.as_mut() {
             let prev_children = &mut prev.children;
             for child in self.children.iter_mut() {
-                child.view(
-                    prev_children.iter_mut().find(|x| x.key == child.key),
-                    registrations,
-                )
+                let prev_child = prev_children.iter_mut().find(|x| x.key == child.key);
+                let entering = prev_child.is_none();
+                child.view(prev_child, registrations);
+                if let Some(enter) = child.layout.enter.filter(|_| entering) {
+                    child.layout_animation.enter(enter);
+                }
             }
+            self.keep_exiting_children(prev_children);
         } else {
             for child in self.children.iter_mut() {
                 child.view(None, registrations)
@@ -248,6 +285,39 @@ impl Node {
         );
     }
 
+    /// Moves the children that `view` removed, and that have an exit transition, from
+    /// `prev_children` back into this node until their transition ends. They keep the place
+    /// they were last laid out at and are not viewed again.
+    fn keep_exiting_children(&mut self, prev_children: &mut Vec<Self>) {
+        let mut i = 0;
+        let mut index = 0;
+        while i < prev_children.len() {
+            let prev_child = &prev_children[i];
+            let removed = !self.children.iter().any(|c| c.key == prev_child.key);
+            let exit = if prev_child.layout_animation.is_exiting() {
+                !prev_child.layout_animation.has_exited()
+            } else {
+                prev_child.layout.exit.is_some()
+            };
+
+            if removed && exit {
+                let mut child = prev_children.remove(i);
+                if !child.layout_animation.is_exiting() {
+                    child.layout_animation.exit(child.layout.exit.unwrap());
+                    // Out of the flow of its siblings
+                    child.layout.position_type = PositionType::Absolute;
+                    child.layout.position = child.layout_result.position;
+                    child.layout.size = child.layout_result.size;
+                    child.layout.margin = Rect::ZERO;
+                }
+                self.children.insert(index.min(self.children.len()), child);
+            } else {
+                i += 1;
+            }
+            index += 1;
+        }
+    }
+
     fn set_aabb(
         &mut self,
         parent_pos: Pos,
@@ -267,7 +337,11 @@ impl Node {
                 s.width = (s.width * scale_factor).round();
                 s.height = (s.height * scale_factor).round();
             }
+            self.aabb = self
+                .layout_animation
+                .apply(self.layout.animate, self.aabb, scale_factor);
         }
+        self.opacity = self.layout.opacity * self.layout_animation.opacity();
         self.aabb.pos += parent_pos;
         self.aabb.bottom_right += parent_pos.into();
         self.aabb.pos.z = (self.layout.z_index.unwrap_or((parent_pos.z + 1.0).into())
@@ -293,6 +367,13 @@ impl Node {
                 .set_aabb(&mut self.aabb, parent_aabb, children, frame, scale_factor);
         }
 
+        self.transform = self
+            .layout
+            .transform
+            .filter(|transform| !transform.is_identity())
+            .map(|transform| Transform2D(transform.matrix(self.aabb, scale_factor)));
+        self.clip_shape = self.component.clip(self.aabb);
+
         self.inclusive_aabb = self.aabb;
         if let Some(scale) = self.inner_scale {
             self.inclusive_aabb.set_scale_mut(scale.width, scale.height);
@@ -345,7 +426,7 @@ impl Node {
                 scale_factor,
             );
             if !scrollable {
-                expand_aabb(&mut self.inclusive_aabb, child.inclusive_aabb);
+                expand_aabb(&mut self.inclusive_aabb, child.transformed_inclusive_aabb());
             }
         }
     }
@@ -374,7 +455,14 @@ impl Node {
             let mut ret = false;
             self.component.render_hash(&mut hasher);
             self.aabb.size().hash(&mut hasher);
+            self.aabb.pos.hash(&mut hasher);
             self.inner_scale.hash(&mut hasher);
+            self.style_transitions.hash(&mut hasher);
+            self.layout_animation.hash(&mut hasher);
+            ((self.opacity * 255.0) as u8).hash(&mut hasher);
+            self.transform
+                .map(|transform| transform.0.map(f32::to_bits))
+                .hash(&mut hasher);
             self.render_hash = hasher.finish();
 
             if self.render_hash != prev.render_hash {
@@ -385,7 +473,10 @@ impl Node {
                     prev_state: prev.render_cache.take(),
                     scale_factor,
                 };
-                self.render_cache = self.component.render(context.clone());
+                let component = &mut self.component;
+                self.render_cache = self
+                    .style_transitions
+                    .with_active(|| component.render(context.clone()));
 
                 // println!("render::aabb - {:?}", self.aabb);
                 if self.scrollable() {
@@ -441,7 +532,10 @@ impl Node {
                 prev_state: None,
                 scale_factor,
             };
-            self.render_cache = self.component.render(context);
+            let component = &mut self.component;
+            self.render_cache = self
+                .style_transitions
+                .with_active(|| component.render(context));
             self.component.render_hash(&mut hasher);
             self.render_hash = hasher.finish();
 
@@ -453,6 +547,16 @@ impl Node {
         }
     }
 
+    /// Whether a style transition or layout animation of this node or its children is running.
+    pub(crate) fn transitions_running(&self) -> bool {
+        self.style_transitions.is_running()
+            || self.layout_animation.is_running()
+            || self
+                .children
+                .iter()
+                .any(|child| child.transitions_running())
+    }
+
     pub(crate) fn scroll_x(&self) -> Option<f32> {
         self.component.scroll_position().and_then(|p| p.x)
     }
@@ -465,8 +569,64 @@ impl Node {
         self.scroll_x().is_some() || self.scroll_y().is_some()
     }
 
+    /// Whether the node and its children are drawn onto a layer of their own, that is
+    /// clipped, and composited with the opacity and composite operation of the node.
+    pub(crate) fn is_layer(&self) -> bool {
+        self.opacity < 1.0
+            || self.layout.composite_operation != CompositeOperation::SourceOver
+            || self.clip_shape.is_some()
+    }
+
+    /// Whether the node and its children are drawn apart from the rest of the tree, because
+    /// they are a layer or transformed.
+    pub(crate) fn is_group(&self) -> bool {
+        self.is_layer() || self.transform.is_some()
+    }
+
+    /// Maps a position onto the node as it was laid out, before its transform.
+    fn untransform(&self, position: Point) -> Point {
+        match self.transform {
+            Some(transform) => {
+                let (x, y) = transform.inversed().transform_point(position.x, position.y);
+                Point::new(x, y)
+            }
+            None => position,
+        }
+    }
+
+    /// The bounds of the node and its children once transformed.
+    fn transformed_inclusive_aabb(&self) -> AABB {
+        let Some(transform) = self.transform else {
+            return self.inclusive_aabb;
+        };
+        let aabb = self.inclusive_aabb;
+        let corners = [
+            (aabb.pos.x, aabb.pos.y),
+            (aabb.bottom_right.x, aabb.pos.y),
+            (aabb.pos.x, aabb.bottom_right.y),
+            (aabb.bottom_right.x, aabb.bottom_right.y),
+        ]
+        .map(|(x, y)| transform.transform_point(x, y));
+
+        let mut transformed = aabb;
+        transformed.pos.x = corners.iter().map(|c| c.0).fold(f32::INFINITY, f32::min);
+        transformed.pos.y = corners.iter().map(|c| c.1).fold(f32::INFINITY, f32::min);
+        transformed.bottom_right.x = corners
+            .iter()
+            .map(|c| c.0)
+            .fold(f32::NEG_INFINITY, f32::max);
+        transformed.bottom_right.y = corners
+            .iter()
+            .map(|c| c.1)
+            .fold(f32::NEG_INFINITY, f32::max);
+        transformed
+    }
+
+    /// Iterates over the renderables of this node and its children. The children that are
+    /// groups are returned as a [`RenderItem::Group`], without their renderables.
     pub(crate) fn iter_renderables(&self) -> NodeRenderableIterator<'_> {
         NodeRenderableIterator {
+            root: self,
             queue: vec![self],
             current_frame: vec![],
             frame_queue: vec![],
@@ -512,10 +672,15 @@ impl Node {
         event.over_child_n = None;
         event.over_subchild_n = None;
         for (n, child) in self.children.iter_mut().enumerate() {
+            let child_position = child.untransform(event_target_position);
             if child
                 .component
-                .is_mouse_maybe_over(event_target_position, child.inclusive_aabb)
+                .is_mouse_maybe_over(child_position, child.inclusive_aabb)
             {
+                // The child and its handlers see the positions before its transform
+                let positions = (event.mouse_position, event.touch_position);
+                event.mouse_position = child.untransform(event.mouse_position);
+                event.touch_position = child.untransform(event.touch_position);
                 for message in child
                     ._handle_event_under_mouse(event, handler, node_order, use_touch)
                     .drain(..)
@@ -525,10 +690,9 @@ impl Node {
                         event.dirty();
                     }
                 }
-                if child
-                    .component
-                    .is_mouse_over(event_target_position, child.aabb)
-                {
+                (event.mouse_position, event.touch_position) = positions;
+
+                if child.component.is_mouse_over(child_position, child.aabb) {
                     event.over_subchild_n = event.over_child_n;
                     event.over_child_n = Some(n);
                     event.over_child_n_aabb = Some(child.aabb);
@@ -579,25 +743,20 @@ impl Node {
     fn nodes_under<E: EventInput>(&self, event: &Event<E>, use_touch: bool) -> Vec<(u64, f32)> {
         let mut collector: Vec<(u64, f32)> = vec![];
 
-        self._nodes_under(event, &mut collector, use_touch);
+        // switch to touch position
+        let position = if use_touch {
+            event.touch_position
+        } else {
+            event.mouse_position
+        };
+        self._nodes_under(position, &mut collector);
         // Maybe TODO: Discard siblings?
         collector.sort_by(|(m, _), (n, _)| m.partial_cmp(n).unwrap());
         collector
     }
 
-    fn _nodes_under<E: EventInput>(
-        &self,
-        event: &Event<E>,
-        collector: &mut Vec<(u64, f32)>,
-        use_touch: bool,
-    ) {
-        let mut event_target_position = event.mouse_position;
-
-        // switch to touch position
-        if use_touch {
-            event_target_position = event.touch_position;
-        }
-
+    /// `event_target_position` is already mapped onto the node, see [`Node::untransform`].
+    fn _nodes_under(&self, event_target_position: Point, collector: &mut Vec<(u64, f32)>) {
         if self
             .component
             .is_mouse_over(event_target_position, self.aabb)
@@ -615,11 +774,15 @@ impl Node {
         }
 
         for child in self.children.iter() {
+            if child.layout_animation.is_exiting() {
+                continue;
+            }
+            let child_position = child.untransform(event_target_position);
             if child
                 .component
-                .is_mouse_maybe_over(event_target_position, child.inclusive_aabb)
+                .is_mouse_maybe_over(child_position, child.inclusive_aabb)
             {
-                child._nodes_under(event, collector, use_touch);
+                child._nodes_under(child_position, collector);
             }
         }
     }
@@ -915,31 +1078,76 @@ impl Node {
         self.handle_targeted_event(event, |node, e| node.component.on_menu_select(e));
     }
 
-    pub(crate) fn tick(&mut self, event: &mut Event<event::Tick>) -> Vec<Message> {
-        let mut m: Vec<Message> = vec![];
+    /// Sends the tick to the nodes that registered for animation frames, they are redrawn on every frame
+    pub(crate) fn tick(&mut self, event: &mut Event<event::Tick>) {
+        for node_id in event.matching_registrations().iter() {
+            event.target = Some(*node_id);
+            self.handle_registered_event(event, |node, e| {
+                node.component.on_tick(e);
+                e.dirty();
+            });
+        }
+    }
 
-        for child in self.children.iter_mut() {
-            for message in child.tick(event).drain(..) {
-                m.append(&mut self.component.update(message));
-            }
+    /// Sends the timer event to its `event.target`
+    pub(crate) fn timer(&mut self, event: &mut Event<event::Timer>) {
+        self.handle_registered_event(event, |node, e| node.component.on_timer(e));
+    }
+
+    /// Sends the image load event to the nodes that registered for it
+    pub(crate) fn image_load(&mut self, event: &mut Event<event::ImageLoad>) {
+        for node_id in event.matching_registrations().iter() {
+            event.target = Some(*node_id);
+            self.handle_registered_event(event, |node, e| node.component.on_image_load(e));
         }
+    }
+
+    fn handle_registered_event<E: EventInput>(
+        &mut self,
+        event: &mut Event<E>,
+        handler: fn(&mut Self, &mut Event<E>),
+    ) {
+        let mut stack = match event
+            .target
+            .and_then(|target| self.get_target_stack(target))
+        {
+            Some(stack) => stack,
+            // the node was removed since it registered
+            None => return,
+        };
 
-        event.current_node_id = Some(self.id);
-        event.current_aabb = Some(self.aabb);
-        event.current_inner_scale = self.inner_scale;
-        self.component.on_tick(event);
-        if self.component.is_dirty() {
+        let node = self.get_target_from_stack(&stack);
+        event.current_node_id = Some(node.id);
+        event.current_aabb = Some(node.aabb);
+        event.current_inner_scale = node.inner_scale;
+        handler(node, event);
+        if node.component.is_dirty() {
             event.dirty();
         }
-        m.append(&mut event.messages);
 
-        m
+        if stack.is_empty() || event.messages.is_empty() {
+            event.messages.clear();
+            return;
+        }
+        stack.pop();
+        let mut messages = std::mem::take(&mut event.messages);
+        if self.send_messages(stack, &mut messages) {
+            event.dirty();
+        }
     }
 }
 
 pub(crate) type ScrollFrame = AABB;
 
+pub(crate) enum RenderItem<'a> {
+    /// A renderable, the AABB of its node, and the scroll frames it is in
+    Renderable(&'a Renderable, &'a AABB, Vec<ScrollFrame>),
+    /// A node that is drawn apart from the rest, see [`Node::is_group`]
+    Group(&'a Node),
+}
+
 pub(crate) struct NodeRenderableIterator<'a> {
+    root: &'a Node,
     queue: Vec<&'a Node>,
     current_frame: Vec<ScrollFrame>,
     frame_queue: Vec<(&'a Node, Vec<ScrollFrame>)>,
@@ -947,10 +1155,13 @@ pub(crate) struct NodeRenderableIterator<'a> {
 }
 
 impl<'a> Iterator for NodeRenderableIterator<'a> {
-    type Item = (&'a Renderable, &'a AABB, Vec<ScrollFrame>);
+    type Item = RenderItem<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
         while let Some(n) = self.queue.pop() {
+            if self.i == 0 && n.is_group() && !std::ptr::eq(n, self.root) {
+                return Some(RenderItem::Group(n));
+            }
             if let Some(c) = &n.render_cache {
                 let i = self.i;
 
@@ -966,7 +1177,11 @@ impl<'a> Iterator for NodeRenderableIterator<'a> {
                 } else {
                     self.i += 1;
                     self.queue.push(n);
-                    return Some((&c[i], &n.aabb, self.current_frame.clone()));
+                    return Some(RenderItem::Renderable(
+                        &c[i],
+                        &n.aabb,
+                        self.current_frame.clone(),
+                    ));
                 }
             } else if n.scrollable() {
                 let mut f = self.current_frame.clone();
@@ -0,0 +1,276 @@
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use crate::animation::Animatable;
+use crate::{Color, Pos};
+
+use super::types::Canvas;
+use derive_builder::Builder;
+use femtovg::{LineCap, Paint, Path, Solidity};
+
+/// A ring, or a pie when `inner_radius` is `0.0`, swept around `center`.
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct Instance {
+    pub center: Pos,
+    #[builder(default = "0.0")]
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    /// Radians, clockwise from 12 o'clock
+    #[builder(default = "0.0")]
+    pub start_angle: f32,
+    /// Radians, clockwise, or counter-clockwise when negative
+    #[builder(default = "TAU")]
+    pub sweep: f32,
+    #[builder(default = "Color::default()")]
+    pub color: Color,
+    /// Color stops from the start to the end of the arc, instead of `color`
+    #[builder(default = "None")]
+    pub gradient: Option<Vec<(f32, Color)>>,
+    /// Caps of the ends of a ring
+    #[builder(default = "LineCap::Butt")]
+    pub cap: LineCap,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Arc {
+    pub instance_data: Instance,
+}
+
+impl Arc {
+    pub fn new(center: Pos, inner_radius: f32, outer_radius: f32, color: Color) -> Self {
+        Self {
+            instance_data: Instance {
+                center,
+                inner_radius,
+                outer_radius,
+                start_angle: 0.0,
+                sweep: TAU,
+                color,
+                gradient: None,
+                cap: LineCap::Butt,
+            },
+        }
+    }
+
+    pub fn from_instance_data(instance_data: Instance) -> Self {
+        Self { instance_data }
+    }
+
+    pub fn render(&self, canvas: &mut Canvas) {
+        let Instance {
+            center,
+            inner_radius,
+            outer_radius,
+            start_angle,
+            sweep,
+            color,
+            gradient,
+            cap,
+        } = &self.instance_data;
+        if *sweep == 0.0 || outer_radius <= inner_radius {
+            return;
+        }
+
+        let sweep = sweep.clamp(-TAU, TAU);
+        // femtovg measures angles from 3 o'clock
+        let start = start_angle - FRAC_PI_2;
+        let end = start + sweep;
+        let direction = direction(sweep);
+
+        if let Some(stops) = gradient.as_ref().filter(|stops| !stops.is_empty()) {
+            self.render_gradient(canvas, stops, start, sweep);
+            return;
+        }
+
+        let mut paint = Paint::color((*color).into());
+        let mut path = Path::new();
+        if *inner_radius > 0.0 {
+            // A ring is the stroke along the middle of it
+            let radius = (inner_radius + outer_radius) / 2.0;
+            let (x, y) = point(*center, start, radius);
+            path.move_to(x, y);
+            path.arc(center.x, center.y, radius, start, end, direction);
+            paint.set_line_width(outer_radius - inner_radius);
+            paint.set_line_cap(*cap);
+            canvas.stroke_path(&path, &paint);
+        } else {
+            path.move_to(center.x, center.y);
+            path.arc(center.x, center.y, *outer_radius, start, end, direction);
+            path.close();
+            canvas.fill_path(&path, &paint);
+        }
+    }
+
+    /// Fills the arc in short segments, each with a linear gradient between the colors of its ends,
+    /// which follows the arc closely, however far it sweeps.
+    fn render_gradient(&self, canvas: &mut Canvas, stops: &[(f32, Color)], start: f32, sweep: f32) {
+        let Instance {
+            center,
+            inner_radius,
+            outer_radius,
+            cap,
+            ..
+        } = &self.instance_data;
+        let radius = (inner_radius + outer_radius) / 2.0;
+        let segments = gradient_segments(sweep);
+        let step = sweep / segments as f32;
+        // Segments overlap a little, so that their anti-aliased edges don't show as seams
+        let overlap = (SEAM_OVERLAP / outer_radius).min(step.abs()) * sweep.signum();
+
+        for i in 0..segments {
+            let from = start + step * i as f32;
+            let to = from + step;
+            let mut path = Path::new();
+            let drawn_to = if i + 1 < segments { to + overlap } else { to };
+            sector(
+                &mut path,
+                *center,
+                *inner_radius,
+                *outer_radius,
+                from,
+                drawn_to,
+            );
+
+            let (from_x, from_y) = point(*center, from, radius);
+            let (to_x, to_y) = point(*center, to, radius);
+            let paint = Paint::linear_gradient(
+                from_x,
+                from_y,
+                to_x,
+                to_y,
+                gradient_color(stops, i as f32 / segments as f32).into(),
+                gradient_color(stops, (i + 1) as f32 / segments as f32).into(),
+            );
+            canvas.fill_path(&path, &paint);
+        }
+
+        // Caps would overlap on a whole ring
+        if *inner_radius <= 0.0 || *cap == LineCap::Butt || sweep.abs() >= TAU {
+            return;
+        }
+        let half_width = (outer_radius - inner_radius) / 2.0;
+        let ends = [
+            (start, -sweep.signum(), gradient_color(stops, 0.0)),
+            (start + sweep, sweep.signum(), gradient_color(stops, 1.0)),
+        ];
+        for (angle, outwards, color) in ends {
+            let mut path = Path::new();
+            let (x, y) = point(*center, angle, radius);
+            if *cap == LineCap::Round {
+                // Half a circle, from the outer edge to the inner one
+                path.arc(
+                    x,
+                    y,
+                    half_width,
+                    angle,
+                    angle + outwards * PI,
+                    direction(outwards),
+                );
+            } else {
+                // Square, a half width past the end
+                let (dx, dy) = (
+                    -angle.sin() * outwards * half_width,
+                    angle.cos() * outwards * half_width,
+                );
+                let (outer_x, outer_y) = point(*center, angle, *outer_radius);
+                let (inner_x, inner_y) = point(*center, angle, *inner_radius);
+                path.move_to(outer_x, outer_y);
+                path.line_to(outer_x + dx, outer_y + dy);
+                path.line_to(inner_x + dx, inner_y + dy);
+                path.line_to(inner_x, inner_y);
+            }
+            path.close();
+            canvas.fill_path(&path, &Paint::color(color.into()));
+        }
+    }
+}
+
+/// Gradients are drawn in segments that sweep at most this far, over which a linear gradient is
+/// close to one around the arc.
+const GRADIENT_SEGMENT: f32 = TAU / 72.0;
+
+/// How far segments of a gradient overlap, in pixels along the outer edge.
+const SEAM_OVERLAP: f32 = 0.5;
+
+/// How femtovg draws an arc that sweeps `sweep`.
+fn direction(sweep: f32) -> Solidity {
+    if sweep > 0.0 {
+        Solidity::Hole // clockwise
+    } else {
+        Solidity::Solid
+    }
+}
+
+fn point(center: Pos, angle: f32, radius: f32) -> (f32, f32) {
+    (
+        center.x + angle.cos() * radius,
+        center.y + angle.sin() * radius,
+    )
+}
+
+/// Adds the part of the ring between `inner_radius` and `outer_radius` from angle `from` to `to`
+/// to `path`, or of the pie when `inner_radius` is `0.0`.
+fn sector(path: &mut Path, center: Pos, inner_radius: f32, outer_radius: f32, from: f32, to: f32) {
+    let (forwards, backwards) = (direction(to - from), direction(from - to));
+    if inner_radius > 0.0 {
+        let (x, y) = point(center, from, outer_radius);
+        path.move_to(x, y);
+        path.arc(center.x, center.y, outer_radius, from, to, forwards);
+        let (x, y) = point(center, to, inner_radius);
+        path.line_to(x, y);
+        path.arc(center.x, center.y, inner_radius, to, from, backwards);
+    } else {
+        path.move_to(center.x, center.y);
+        path.arc(center.x, center.y, outer_radius, from, to, forwards);
+    }
+    path.close();
+}
+
+/// How many segments a gradient that sweeps `sweep` is drawn in.
+fn gradient_segments(sweep: f32) -> usize {
+    ((sweep.abs() / GRADIENT_SEGMENT).ceil() as usize).max(1)
+}
+
+/// The color of the gradient of `stops` at `t`, from `0.0` at the start of the arc to `1.0` at its
+/// end. Before the first stop and after the last one, their colors are kept.
+fn gradient_color(stops: &[(f32, Color)], t: f32) -> Color {
+    let after = stops.iter().position(|(offset, _)| *offset > t);
+    match after {
+        Some(0) => stops[0].1,
+        Some(i) => {
+            let (from, from_color) = stops[i - 1];
+            let (to, to_color) = stops[i];
+            from_color.interpolate(&to_color, (t - from) / (to - from))
+        }
+        None => stops[stops.len() - 1].1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gradient_segments() {
+        assert_eq!(gradient_segments(0.01), 1);
+        assert_eq!(gradient_segments(-GRADIENT_SEGMENT * 2.5), 3);
+        // A whole ring is drawn around, not between its ends
+        assert_eq!(gradient_segments(TAU), 72);
+    }
+
+    #[test]
+    fn test_gradient_color() {
+        let red = Color::rgb(255.0, 0.0, 0.0);
+        let blue = Color::rgb(0.0, 0.0, 255.0);
+        let stops = [(0.25, red), (0.75, blue)];
+        assert_eq!(gradient_color(&stops, 0.0), red);
+        assert_eq!(gradient_color(&stops, 0.25), red);
+        assert_eq!(gradient_color(&stops, 0.5), Color::rgb(127.5, 0.0, 127.5));
+        assert_eq!(gradient_color(&stops, 0.75), blue);
+        assert_eq!(gradient_color(&stops, 1.0), blue);
+
+        // A hard stop
+        let stops = [(0.0, red), (0.5, red), (0.5, blue), (1.0, blue)];
+        assert_eq!(gradient_color(&stops, 0.49), red);
+        assert_eq!(gradient_color(&stops, 0.5), blue);
+    }
+}
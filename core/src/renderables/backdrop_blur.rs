@@ -0,0 +1,29 @@
+use crate::types::{Pos, Scale};
+use derive_builder::Builder;
+
+/// Blurs what was drawn behind the rect at `pos` and `scale`, for frosted panels.
+///
+/// Drawn by the renderer rather than on its own, since it needs what was drawn before it:
+/// the frame is drawn onto an offscreen image when it has a backdrop blur. The transform of the
+/// node moves the rect, but not what is behind it.
+#[derive(Clone, Copy, Debug, PartialEq, Builder)]
+pub struct Instance {
+    pub pos: Pos,
+    pub scale: Scale,
+    #[builder(default = "(0., 0., 0., 0.)")]
+    pub radius: (f32, f32, f32, f32),
+    /// Standard deviation of the gaussian blur, in physical pixels. Blurs wider than 8 are done on
+    /// a scaled down backdrop, so they lose detail they would have blurred away anyway.
+    pub sigma: f32,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct BackdropBlur {
+    pub instance_data: Instance,
+}
+
+impl BackdropBlur {
+    pub fn from_instance_data(instance_data: Instance) -> Self {
+        Self { instance_data }
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::component::RenderContext;
+use crate::AABB;
+
+use super::types::Canvas;
+
+/// Draws straight onto the femtovg canvas, for what the other renderables can't draw, like
+/// charts, waveforms or signature pads.
+///
+/// Implemented for closures, so a [`Custom`] can be made from one:
+///
+/// ```ignore
+/// fn render(&mut self, context: RenderContext) -> Option<Vec<Renderable>> {
+///     let samples = self.state_ref().samples.clone();
+///     Some(vec![Renderable::Custom(Custom::from_context(
+///         &context,
+///         move |canvas: &mut Canvas, aabb: AABB, scale_factor: f32| {
+///             // draw the waveform in `aabb`
+///         },
+///     ))])
+/// }
+/// ```
+pub trait Draw: Send + Sync {
+    /// Called every frame the node is drawn, with the [`AABB`] of the node and the scale factor
+    /// of the monitor, in physical pixels like the other renderables.
+    ///
+    /// The canvas already has the transform of the node and its parents, so transform it
+    /// with `translate`, `rotate` and `scale` rather than replacing its transform. Its state is
+    /// restored after drawing.
+    fn draw(&self, canvas: &mut Canvas, aabb: AABB, scale_factor: f32);
+}
+
+impl<F> Draw for F
+where
+    F: Fn(&mut Canvas, AABB, f32) + Send + Sync,
+{
+    fn draw(&self, canvas: &mut Canvas, aabb: AABB, scale_factor: f32) {
+        self(canvas, aabb, scale_factor)
+    }
+}
+
+#[derive(Clone)]
+pub struct Custom {
+    pub aabb: AABB,
+    pub scale_factor: f32,
+    pub drawer: Arc<dyn Draw>,
+}
+
+impl Custom {
+    pub fn new(aabb: AABB, scale_factor: f32, drawer: impl Draw + 'static) -> Self {
+        Self {
+            aabb,
+            scale_factor,
+            drawer: Arc::new(drawer),
+        }
+    }
+
+    /// Draws in the AABB of the component being rendered.
+    pub fn from_context(context: &RenderContext, drawer: impl Draw + 'static) -> Self {
+        Self::new(context.aabb, context.scale_factor, drawer)
+    }
+
+    pub fn render(&self, canvas: &mut Canvas) {
+        canvas.save();
+        self.drawer.draw(canvas, self.aabb, self.scale_factor);
+        canvas.restore();
+    }
+}
+
+impl fmt::Debug for Custom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Custom")
+            .field("aabb", &self.aabb)
+            .field("scale_factor", &self.scale_factor)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Custom {
+    fn eq(&self, other: &Self) -> bool {
+        self.aabb == other.aabb
+            && self.scale_factor == other.scale_factor
+            && Arc::ptr_eq(&self.drawer, &other.drawer)
+    }
+}
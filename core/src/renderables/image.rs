@@ -1,15 +1,48 @@
-use std::collections::HashMap;
-
-use crate::{Pos, Scale};
+use crate::renderer::image_cache::ImageCache;
+use crate::renderer::image_loader::ImageTarget;
+use crate::{AssetParams, Pos, Scale};
 
 use super::types;
 use super::types::Canvas;
 use derive_builder::Builder;
-use femtovg::{CompositeOperation, ImageFlags, ImageId, Paint, Path};
+use femtovg::{CompositeOperation, ImageId, Paint, Path};
 
 type Point = types::Point<f32>;
 type Size = types::Size<f32>;
 
+/// `(x, y, width, height)`
+type Bounds = (f32, f32, f32, f32);
+
+/// How an image is sized within its bounds, like CSS `object-fit`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ImageFit {
+    /// Stretched to the bounds
+    #[default]
+    Fill,
+    /// As big as fits within the bounds, keeping its aspect ratio
+    Contain,
+    /// As small as covers the bounds, keeping its aspect ratio, and cropped
+    Cover,
+    /// At its own size, and cropped
+    None,
+    /// Like `None`, or `Contain` when it is bigger than the bounds
+    ScaleDown,
+}
+
+impl ImageFit {
+    /// From `"fill"`, `"contain"`, `"cover"`, `"none"` or `"scale_down"`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fill" => Some(Self::Fill),
+            "contain" => Some(Self::Contain),
+            "cover" => Some(Self::Cover),
+            "none" => Some(Self::None),
+            "scale_down" => Some(Self::ScaleDown),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Builder)]
 pub struct Instance {
     pub name: String,
@@ -19,8 +52,30 @@ pub struct Instance {
     pub composite_operation: CompositeOperation,
     #[builder(default = "0.0")]
     pub radius: f32,
+    /// Path to decode the image from in the background, when there is no image named `name` yet
     #[builder(default = "None")]
     pub dynamic_load_from: Option<String>,
+    /// Frame to draw of an animated image. `dynamic_load_from` is decoded as an animation when set
+    #[builder(default = "None")]
+    pub frame: Option<usize>,
+    #[builder(default = "ImageFit::Fill")]
+    pub fit: ImageFit,
+    /// Where the image is placed when it doesn't fill the bounds, from `(0, 0)` at the top left
+    /// to `(1, 1)` at the bottom right
+    #[builder(default = "Point::new(0.5, 0.5)")]
+    pub align: Point,
+    /// Tile the image at its own size, starting from `align`, instead of fitting it. `radius` does not apply to tiles
+    #[builder(default = "false")]
+    pub repeat: bool,
+    /// Nine-patch insets of the image, in pixels of its 1x variant as `(top, right, bottom, left)`: the corners
+    /// keep their size, the edges stretch along the sides and the center stretches to fill the
+    /// rest. Takes precedence over `fit` and `repeat`
+    #[builder(default = "None")]
+    pub slice: Option<(f32, f32, f32, f32)>,
+    /// Physical pixels per logical pixel. Images that keep their own size are drawn at their
+    /// variant's size in logical pixels
+    #[builder(default = "1.0")]
+    pub scale_factor: f32,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -38,6 +93,12 @@ impl Image {
                 composite_operation: CompositeOperation::SourceOver,
                 radius: Default::default(),
                 dynamic_load_from: Default::default(),
+                frame: None,
+                fit: ImageFit::Fill,
+                align: Point::new(0.5, 0.5),
+                repeat: false,
+                slice: None,
+                scale_factor: 1.0,
             },
         }
     }
@@ -47,35 +108,70 @@ impl Image {
         self
     }
 
-    pub fn render(&self, canvas: &mut Canvas, assets: &mut HashMap<String, ImageId>) {
+    pub(crate) fn render(
+        &self,
+        canvas: &mut Canvas,
+        assets: &mut ImageCache,
+        target: &mut ImageTarget,
+    ) {
         let Instance {
             pos,
             scale,
             composite_operation,
             radius,
             dynamic_load_from,
+            frame,
+            fit,
+            align,
+            repeat,
+            slice,
+            scale_factor,
             ..
         } = self.instance_data.clone();
 
         canvas.global_composite_operation(composite_operation);
 
-        //Load image dynamically
-        if assets.get(&self.instance_data.name).is_none() && dynamic_load_from.is_some() {
-            let path = dynamic_load_from.unwrap();
-            let image_load_r = canvas.load_image_file(path, ImageFlags::empty());
-            if let Ok(image_id) = image_load_r {
-                assets.insert(self.instance_data.name.clone(), image_id);
-            }
+        // Nothing is drawn until it is decoded, or decoded again after it was evicted
+        let name = &self.instance_data.name;
+        target.show_frame(canvas, assets, name, frame.unwrap_or(0));
+        if !assets.contains(name) {
+            let params = dynamic_load_from.map(|path| match frame {
+                Some(_) => AssetParams::new(path).animated(),
+                None => AssetParams::new(path),
+            });
+            target.request(name, params);
         }
 
-        if let Some(image_id) = assets.get(&self.instance_data.name) {
-            let Pos { x, y, z } = pos;
-            let Scale { width, height } = scale;
+        if let Some(image_id) = assets.get(name) {
+            // A 2x variant is drawn at half its size in pixels on a 1x display
+            let density = assets.density(name);
+            let bounds = (pos.x, pos.y, scale.width, scale.height);
+            let image_size = canvas
+                .image_size(image_id)
+                .map(|(width, height)| (width as f32, height as f32))
+                .unwrap_or((scale.width, scale.height));
 
-            let paint = Paint::image(*image_id, x, y, width, height, 0.0, 1.0);
-            let mut path = Path::new();
-            path.rounded_rect(x, y, width, height, radius);
-            canvas.fill_path(&path, &paint);
+            let pixel_scale = scale_factor / density;
+            let natural = (image_size.0 * pixel_scale, image_size.1 * pixel_scale);
+
+            if let Some((top, right, bottom, left)) = slice {
+                let slice = (
+                    top * density,
+                    right * density,
+                    bottom * density,
+                    left * density,
+                );
+                draw_nine_patch(canvas, image_id, image_size, slice, pixel_scale, bounds);
+            } else if repeat {
+                draw_tiles(canvas, image_id, natural, align, bounds);
+            } else {
+                let (x, y, width, height) = fit_rect(fit, align, natural, bounds);
+                let paint = Paint::image(image_id, x, y, width, height, 0.0, 1.0);
+                let (x, y, width, height) = intersect((x, y, width, height), bounds);
+                let mut path = Path::new();
+                path.rounded_rect(x, y, width, height, radius);
+                canvas.fill_path(&path, &paint);
+            }
         }
 
         canvas.global_composite_operation(CompositeOperation::SourceOver);
@@ -85,3 +181,189 @@ impl Image {
         Self { instance_data }
     }
 }
+
+/// Where an image of `natural` size is drawn within `bounds`.
+fn fit_rect(fit: ImageFit, align: Point, natural: (f32, f32), bounds: Bounds) -> Bounds {
+    let (width, height) = natural;
+    let (_, _, bounds_width, bounds_height) = bounds;
+    if width <= 0.0 || height <= 0.0 {
+        return bounds;
+    }
+
+    let scale = match fit {
+        ImageFit::Fill => return bounds,
+        ImageFit::Contain => (bounds_width / width).min(bounds_height / height),
+        ImageFit::Cover => (bounds_width / width).max(bounds_height / height),
+        ImageFit::None => 1.0,
+        ImageFit::ScaleDown => (bounds_width / width).min(bounds_height / height).min(1.0),
+    };
+    let (width, height) = (width * scale, height * scale);
+    (
+        bounds.0 + (bounds_width - width) * align.x,
+        bounds.1 + (bounds_height - height) * align.y,
+        width,
+        height,
+    )
+}
+
+fn intersect(a: Bounds, b: Bounds) -> Bounds {
+    let x = a.0.max(b.0);
+    let y = a.1.max(b.1);
+    let right = (a.0 + a.2).min(b.0 + b.2);
+    let bottom = (a.1 + a.3).min(b.1 + b.3);
+    (x, y, (right - x).max(0.0), (bottom - y).max(0.0))
+}
+
+fn draw_tiles(
+    canvas: &mut Canvas,
+    image_id: ImageId,
+    tile: (f32, f32),
+    align: Point,
+    bounds: Bounds,
+) {
+    let (tile_width, tile_height) = tile;
+    // Too many tiles to draw
+    if tile_width < 1.0 || tile_height < 1.0 {
+        return;
+    }
+
+    // A tile sits at `align`, the rest are laid out from it
+    let (aligned_x, aligned_y, _, _) = fit_rect(ImageFit::None, align, tile, bounds);
+    let start_x = aligned_x - ((aligned_x - bounds.0) / tile_width).ceil() * tile_width;
+    let start_y = aligned_y - ((aligned_y - bounds.1) / tile_height).ceil() * tile_height;
+
+    let mut y = start_y;
+    while y < bounds.1 + bounds.3 {
+        let mut x = start_x;
+        while x < bounds.0 + bounds.2 {
+            let paint = Paint::image(image_id, x, y, tile_width, tile_height, 0.0, 1.0);
+            let (cx, cy, cw, ch) = intersect((x, y, tile_width, tile_height), bounds);
+            let mut path = Path::new();
+            path.rect(cx, cy, cw, ch);
+            canvas.fill_path(&path, &paint);
+            x += tile_width;
+        }
+        y += tile_height;
+    }
+}
+
+/// Column or row edges of the nine patches, in the image and within the bounds.
+fn nine_patch_edges(
+    image_size: f32,
+    start: f32,
+    end: f32,
+    scale_factor: f32,
+    bounds_start: f32,
+    bounds_size: f32,
+) -> ([f32; 4], [f32; 4]) {
+    let (mut start_size, mut end_size) = (start * scale_factor, end * scale_factor);
+    // Corners shrink when they don't fit
+    if start_size + end_size > bounds_size && start_size + end_size > 0.0 {
+        let shrink = bounds_size / (start_size + end_size);
+        start_size *= shrink;
+        end_size *= shrink;
+    }
+    (
+        [0.0, start, image_size - end, image_size],
+        [
+            bounds_start,
+            bounds_start + start_size,
+            bounds_start + bounds_size - end_size,
+            bounds_start + bounds_size,
+        ],
+    )
+}
+
+fn draw_nine_patch(
+    canvas: &mut Canvas,
+    image_id: ImageId,
+    image_size: (f32, f32),
+    slice: (f32, f32, f32, f32),
+    scale_factor: f32,
+    bounds: Bounds,
+) {
+    let (top, right, bottom, left) = slice;
+    let (src_x, dst_x) =
+        nine_patch_edges(image_size.0, left, right, scale_factor, bounds.0, bounds.2);
+    let (src_y, dst_y) =
+        nine_patch_edges(image_size.1, top, bottom, scale_factor, bounds.1, bounds.3);
+
+    for row in 0..3 {
+        for column in 0..3 {
+            let (src_width, src_height) = (
+                src_x[column + 1] - src_x[column],
+                src_y[row + 1] - src_y[row],
+            );
+            let (dst_width, dst_height) = (
+                dst_x[column + 1] - dst_x[column],
+                dst_y[row + 1] - dst_y[row],
+            );
+            if src_width <= 0.0 || src_height <= 0.0 || dst_width <= 0.0 || dst_height <= 0.0 {
+                continue;
+            }
+
+            // The whole image, scaled so that this patch lands on its place
+            let (scale_x, scale_y) = (dst_width / src_width, dst_height / src_height);
+            let paint = Paint::image(
+                image_id,
+                dst_x[column] - src_x[column] * scale_x,
+                dst_y[row] - src_y[row] * scale_y,
+                image_size.0 * scale_x,
+                image_size.1 * scale_y,
+                0.0,
+                1.0,
+            );
+            let mut path = Path::new();
+            path.rect(dst_x[column], dst_y[row], dst_width, dst_height);
+            canvas.fill_path(&path, &paint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_rect() {
+        let bounds = (10.0, 10.0, 100.0, 50.0);
+        let center = Point::new(0.5, 0.5);
+        let natural = (20.0, 20.0);
+
+        assert_eq!(fit_rect(ImageFit::Fill, center, natural, bounds), bounds);
+        assert_eq!(
+            fit_rect(ImageFit::Contain, center, natural, bounds),
+            (35.0, 10.0, 50.0, 50.0)
+        );
+        assert_eq!(
+            fit_rect(ImageFit::Cover, Point::new(0.0, 0.0), natural, bounds),
+            (10.0, 10.0, 100.0, 100.0)
+        );
+        assert_eq!(
+            fit_rect(ImageFit::None, Point::new(1.0, 1.0), natural, bounds),
+            (90.0, 40.0, 20.0, 20.0)
+        );
+        // Only ever scaled down
+        assert_eq!(
+            fit_rect(ImageFit::ScaleDown, center, natural, bounds),
+            (50.0, 25.0, 20.0, 20.0)
+        );
+        assert_eq!(
+            fit_rect(ImageFit::ScaleDown, center, (200.0, 100.0), bounds),
+            (10.0, 10.0, 100.0, 50.0)
+        );
+    }
+
+    #[test]
+    fn test_nine_patch_edges() {
+        assert_eq!(
+            nine_patch_edges(30.0, 10.0, 5.0, 2.0, 100.0, 200.0),
+            ([0.0, 10.0, 25.0, 30.0], [100.0, 120.0, 290.0, 300.0])
+        );
+        // Corners bigger than the bounds shrink
+        assert_eq!(
+            nine_patch_edges(30.0, 10.0, 10.0, 1.0, 0.0, 10.0),
+            ([0.0, 10.0, 20.0, 30.0], [0.0, 5.0, 5.0, 10.0])
+        );
+    }
+}
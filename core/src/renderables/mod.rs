@@ -1,19 +1,29 @@
+pub mod arc;
+pub mod backdrop_blur;
 pub mod circle;
 pub mod curve;
+pub mod custom;
 pub mod image;
 pub mod line;
+pub mod path;
 pub mod radial_gradient;
 pub mod rect;
+pub mod shadow;
 pub mod svg;
 pub mod text;
 pub mod types;
 
+pub use arc::Arc;
+pub use backdrop_blur::BackdropBlur;
 pub use circle::Circle;
 pub use curve::Curve;
+pub use custom::Custom;
 pub use image::Image;
 pub use line::Line;
+pub use path::Path;
 pub use radial_gradient::RadialGradient;
 pub use rect::Rect;
+pub use shadow::Shadow;
 pub use svg::Svg;
 pub use text::Text;
 
@@ -27,4 +37,10 @@ pub enum Renderable {
     Svg(Svg),
     RadialGradient(RadialGradient),
     Curve(Curve),
+    Shadow(Shadow),
+    BackdropBlur(BackdropBlur),
+    Arc(Arc),
+    Path(Path),
+    /// Drawn by a component, see [`custom::Draw`]
+    Custom(Custom),
 }
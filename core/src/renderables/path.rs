@@ -0,0 +1,354 @@
+use std::mem;
+
+use crate::{Color, Point};
+
+use super::types::Canvas;
+use derive_builder::Builder;
+use femtovg::{LineCap, LineJoin, Paint, Path as FemtovgPath};
+
+/// A segment of a [`PathData`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathCommand {
+    MoveTo(Point),
+    LineTo(Point),
+    /// Control point, end point
+    QuadTo(Point, Point),
+    /// First and second control points, end point
+    CubicTo(Point, Point, Point),
+    Close,
+}
+
+/// Builds an arbitrary path, in physical pixels:
+///
+/// ```
+/// # use mctk_core::renderables::path::PathData;
+/// let triangle = PathData::new()
+///     .move_to(0.0, 10.0)
+///     .line_to(5.0, 0.0)
+///     .line_to(10.0, 10.0)
+///     .close();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PathData {
+    pub commands: Vec<PathCommand>,
+}
+
+impl PathData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        self.commands.push(PathCommand::MoveTo(Point::new(x, y)));
+        self
+    }
+
+    pub fn line_to(mut self, x: f32, y: f32) -> Self {
+        self.commands.push(PathCommand::LineTo(Point::new(x, y)));
+        self
+    }
+
+    pub fn quad_to(mut self, cx: f32, cy: f32, x: f32, y: f32) -> Self {
+        self.commands
+            .push(PathCommand::QuadTo(Point::new(cx, cy), Point::new(x, y)));
+        self
+    }
+
+    pub fn cubic_to(mut self, c1x: f32, c1y: f32, c2x: f32, c2y: f32, x: f32, y: f32) -> Self {
+        self.commands.push(PathCommand::CubicTo(
+            Point::new(c1x, c1y),
+            Point::new(c2x, c2y),
+            Point::new(x, y),
+        ));
+        self
+    }
+
+    pub fn close(mut self) -> Self {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    fn to_femtovg(&self) -> FemtovgPath {
+        let mut path = FemtovgPath::new();
+        for command in self.commands.iter() {
+            match *command {
+                PathCommand::MoveTo(p) => path.move_to(p.x, p.y),
+                PathCommand::LineTo(p) => path.line_to(p.x, p.y),
+                PathCommand::QuadTo(c, p) => path.quad_to(c.x, c.y, p.x, p.y),
+                PathCommand::CubicTo(c1, c2, p) => path.bezier_to(c1.x, c1.y, c2.x, c2.y, p.x, p.y),
+                PathCommand::Close => path.close(),
+            }
+        }
+        path
+    }
+
+    /// The subpaths as polylines, with curves split into short lines.
+    fn flatten(&self) -> Vec<Vec<Point>> {
+        let mut polylines: Vec<Vec<Point>> = vec![];
+        let mut current: Vec<Point> = vec![];
+        let mut last = Point::default();
+
+        for command in self.commands.iter() {
+            match *command {
+                PathCommand::MoveTo(p) => {
+                    if current.len() > 1 {
+                        polylines.push(mem::take(&mut current));
+                    }
+                    current = vec![p];
+                }
+                PathCommand::LineTo(p) => current.push(p),
+                PathCommand::QuadTo(c, p) => {
+                    let steps = curve_steps(last.dist(c) + c.dist(p));
+                    for i in 1..=steps {
+                        let t = i as f32 / steps as f32;
+                        let u = 1.0 - t;
+                        current.push(last * (u * u) + c * (2.0 * u * t) + p * (t * t));
+                    }
+                }
+                PathCommand::CubicTo(c1, c2, p) => {
+                    let steps = curve_steps(last.dist(c1) + c1.dist(c2) + c2.dist(p));
+                    for i in 1..=steps {
+                        let t = i as f32 / steps as f32;
+                        let u = 1.0 - t;
+                        current.push(
+                            last * (u * u * u)
+                                + c1 * (3.0 * u * u * t)
+                                + c2 * (3.0 * u * t * t)
+                                + p * (t * t * t),
+                        );
+                    }
+                }
+                PathCommand::Close => {
+                    if let Some(first) = current.first().copied() {
+                        current.push(first);
+                        polylines.push(mem::take(&mut current));
+                        current = vec![first];
+                    }
+                }
+            }
+            if let Some(p) = current.last() {
+                last = *p;
+            }
+        }
+        if current.len() > 1 {
+            polylines.push(current);
+        }
+        polylines
+    }
+}
+
+/// Roughly one line every 3 pixels of the control polygon.
+fn curve_steps(length: f32) -> usize {
+    ((length / 3.0).ceil() as usize).clamp(1, 100)
+}
+
+/// Splits `polyline` into the "on" stretches of a dash pattern of alternating on and off lengths.
+///
+/// Like SVG, a pattern with an odd number of lengths is repeated to make it even.
+fn dash(polyline: &[Point], pattern: &[f32], offset: f32) -> Vec<Vec<Point>> {
+    if polyline.len() < 2 || pattern.iter().sum::<f32>() <= 0.0 || pattern.iter().any(|l| *l < 0.0)
+    {
+        return vec![polyline.to_vec()];
+    }
+    let pattern: Vec<f32> = if pattern.len() % 2 == 1 {
+        pattern.iter().chain(pattern.iter()).copied().collect()
+    } else {
+        pattern.to_vec()
+    };
+    let total: f32 = pattern.iter().sum();
+    let is_on = |index: usize| index % 2 == 0;
+
+    // Where along the pattern the polyline starts
+    let mut index = 0;
+    let mut remaining = pattern[0];
+    let mut skip = offset.rem_euclid(total);
+    while skip > 0.0 {
+        if skip >= remaining {
+            skip -= remaining;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        } else {
+            remaining -= skip;
+            skip = 0.0;
+        }
+    }
+
+    let mut dashes = vec![];
+    let mut current = vec![];
+    if is_on(index) {
+        current.push(polyline[0]);
+    }
+    for segment in polyline.windows(2) {
+        let (mut from, to) = (segment[0], segment[1]);
+        let mut length = from.dist(to);
+        while length > remaining {
+            let point = from + (to - from) * (remaining / length);
+            current.push(point);
+            if is_on(index) {
+                dashes.push(mem::take(&mut current));
+            }
+            length -= remaining;
+            from = point;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+        remaining -= length;
+        if is_on(index) {
+            current.push(to);
+        }
+    }
+    if current.len() > 1 {
+        dashes.push(current);
+    }
+    dashes
+}
+
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct Instance {
+    pub data: PathData,
+    #[builder(default = "None")]
+    pub fill: Option<Color>,
+    #[builder(default = "Some(Color::default())")]
+    pub stroke: Option<Color>,
+    #[builder(default = "1.0")]
+    pub stroke_width: f32,
+    #[builder(default = "LineCap::Butt")]
+    pub line_cap: LineCap,
+    #[builder(default = "LineJoin::Miter")]
+    pub line_join: LineJoin,
+    /// Alternating lengths of dashes and gaps along the stroke, or a solid stroke when empty
+    #[builder(default = "vec![]")]
+    pub dash: Vec<f32>,
+    /// How far into the dash pattern the stroke starts
+    #[builder(default = "0.0")]
+    pub dash_offset: f32,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Path {
+    pub instance_data: Instance,
+}
+
+impl Path {
+    pub fn new(data: PathData, stroke: Color, stroke_width: f32) -> Self {
+        Self {
+            instance_data: Instance {
+                data,
+                fill: None,
+                stroke: Some(stroke),
+                stroke_width,
+                line_cap: LineCap::Butt,
+                line_join: LineJoin::Miter,
+                dash: vec![],
+                dash_offset: 0.0,
+            },
+        }
+    }
+
+    pub fn from_instance_data(instance_data: Instance) -> Self {
+        Self { instance_data }
+    }
+
+    pub fn render(&self, canvas: &mut Canvas) {
+        let Instance {
+            data,
+            fill,
+            stroke,
+            stroke_width,
+            line_cap,
+            line_join,
+            dash: pattern,
+            dash_offset,
+        } = &self.instance_data;
+        if data.commands.is_empty() {
+            return;
+        }
+
+        let mut path = data.to_femtovg();
+        if let Some(fill) = fill {
+            canvas.fill_path(&path, &Paint::color((*fill).into()));
+        }
+
+        if let Some(stroke) = stroke {
+            // femtovg has no dashes, so a dashed stroke is drawn as many short ones
+            if !pattern.is_empty() {
+                path = FemtovgPath::new();
+                for polyline in data.flatten() {
+                    for dash in dash(&polyline, pattern, *dash_offset) {
+                        path.move_to(dash[0].x, dash[0].y);
+                        for p in dash.iter().skip(1) {
+                            path.line_to(p.x, p.y);
+                        }
+                    }
+                }
+            }
+
+            let mut paint = Paint::color((*stroke).into());
+            paint.set_line_width(*stroke_width);
+            paint.set_line_cap(*line_cap);
+            paint.set_line_join(*line_join);
+            canvas.stroke_path(&path, &paint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(length: f32) -> Vec<Point> {
+        vec![Point::new(0.0, 0.0), Point::new(length, 0.0)]
+    }
+
+    fn xs(dashes: Vec<Vec<Point>>) -> Vec<Vec<f32>> {
+        dashes
+            .iter()
+            .map(|dash| dash.iter().map(|p| (p.x * 100.0).round() / 100.0).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_dash() {
+        assert_eq!(
+            xs(dash(&line(10.0), &[2.0, 3.0], 0.0)),
+            vec![vec![0.0, 2.0], vec![5.0, 7.0]]
+        );
+        assert_eq!(
+            xs(dash(&line(10.0), &[2.0, 3.0], 1.0)),
+            vec![vec![0.0, 1.0], vec![4.0, 6.0], vec![9.0, 10.0]]
+        );
+        // Odd patterns repeat, so the second dash is a gap
+        assert_eq!(
+            xs(dash(&line(10.0), &[2.0], 0.0)),
+            vec![vec![0.0, 2.0], vec![4.0, 6.0], vec![8.0, 10.0]]
+        );
+        // Dashes carry on around corners
+        let corner = vec![
+            Point::new(0.0, 0.0),
+            Point::new(2.0, 0.0),
+            Point::new(2.0, 2.0),
+        ];
+        assert_eq!(
+            dash(&corner, &[3.0, 1.0], 0.0),
+            vec![vec![
+                Point::new(0.0, 0.0),
+                Point::new(2.0, 0.0),
+                Point::new(2.0, 1.0)
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_flatten() {
+        let data = PathData::new()
+            .move_to(0.0, 0.0)
+            .line_to(4.0, 0.0)
+            .quad_to(4.0, 4.0, 0.0, 4.0)
+            .close();
+        let polylines = data.flatten();
+        assert_eq!(polylines.len(), 1);
+        assert_eq!(polylines[0].first(), Some(&Point::new(0.0, 0.0)));
+        assert_eq!(polylines[0].last(), Some(&Point::new(0.0, 0.0)));
+        assert!(polylines[0].contains(&Point::new(0.0, 4.0)));
+    }
+}
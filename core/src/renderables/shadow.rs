@@ -0,0 +1,111 @@
+use super::types::Canvas;
+use crate::types::{Color, Point, Pos, Scale};
+use derive_builder::Builder;
+use femtovg::{Paint, Path, Solidity};
+
+/// A box shadow around, or inside, the rect at `pos` and `scale`.
+#[derive(Clone, Copy, Debug, PartialEq, Builder)]
+pub struct Instance {
+    /// Top left of the rect that casts the shadow
+    pub pos: Pos,
+    pub scale: Scale,
+    #[builder(default = "(0., 0., 0., 0.)")]
+    pub radius: (f32, f32, f32, f32),
+    #[builder(default = "Color::rgba(0., 0., 0., 0.5)")]
+    pub color: Color,
+    #[builder(default = "Point::new(0., 0.)")]
+    pub offset: Point,
+    /// How far the edge of the shadow fades out
+    #[builder(default = "0.0")]
+    pub blur: f32,
+    /// How much bigger than the rect the shadow is, or smaller when negative
+    #[builder(default = "0.0")]
+    pub spread: f32,
+    /// Draw the shadow inside the rect, instead of around it
+    #[builder(default = "false")]
+    pub inset: bool,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Shadow {
+    pub instance_data: Instance,
+}
+
+impl Shadow {
+    pub fn from_instance_data(instance_data: Instance) -> Self {
+        Self { instance_data }
+    }
+
+    pub fn render(&self, canvas: &mut Canvas) {
+        let Instance {
+            pos,
+            scale,
+            radius,
+            color,
+            offset,
+            blur,
+            spread,
+            inset,
+        } = self.instance_data;
+        let transparent = Color::rgba(color.r, color.g, color.b, 0.0);
+        // Box gradients have the same radius on every corner
+        let corner_radius = radius.0.max(radius.1).max(radius.2).max(radius.3);
+        // The gradient fades out from `blur / 2` inside its edge to `blur / 2` outside of it
+        let feather = blur.max(1.0);
+
+        let mut rect = Path::new();
+        rect.rounded_rect_varying(
+            pos.x,
+            pos.y,
+            scale.width,
+            scale.height,
+            radius.0,
+            radius.1,
+            radius.2,
+            radius.3,
+        );
+
+        if inset {
+            let paint = Paint::box_gradient(
+                pos.x + offset.x + spread,
+                pos.y + offset.y + spread,
+                (scale.width - spread * 2.0).max(0.0),
+                (scale.height - spread * 2.0).max(0.0),
+                (corner_radius - spread).max(0.0),
+                feather,
+                transparent.into(),
+                color.into(),
+            );
+            canvas.fill_path(&rect, &paint);
+        } else {
+            let (x, y) = (pos.x + offset.x - spread, pos.y + offset.y - spread);
+            let (width, height) = (scale.width + spread * 2.0, scale.height + spread * 2.0);
+            let paint = Paint::box_gradient(
+                x,
+                y,
+                width,
+                height,
+                corner_radius + spread,
+                feather,
+                color.into(),
+                transparent.into(),
+            );
+
+            // Only around the rect, so it doesn't show through a translucent background
+            let mut path = Path::new();
+            path.rect(x - blur, y - blur, width + blur * 2.0, height + blur * 2.0);
+            path.rounded_rect_varying(
+                pos.x,
+                pos.y,
+                scale.width,
+                scale.height,
+                radius.0,
+                radius.1,
+                radius.2,
+                radius.3,
+            );
+            path.solidity(Solidity::Hole);
+            canvas.fill_path(&path, &paint);
+        }
+    }
+}
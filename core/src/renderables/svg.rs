@@ -1,12 +1,11 @@
 use super::types::Canvas;
 use crate::{
-    renderer::svg::{load_svg_path, SvgData},
-    Pos, Scale,
+    renderer::svg::{SvgData, SvgLoader, SvgPainter},
+    Color, Pos, Scale,
 };
 use derive_builder::Builder;
-use femtovg::Transform2D;
+use femtovg::{ImageId, RenderTarget};
 use std::collections::HashMap;
-use usvg::fontdb::Database;
 
 #[derive(Clone, Debug, PartialEq, Builder)]
 pub struct Instance {
@@ -14,6 +13,12 @@ pub struct Instance {
     pub pos: Pos,
     pub scale: Scale,
     pub dynamic_load_from: Option<String>,
+    /// The color of the fills and strokes that use `currentColor`
+    #[builder(default = "Color::BLACK")]
+    pub color: Color,
+    /// Draws every fill and stroke with `color`, keeping their opacity
+    #[builder(default)]
+    pub tint: bool,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -29,25 +34,35 @@ impl Svg {
                 scale,
                 name: name.into(),
                 dynamic_load_from: None,
+                color: Color::BLACK,
+                tint: false,
             },
         }
     }
 
-    pub fn render(&self, canvas: &mut Canvas, svgs: &mut HashMap<String, SvgData>) {
+    pub fn render(
+        &self,
+        canvas: &mut Canvas,
+        svgs: &mut HashMap<String, SvgData>,
+        svg_loader: &SvgLoader,
+        layers: &mut Vec<ImageId>,
+        target: RenderTarget,
+    ) {
         let Instance {
             pos,
             scale,
             dynamic_load_from,
+            color,
+            tint,
             ..
         } = self.instance_data.clone();
 
-        if svgs.get_mut(&self.instance_data.name).is_none() && dynamic_load_from.is_some() {
-            let svg_data = load_svg_path(dynamic_load_from.unwrap(), &Database::default());
-            svgs.insert(self.instance_data.name.clone(), svg_data);
-        }
-
         if svgs.get_mut(&self.instance_data.name).is_none() {
-            println!("error: svg not found {:?}", self.instance_data.name);
+            match dynamic_load_from {
+                // Drawn from the next frame, once the canvas has it
+                Some(path) => svg_loader.load_path(&self.instance_data.name, &path),
+                None => println!("error: svg not found {:?}", self.instance_data.name),
+            }
             return;
         }
 
@@ -61,38 +76,14 @@ impl Svg {
 
         canvas.scale(width / svg_data.scale.width, height / svg_data.scale.height);
 
-        for (path, fill, stroke, transform) in &mut svg_data.paths {
-            canvas.save();
-            // canvas.set_transform(
-            //     transform.a as f32,
-            //     transform.b as f32,
-            //     transform.c as f32,
-            //     transform.d as f32,
-            //     transform.e as f32,
-            //     transform.f as f32,
-            // );
-            let canvas_transform: Transform2D = Transform2D([
-                transform.sx,
-                transform.kx,
-                transform.ky,
-                transform.sy,
-                transform.tx,
-                transform.ty,
-            ]);
-            canvas.set_transform(&canvas_transform);
-
-            if let Some(fill) = fill {
-                fill.set_anti_alias(true);
-                canvas.fill_path(&path, &fill);
-            }
-
-            if let Some(stroke) = stroke {
-                stroke.set_anti_alias(true);
-                canvas.stroke_path(&path, &stroke);
-            }
-
-            canvas.restore();
+        SvgPainter {
+            canvas,
+            layers,
+            target,
+            color: color.into(),
+            tint,
         }
+        .draw(svg_data);
 
         canvas.restore();
     }
@@ -1,19 +1,23 @@
 use super::gl::{init_gl, init_gl_canvas};
-use super::svg::{load_svg_paths, SvgData};
+use super::image_cache::ImageCache;
+use super::image_loader::ImageTarget;
+use super::svg::{SvgData, SvgLoader, SvgTarget};
 use super::text::TextRenderer;
 use super::{Caches, RendererContext};
-use crate::font_cache::FontCache;
-use crate::renderables::Renderable;
-use crate::{node::Node, types::PixelSize};
-use crate::{AssetParams, ImgFilter};
+use crate::node::{Node, RenderItem};
+use crate::renderables::{backdrop_blur, BackdropBlur, Renderable};
+use crate::resources::Resources;
+use crate::types::{Clip, PixelSize, AABB};
 use femtovg::renderer::OpenGl;
-use femtovg::{Canvas, Color, ImageFlags, ImageId, ImageSource};
+use femtovg::{
+    Canvas, Color, CompositeOperation, ImageFilter, ImageFlags, ImageId, Paint, Path, PixelFormat,
+    RenderTarget, Solidity, Transform2D, Verb,
+};
 use glutin::api::egl;
 use glutin::api::egl::context::PossiblyCurrentContext;
 use glutin::api::egl::surface::Surface;
 use glutin::context::{PossiblyCurrentContextGlSurfaceAccessor, PossiblyCurrentGlContext};
 use glutin::surface::{GlSurface, WindowSurface};
-use image::DynamicImage;
 use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
 use std::any::Any;
 use std::collections::HashMap;
@@ -21,6 +25,9 @@ use std::fmt;
 use std::num::NonZeroU32;
 use std::sync::{Arc, RwLock};
 
+/// The widest gaussian blur femtovg draws, whose shader loops a fixed number of times.
+const MAX_BLUR_SIGMA: f32 = 8.0;
+
 pub struct GlCanvasContext {
     // egl context, surface
     pub gl_context: PossiblyCurrentContext,
@@ -28,73 +35,398 @@ pub struct GlCanvasContext {
     // femto canvas
     pub gl_canvas: Canvas<OpenGl>,
     // canvas images
-    pub images: HashMap<String, ImageId>,
+    pub images: ImageCache,
 }
 
 impl RendererContext for GlCanvasContext {}
 
-pub fn load_assets_to_canvas(
-    gl_canvas: &mut Canvas<OpenGl>,
-    assets: HashMap<String, AssetParams>,
-) -> HashMap<String, ImageId> {
-    let mut loaded_assets = HashMap::new();
+/// What a frame is drawn with.
+struct Frame<'a> {
+    canvas: &'a mut Canvas<OpenGl>,
+    images: &'a mut ImageCache,
+    image_target: &'a mut ImageTarget,
+    svgs: &'a mut HashMap<String, SvgData>,
+    svg_loader: &'a SvgLoader,
+    text_renderer: &'a mut TextRenderer,
+    layers: &'a mut Vec<ImageId>,
+    svg_layers: &'a mut Vec<ImageId>,
+    /// The image the frame is drawn onto when it has a backdrop blur, and its blurred copy
+    backdrop: Option<(ImageId, ImageId)>,
+    /// A scaled down copy of the backdrop, for blurs wider than femtovg blurs
+    scaled_backdrop: &'a mut Option<ImageId>,
+}
 
-    for (name, params) in assets.into_iter() {
-        let AssetParams { path, filter, blur } = params;
-        let image_r = image::open(path);
+impl Frame<'_> {
+    /// Draws `node` and its children onto `target`, which is the current render target.
+    /// `depth` is the number of layers `target` is nested in.
+    fn draw(&mut self, node: &Node, target: RenderTarget, depth: usize) {
+        for item in node.iter_renderables() {
+            match item {
+                RenderItem::Renderable(Renderable::BackdropBlur(blur), _, _) => {
+                    self.draw_backdrop_blur(blur, target)
+                }
+                RenderItem::Renderable(renderable, _, _) => {
+                    self.draw_renderable(renderable, target)
+                }
+                RenderItem::Group(group) => self.draw_group(group, target, depth),
+            }
+        }
+    }
+
+    /// Draws `node` and its children with its transform, onto a layer if it is one.
+    fn draw_group(&mut self, node: &Node, target: RenderTarget, depth: usize) {
+        self.canvas.save();
+        if let Some(transform) = node.transform.as_ref() {
+            self.canvas.set_transform(transform);
+        }
+        if node.is_layer() {
+            self.draw_layer(node, target, depth);
+        } else {
+            self.draw(node, target, depth);
+        }
+        self.canvas.restore();
+    }
+
+    /// Draws `node` and its children onto an offscreen image, and composites it onto `target`.
+    fn draw_layer(&mut self, node: &Node, target: RenderTarget, depth: usize) {
+        if node.opacity <= 0.0 {
+            return;
+        }
+        let Some(layer) = self.layer(depth) else {
+            // Better drawn without the opacity than not at all
+            self.draw(node, target, depth);
+            return;
+        };
 
-        if let Err(e) = image_r {
-            println!("Error while opening image {:?} error: {:?}", name, e);
-            continue;
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        self.canvas.set_render_target(RenderTarget::Image(layer));
+        self.canvas
+            .clear_rect(0, 0, width, height, Color::rgba(0, 0, 0, 0));
+        self.draw(node, RenderTarget::Image(layer), depth + 1);
+        if let Some(clip) = node.clip_shape.as_ref() {
+            self.erase_outside(clip, node.aabb);
         }
+        self.canvas.set_render_target(target);
 
-        let mut image = image_r.unwrap();
+        let (width, height) = (width as f32, height as f32);
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, width, height);
+        let paint = Paint::image(layer, 0.0, 0.0, width, height, 0.0, node.opacity);
+        // The transforms were applied while drawing onto the layer
+        self.canvas.save();
+        self.canvas.reset_transform();
+        self.canvas
+            .global_composite_operation(node.layout.composite_operation);
+        self.canvas.fill_path(&path, &paint);
+        self.canvas.restore();
+    }
 
-        if let Some(sigma) = blur {
-            image = image.blur(sigma);
+    /// Erases what was drawn onto the current render target outside of `clip`, of a node at `aabb`.
+    fn erase_outside(&mut self, clip: &Clip, aabb: AABB) {
+        let (width, height) = (self.canvas.width() as f32, self.canvas.height() as f32);
+        // The whole render target, in the coordinates of the node
+        let inverse = self.canvas.transform().inversed();
+        let mut path = Path::new();
+        for (i, (x, y)) in [(0.0, 0.0), (width, 0.0), (width, height), (0.0, height)]
+            .into_iter()
+            .enumerate()
+        {
+            let (x, y) = inverse.transform_point(x, y);
+            if i == 0 {
+                path.move_to(x, y);
+            } else {
+                path.line_to(x, y);
+            }
         }
+        path.close();
 
-        let buffer;
-        let img_src_r = match filter {
-            ImgFilter::RGB => ImageSource::try_from(&image),
-            ImgFilter::GRAY => {
-                //Temporary patch as gray scale image was not rendering
-                let gray_scale = image.grayscale().into_rgb8();
-                buffer = DynamicImage::ImageRgb8(gray_scale);
-                ImageSource::try_from(&buffer)
+        // with a hole in the shape of the clip
+        match clip {
+            Clip::RoundedRect(radius) => {
+                path.rounded_rect_varying(
+                    aabb.pos.x,
+                    aabb.pos.y,
+                    aabb.width(),
+                    aabb.height(),
+                    radius.0,
+                    radius.1,
+                    radius.2,
+                    radius.3,
+                );
+                path.solidity(Solidity::Hole);
+            }
+            Clip::Ellipse => {
+                let (rx, ry) = (aabb.width() / 2.0, aabb.height() / 2.0);
+                path.ellipse(aabb.pos.x + rx, aabb.pos.y + ry, rx, ry);
+                path.solidity(Solidity::Hole);
             }
+            Clip::Path(clip) => {
+                for verb in clip.verbs() {
+                    match verb {
+                        Verb::MoveTo(x, y) => {
+                            path.move_to(x, y);
+                            path.solidity(Solidity::Hole);
+                        }
+                        Verb::LineTo(x, y) => path.line_to(x, y),
+                        Verb::BezierTo(c1x, c1y, c2x, c2y, x, y) => {
+                            path.bezier_to(c1x, c1y, c2x, c2y, x, y)
+                        }
+                        Verb::Close => path.close(),
+                        Verb::Solid | Verb::Hole => (),
+                    }
+                }
+            }
+        }
+
+        self.canvas.save();
+        self.canvas
+            .global_composite_operation(CompositeOperation::DestinationOut);
+        self.canvas.fill_path(&path, &Paint::color(Color::black()));
+        self.canvas.restore();
+    }
+
+    /// The offscreen image for layers nested in `depth` other layers.
+    fn layer(&mut self, depth: usize) -> Option<ImageId> {
+        if let Some(layer) = self.layers.get(depth) {
+            return Some(*layer);
+        }
+
+        let layer = create_offscreen_image(self.canvas)?;
+        self.layers.push(layer);
+        Some(layer)
+    }
+
+    /// Fills the rect of `blur` with the blurred backdrop, onto `target`.
+    fn draw_backdrop_blur(&mut self, blur: &BackdropBlur, target: RenderTarget) {
+        let Some((backdrop, blurred)) = self.backdrop else {
+            return;
         };
+        let backdrop_blur::Instance {
+            pos,
+            scale,
+            radius,
+            sigma,
+        } = blur.instance_data;
+        let (width, height) = (self.canvas.width() as f32, self.canvas.height() as f32);
 
-        if let Err(e) = img_src_r {
-            println!("Error while creating image src {:?} error: {:?}", name, e);
-            continue;
+        // Wider blurs are blurred on a copy that is scaled down for them to fit, and scaled back up
+        let downscale = (sigma / MAX_BLUR_SIGMA).ceil().max(1.0);
+        let scaled = match downscale > 1.0 {
+            true => self.scale_backdrop(backdrop, downscale, target),
+            false => None,
+        };
+        let (downscale, source) = match scaled {
+            Some(scaled) => (downscale, scaled),
+            None => (1.0, backdrop),
+        };
+        self.canvas.filter_image(
+            blurred,
+            ImageFilter::GaussianBlur {
+                sigma: (sigma / downscale).min(MAX_BLUR_SIGMA),
+            },
+            source,
+        );
+
+        let mut path = Path::new();
+        path.rounded_rect_varying(
+            pos.x,
+            pos.y,
+            scale.width,
+            scale.height,
+            radius.0,
+            radius.1,
+            radius.2,
+            radius.3,
+        );
+        // The backdrop was drawn in the coordinates of the frame, so the shape is moved there
+        let path = transform_path(&path, &self.canvas.transform());
+        let paint = Paint::image(
+            blurred,
+            0.0,
+            0.0,
+            width * downscale,
+            height * downscale,
+            0.0,
+            1.0,
+        );
+        self.canvas.save();
+        self.canvas.reset_transform();
+        self.canvas.fill_path(&path, &paint);
+        self.canvas.restore();
+    }
+
+    /// Draws `backdrop` scaled down by `downscale` onto the top left of the scaled backdrop image,
+    /// then goes back to drawing onto `target`.
+    fn scale_backdrop(
+        &mut self,
+        backdrop: ImageId,
+        downscale: f32,
+        target: RenderTarget,
+    ) -> Option<ImageId> {
+        if self.scaled_backdrop.is_none() {
+            *self.scaled_backdrop = create_offscreen_image(self.canvas);
         }
+        let scaled = (*self.scaled_backdrop)?;
 
-        let img_src = img_src_r.unwrap();
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, width as f32, height as f32);
+        // Past the copy, its edges are stretched, so they don't blur into transparency
+        let paint = Paint::image(
+            backdrop,
+            0.0,
+            0.0,
+            width as f32 / downscale,
+            height as f32 / downscale,
+            0.0,
+            1.0,
+        );
+        self.canvas.save();
+        self.canvas.set_render_target(RenderTarget::Image(scaled));
+        self.canvas.reset_transform();
+        self.canvas.reset_scissor();
+        self.canvas
+            .clear_rect(0, 0, width, height, Color::rgba(0, 0, 0, 0));
+        self.canvas.fill_path(&path, &paint);
+        self.canvas.set_render_target(target);
+        self.canvas.restore();
+        Some(scaled)
+    }
 
-        let img_create_res = gl_canvas.create_image(img_src, ImageFlags::empty());
+    fn draw_renderable(&mut self, renderable: &Renderable, target: RenderTarget) {
+        let canvas = &mut *self.canvas;
+        match renderable {
+            Renderable::Rect(rect) => {
+                rect.render(canvas);
+            }
+            Renderable::Line(line) => {
+                line.render(canvas);
+            }
+            Renderable::Circle(circle) => {
+                circle.render(canvas);
+            }
+            Renderable::Image(image) => {
+                image.render(canvas, self.images, self.image_target);
+            }
+            Renderable::Svg(svg) => {
+                svg.render(canvas, self.svgs, self.svg_loader, self.svg_layers, target);
+            }
+            Renderable::Text(text) => {
+                text.render(canvas, self.text_renderer);
+            }
+            Renderable::RadialGradient(rg) => {
+                rg.render(canvas);
+            }
+            Renderable::Curve(curve) => {
+                curve.render(canvas);
+            }
+            Renderable::Shadow(shadow) => {
+                shadow.render(canvas);
+            }
+            Renderable::Arc(arc) => {
+                arc.render(canvas);
+            }
+            Renderable::Path(path) => {
+                path.render(canvas);
+            }
+            Renderable::Custom(custom) => {
+                custom.render(canvas);
+            }
+            // Drawn by the frame
+            Renderable::BackdropBlur(_) => (),
+        }
+    }
+}
 
-        if let Err(img_create_res) = img_create_res {
-            println!(
-                "Error while creating image {:?} error: {:?}",
-                name, img_create_res
-            );
-            continue;
+/// Creates an image as big as the canvas, to draw onto.
+pub(super) fn create_offscreen_image(canvas: &mut Canvas<OpenGl>) -> Option<ImageId> {
+    create_offscreen(canvas, PixelFormat::Rgba8)
+}
+
+/// A single channel offscreen image, which femtovg samples as that value in every channel,
+/// alpha included.
+pub(super) fn create_offscreen_grey_image(canvas: &mut Canvas<OpenGl>) -> Option<ImageId> {
+    create_offscreen(canvas, PixelFormat::Gray8)
+}
+
+fn create_offscreen(canvas: &mut Canvas<OpenGl>, format: PixelFormat) -> Option<ImageId> {
+    let image = canvas.create_image_empty(
+        canvas.width() as usize,
+        canvas.height() as usize,
+        format,
+        // Drawn upside down, with premultiplied alpha
+        ImageFlags::FLIP_Y | ImageFlags::PREMULTIPLIED,
+    );
+    match image {
+        Ok(image) => Some(image),
+        Err(e) => {
+            println!("Error while creating offscreen image error: {:?}", e);
+            None
         }
+    }
+}
 
-        let image_id = img_create_res.unwrap();
-        let x = gl_canvas.get_image(image_id).unwrap();
+/// `path` with `transform` applied to its points.
+fn transform_path(path: &Path, transform: &Transform2D) -> Path {
+    let mut transformed = Path::new();
+    for verb in path.verbs() {
+        match verb {
+            Verb::MoveTo(x, y) => {
+                let (x, y) = transform.transform_point(x, y);
+                transformed.move_to(x, y);
+            }
+            Verb::LineTo(x, y) => {
+                let (x, y) = transform.transform_point(x, y);
+                transformed.line_to(x, y);
+            }
+            Verb::BezierTo(c1x, c1y, c2x, c2y, x, y) => {
+                let (c1x, c1y) = transform.transform_point(c1x, c1y);
+                let (c2x, c2y) = transform.transform_point(c2x, c2y);
+                let (x, y) = transform.transform_point(x, y);
+                transformed.bezier_to(c1x, c1y, c2x, c2y, x, y);
+            }
+            Verb::Close => transformed.close(),
+            Verb::Solid => transformed.solidity(Solidity::Solid),
+            Verb::Hole => transformed.solidity(Solidity::Hole),
+        }
+    }
+    transformed
+}
+
+fn has_backdrop_blur(node: &Node) -> bool {
+    node.render_cache
+        .iter()
+        .flatten()
+        .any(|renderable| matches!(renderable, Renderable::BackdropBlur(_)))
+        || node.children.iter().any(has_backdrop_blur)
+}
 
-        loaded_assets.insert(name, image_id);
+/// Counts how many renderables of the tree draw each image.
+fn count_image_refs(node: &Node, refs: &mut HashMap<String, usize>) {
+    for renderable in node.render_cache.iter().flatten() {
+        if let Renderable::Image(image) = renderable {
+            *refs.entry(image.instance_data.name.clone()).or_default() += 1;
+        }
+    }
+    for child in node.children.iter() {
+        count_image_refs(child, refs);
     }
-    loaded_assets
 }
 
 pub struct CanvasRenderer {
-    fonts: cosmic_text::fontdb::Database,
+    resources: Resources,
     text_renderer: TextRenderer,
     assets: HashMap<String, ImageId>,
     svgs: HashMap<String, SvgData>,
+    svg_target: SvgTarget,
+    image_target: ImageTarget,
+    /// Offscreen images that layers are drawn onto, one per level of nesting
+    layers: Vec<ImageId>,
+    /// Offscreen images that the composited groups of SVGs are drawn onto
+    svg_layers: Vec<ImageId>,
+    /// Offscreen images for frames with a backdrop blur, see [`Frame`]
+    backdrop: Option<(ImageId, ImageId)>,
+    scaled_backdrop: Option<ImageId>,
 }
 
 unsafe impl Send for CanvasRenderer {}
@@ -107,10 +439,20 @@ impl fmt::Debug for CanvasRenderer {
     }
 }
 
+impl CanvasRenderer {
+    pub(crate) fn image_target(&self) -> &ImageTarget {
+        &self.image_target
+    }
+
+    pub(crate) fn svg_target(&self) -> &SvgTarget {
+        &self.svg_target
+    }
+}
+
 impl super::Renderer for CanvasRenderer {
     fn new<W: crate::window::Window>(w: Arc<RwLock<W>>) -> Self {
         let window = w.read().unwrap();
-        let fonts = window.fonts();
+        let resources = window.resources();
         // let (canvas_context, assets) = init_canvas_context(
         //     window.raw_display_handle(),
         //     window.raw_window_handle(),
@@ -118,26 +460,28 @@ impl super::Renderer for CanvasRenderer {
         //     scale_factor,
         //     window.assets(),
         // );
-        let text_renderer = TextRenderer::new(fonts.clone());
-        let svgs = window.svgs();
-        let loaded_svgs = load_svg_paths(svgs, fonts.clone());
+        let text_renderer = TextRenderer::new(resources.fonts().clone());
+        let scale_factor = window.scale_factor();
+        drop(window);
+        // Redraw once images finish decoding, or SVGs change
+        let wake: Arc<dyn Fn() + Send + Sync> = Arc::new(move || w.read().unwrap().wake());
+        let image_target = resources.images().attach(wake.clone(), scale_factor);
+        let svg_target = resources.svgs().attach(wake);
 
         Self {
-            fonts: fonts.clone(),
+            resources,
             text_renderer,
             assets: HashMap::new(),
-            svgs: loaded_svgs,
+            svgs: HashMap::new(),
+            svg_target,
+            image_target,
+            layers: vec![],
+            svg_layers: vec![],
+            backdrop: None,
+            scaled_backdrop: None,
         }
     }
 
-    fn resize(&mut self, _: u32, _: u32) {
-        // clear any saved canvas linked caches,
-        // for e.g the text renderer stored glyphs
-        // that were rendered by the canvas
-        // but during resizing canvas is re-initialized
-        self.text_renderer.clear();
-    }
-
     fn render(&mut self, node: &Node, _physical_size: PixelSize, ctx: &mut (dyn Any + 'static)) {
         let context = &mut ctx.downcast_mut::<GlCanvasContext>().unwrap();
         let canvas = &mut context.gl_canvas;
@@ -150,6 +494,10 @@ impl super::Renderer for CanvasRenderer {
             .make_current(surface)
             .expect("Failed to make newly created OpenGL context current");
 
+        context.images.next_frame();
+        self.image_target.upload(canvas, &mut context.images);
+        self.svg_target.apply(canvas, &mut self.svgs);
+
         canvas.clear_rect(
             0,
             0,
@@ -158,34 +506,89 @@ impl super::Renderer for CanvasRenderer {
             Color::rgba(0, 0, 0, 0),
         );
 
-        for (renderable, _, _) in node.iter_renderables() {
-            match renderable {
-                Renderable::Rect(rect) => {
-                    rect.render(canvas);
-                }
-                Renderable::Line(line) => {
-                    line.render(canvas);
-                }
-                Renderable::Circle(circle) => {
-                    circle.render(canvas);
-                }
-                Renderable::Image(image) => {
-                    image.render(canvas, &mut context.images);
-                }
-                Renderable::Svg(svg) => {
-                    svg.render(canvas, &mut self.svgs);
-                }
-                Renderable::Text(text) => {
-                    text.render(canvas, text_renderer);
-                }
-                Renderable::RadialGradient(rg) => {
-                    rg.render(canvas);
-                }
-                Renderable::Curve(curve) => {
-                    curve.render(canvas);
-                }
+        // Offscreen images are as big as the canvas, since renderables are positioned absolutely
+        let size = (canvas.width() as usize, canvas.height() as usize);
+        if self
+            .layers
+            .first()
+            .map(|layer| canvas.image_size(*layer).ok() != Some(size))
+            .unwrap_or(false)
+        {
+            for layer in self.layers.drain(..) {
+                canvas.delete_image(layer);
+            }
+        }
+        if self
+            .svg_layers
+            .first()
+            .map(|layer| canvas.image_size(*layer).ok() != Some(size))
+            .unwrap_or(false)
+        {
+            for layer in self.svg_layers.drain(..) {
+                canvas.delete_image(layer);
+            }
+        }
+        let backdrop_blur = has_backdrop_blur(node);
+        if let Some((backdrop, blurred)) = self.backdrop {
+            if !backdrop_blur || canvas.image_size(backdrop).ok() != Some(size) {
+                canvas.delete_image(backdrop);
+                canvas.delete_image(blurred);
+                self.backdrop = None;
             }
         }
+        if let Some(scaled) = self.scaled_backdrop {
+            if self.backdrop.is_none() || canvas.image_size(scaled).ok() != Some(size) {
+                canvas.delete_image(scaled);
+                self.scaled_backdrop = None;
+            }
+        }
+        if backdrop_blur && self.backdrop.is_none() {
+            self.backdrop = create_offscreen_image(canvas).zip(create_offscreen_image(canvas));
+        }
+
+        let target = match self.backdrop {
+            Some((backdrop, _)) => {
+                canvas.set_render_target(RenderTarget::Image(backdrop));
+                canvas.clear_rect(
+                    0,
+                    0,
+                    canvas.width(),
+                    canvas.height(),
+                    Color::rgba(0, 0, 0, 0),
+                );
+                RenderTarget::Image(backdrop)
+            }
+            None => RenderTarget::Screen,
+        };
+        let mut frame = Frame {
+            canvas,
+            images: &mut context.images,
+            image_target: &mut self.image_target,
+            svgs: &mut self.svgs,
+            svg_loader: self.resources.svgs(),
+            text_renderer,
+            layers: &mut self.layers,
+            svg_layers: &mut self.svg_layers,
+            backdrop: self.backdrop,
+            scaled_backdrop: &mut self.scaled_backdrop,
+        };
+        frame.draw(node, target, 0);
+
+        if let Some((backdrop, _)) = self.backdrop {
+            canvas.set_render_target(RenderTarget::Screen);
+            let (width, height) = (canvas.width() as f32, canvas.height() as f32);
+            let mut path = Path::new();
+            path.rect(0.0, 0.0, width, height);
+            canvas.fill_path(
+                &path,
+                &Paint::image(backdrop, 0.0, 0.0, width, height, 0.0, 1.0),
+            );
+        }
+
+        let mut refs = HashMap::new();
+        count_image_refs(node, &mut refs);
+        self.image_target
+            .collect(canvas, &mut context.images, &refs);
 
         // Tell renderer to execute all drawing commands
         canvas.flush();
@@ -199,7 +602,9 @@ impl super::Renderer for CanvasRenderer {
     /// This default is provided for tests, it should be overridden
     fn caches(&self) -> Caches {
         Caches {
-            font: Arc::new(RwLock::new(FontCache::new(self.fonts.clone()))),
+            font: self.resources.font_cache(),
+            images: self.resources.images().clone(),
+            svgs: self.resources.svgs().clone(),
         }
     }
 }
@@ -0,0 +1,172 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Duration;
+
+use image::codecs::gif::GifDecoder;
+use image::codecs::png::PngDecoder;
+use image::codecs::webp::WebPDecoder;
+use image::{AnimationDecoder, Frame, ImageFormat};
+
+use super::pixel_buffer::{BufferFormat, PixelBuffer};
+
+/// Frames with a shorter delay are shown for [`DEFAULT_DELAY`] instead, like browsers do, as many
+/// GIFs don't set one.
+const MIN_DELAY: Duration = Duration::from_millis(20);
+const DEFAULT_DELAY: Duration = Duration::from_millis(100);
+
+/// A frame of an animated image.
+#[derive(Debug, Clone)]
+pub struct ImageFrame {
+    pub pixels: PixelBuffer,
+    /// How long the frame is shown for
+    pub delay: Duration,
+}
+
+/// Decodes every frame of the GIF, APNG or animated WebP at `path`, with their delays. Other images
+/// are a single frame.
+pub(crate) fn decode_frames(path: &str) -> Result<Vec<ImageFrame>, String> {
+    let format = image::io::Reader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(|e| e.to_string())?
+        .format();
+    let reader = || {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|e| e.to_string())
+    };
+
+    let frames = match format {
+        Some(ImageFormat::Gif) => GifDecoder::new(reader()?)
+            .map_err(|e| e.to_string())?
+            .into_frames()
+            .collect_frames(),
+        Some(ImageFormat::Png) => {
+            let decoder = PngDecoder::new(reader()?).map_err(|e| e.to_string())?;
+            if !decoder.is_apng() {
+                return still(path);
+            }
+            decoder.apng().into_frames().collect_frames()
+        }
+        Some(ImageFormat::WebP) => {
+            let decoder = WebPDecoder::new(reader()?).map_err(|e| e.to_string())?;
+            if !decoder.has_animation() {
+                return still(path);
+            }
+            decoder.into_frames().collect_frames()
+        }
+        _ => return still(path),
+    }
+    .map_err(|e| e.to_string())?;
+
+    if frames.is_empty() {
+        return Err("Animation has no frames".to_string());
+    }
+    Ok(frames.into_iter().map(to_image_frame).collect())
+}
+
+fn still(path: &str) -> Result<Vec<ImageFrame>, String> {
+    let image = image::open(path).map_err(|e| e.to_string())?;
+    Ok(vec![ImageFrame {
+        pixels: PixelBuffer::from_image(&image),
+        delay: DEFAULT_DELAY,
+    }])
+}
+
+fn to_image_frame(frame: Frame) -> ImageFrame {
+    let delay = Duration::from(frame.delay());
+    let buffer = frame.into_buffer();
+    ImageFrame {
+        pixels: PixelBuffer::new(
+            buffer.width() as usize,
+            buffer.height() as usize,
+            BufferFormat::Rgba8,
+            buffer.into_raw(),
+        ),
+        delay: if delay < MIN_DELAY {
+            DEFAULT_DELAY
+        } else {
+            delay
+        },
+    }
+}
+
+/// Where the playback of an animation is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Playback {
+    /// The frame shown
+    pub frame: usize,
+    /// How long the frame has been shown for
+    pub elapsed: Duration,
+    /// How many times the animation played through
+    pub loops: u32,
+}
+
+impl Playback {
+    /// Moves `dt` forward through frames of `delays`. Returns `true` when this finished the last of
+    /// `loop_count` loops, after which it stays on the last frame. Loops forever when `loop_count` is `None`.
+    pub fn advance(&mut self, delays: &[Duration], dt: Duration, loop_count: Option<u32>) -> bool {
+        if self.is_finished(loop_count) || delays.iter().all(Duration::is_zero) {
+            return false;
+        }
+        self.frame = self.frame.min(delays.len() - 1);
+        self.elapsed += dt;
+
+        while self.elapsed >= delays[self.frame] {
+            self.elapsed -= delays[self.frame];
+            if self.frame + 1 < delays.len() {
+                self.frame += 1;
+                continue;
+            }
+            self.loops += 1;
+            if self.is_finished(loop_count) {
+                self.elapsed = Duration::ZERO;
+                return true;
+            }
+            self.frame = 0;
+        }
+        false
+    }
+
+    pub fn is_finished(&self, loop_count: Option<u32>) -> bool {
+        loop_count.map(|count| self.loops >= count).unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_playback() {
+        let delays = [ms(100), ms(50), ms(100)];
+        let mut playback = Playback::default();
+
+        assert!(!playback.advance(&delays, ms(99), None));
+        assert_eq!(playback.frame, 0);
+        assert!(!playback.advance(&delays, ms(1), None));
+        assert_eq!(playback.frame, 1);
+        // Skips frames when ticks are slow
+        assert!(!playback.advance(&delays, ms(160), None));
+        assert_eq!((playback.frame, playback.elapsed), (0, ms(10)));
+        assert_eq!(playback.loops, 1);
+    }
+
+    #[test]
+    fn test_playback_loop_count() {
+        let delays = [ms(100), ms(100)];
+        let mut playback = Playback::default();
+
+        assert!(!playback.advance(&delays, ms(250), Some(2)));
+        assert_eq!((playback.frame, playback.loops), (0, 1));
+        // Stops on the last frame
+        assert!(playback.advance(&delays, ms(1000), Some(2)));
+        assert_eq!((playback.frame, playback.loops), (1, 2));
+        assert!(playback.is_finished(Some(2)));
+        assert!(!playback.advance(&delays, ms(1000), Some(2)));
+        assert_eq!(playback.frame, 1);
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use femtovg::renderer::OpenGl;
+use femtovg::{Canvas, ImageId};
+
+/// Memory used by the images on the canvas, see [`ImageLoader#stats`][super::image_loader::ImageLoader#method.stats].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ImageStats {
+    /// Images uploaded to the canvas
+    pub count: usize,
+    /// Bytes of GPU memory they take, at 4 bytes a pixel
+    pub bytes: usize,
+    /// Images drawn by the nodes of the last frame, which are never evicted
+    pub in_use: usize,
+    /// Bytes that unused images are evicted down to, unlimited when `None`
+    pub budget: Option<usize>,
+}
+
+#[derive(Debug)]
+struct CachedImage {
+    id: ImageId,
+    bytes: usize,
+    /// Scale factor the image was made for
+    density: f32,
+    /// Frame the image was last drawn in
+    last_used: u64,
+}
+
+/// The images uploaded to the canvas, by name.
+#[derive(Debug, Default)]
+pub struct ImageCache {
+    entries: HashMap<String, CachedImage>,
+    frame: u64,
+}
+
+impl ImageCache {
+    /// Marks the image as used by the current frame.
+    pub fn get(&mut self, name: &str) -> Option<ImageId> {
+        let frame = self.frame;
+        self.entries.get_mut(name).map(|image| {
+            image.last_used = frame;
+            image.id
+        })
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    /// Scale factor of the variant of the image on the canvas, 1 when it has none.
+    pub fn density(&self, name: &str) -> f32 {
+        self.entries
+            .get(name)
+            .map(|image| image.density)
+            .unwrap_or(1.0)
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        canvas: &mut Canvas<OpenGl>,
+        name: String,
+        id: ImageId,
+        density: f32,
+    ) {
+        let bytes = canvas
+            .image_size(id)
+            .map(|(width, height)| width * height * 4)
+            .unwrap_or(0);
+        let image = CachedImage {
+            id,
+            bytes,
+            density,
+            last_used: self.frame,
+        };
+        if let Some(old) = self.entries.insert(name, image) {
+            canvas.delete_image(old.id);
+        }
+    }
+
+    pub(crate) fn remove(&mut self, canvas: &mut Canvas<OpenGl>, name: &str) -> bool {
+        match self.entries.remove(name) {
+            Some(image) => {
+                canvas.delete_image(image.id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Deletes the least recently used images that no node uses, until the cache fits in `budget`.
+    /// Returns the names of the deleted images.
+    pub(crate) fn evict(
+        &mut self,
+        canvas: &mut Canvas<OpenGl>,
+        refs: &HashMap<String, usize>,
+        budget: usize,
+    ) -> Vec<String> {
+        let entries = self
+            .entries
+            .iter()
+            .map(|(name, image)| (name.as_str(), image.bytes, image.last_used));
+        let evicted = lru_victims(entries, refs, budget);
+        for name in evicted.iter() {
+            self.remove(canvas, name);
+        }
+        evicted
+    }
+
+    /// Starts a new frame, for tracking when images were last used.
+    pub(crate) fn next_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    pub(crate) fn stats(&self, refs: &HashMap<String, usize>, budget: Option<usize>) -> ImageStats {
+        ImageStats {
+            count: self.entries.len(),
+            bytes: self.entries.values().map(|image| image.bytes).sum(),
+            in_use: self
+                .entries
+                .keys()
+                .filter(|name| refs.get(name.as_str()).copied().unwrap_or(0) > 0)
+                .count(),
+            budget,
+        }
+    }
+}
+
+/// Names of the images, given as `(name, bytes, last_used)`, to evict to fit in `budget`: the least
+/// recently used first, skipping the ones with references.
+fn lru_victims<'a>(
+    entries: impl Iterator<Item = (&'a str, usize, u64)>,
+    refs: &HashMap<String, usize>,
+    budget: usize,
+) -> Vec<String> {
+    let mut entries: Vec<_> = entries.collect();
+    let mut total: usize = entries.iter().map(|(_, bytes, _)| bytes).sum();
+    if total <= budget {
+        return vec![];
+    }
+
+    entries.sort_by_key(|(_, _, last_used)| *last_used);
+    let mut victims = vec![];
+    for (name, bytes, _) in entries {
+        if total <= budget {
+            break;
+        }
+        if refs.get(name).copied().unwrap_or(0) > 0 {
+            continue;
+        }
+        total -= bytes;
+        victims.push(name.to_string());
+    }
+    victims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_victims() {
+        let entries = [("a", 100, 3), ("b", 100, 1), ("c", 100, 2), ("d", 100, 0)];
+        let mut refs = HashMap::new();
+        refs.insert("d".to_string(), 2);
+
+        // Under budget
+        assert!(lru_victims(entries.iter().copied(), &refs, 400).is_empty());
+        // "d" is the oldest, but in use
+        assert_eq!(
+            lru_victims(entries.iter().copied(), &refs, 200),
+            vec!["b".to_string(), "c".to_string()]
+        );
+        // Images in use are kept, even over budget
+        assert_eq!(
+            lru_victims(entries.iter().copied(), &refs, 0),
+            vec!["b".to_string(), "c".to_string(), "a".to_string()]
+        );
+    }
+}
@@ -0,0 +1,714 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use crossbeam_channel::{unbounded, Receiver, Sender};
+use femtovg::renderer::OpenGl;
+use femtovg::{Canvas, ImageFlags, ImageSource};
+use image::DynamicImage;
+use imgref::ImgRef;
+
+use super::image_animation::{self, ImageFrame};
+use super::image_cache::{ImageCache, ImageStats};
+use super::image_pipeline;
+use super::pixel_buffer::PixelBuffer;
+use crate::event::ImageLoad;
+use crate::AssetParams;
+
+/// How far along the loading of an image is.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageStatus {
+    Loading,
+    /// Decoded, and uploaded to the canvas by the next frame
+    Loaded,
+    Failed(String),
+}
+
+type Waker = Arc<dyn Fn() + Send + Sync>;
+
+/// The frames of an animated image, uploaded one at a time.
+#[derive(Debug)]
+struct Animation {
+    frames: Vec<ImageFrame>,
+    density: f32,
+}
+
+#[derive(Debug)]
+enum Source {
+    Decoded(Arc<DynamicImage>),
+    Pixels(Arc<PixelBuffer>),
+}
+
+/// The pixels of an image, kept for the windows that draw it to upload them.
+#[derive(Debug)]
+struct Pixels {
+    source: Source,
+    density: f32,
+    /// Changes when the pixels do
+    generation: u64,
+    /// Windows with the image on their canvas
+    holders: HashSet<usize>,
+}
+
+/// A window whose canvas the images are uploaded to, see [`ImageTarget`].
+struct Target {
+    wake: Waker,
+    finished: Sender<ImageLoad>,
+    unloads: Sender<String>,
+    updated: Arc<AtomicBool>,
+    scale_factor: f32,
+    stats: ImageStats,
+}
+
+/// Decodes images on a pool of worker threads, so that large images don't stall the render thread.
+///
+/// There is one loader per application, shared by its windows through
+/// [`Resources`][crate::resources::Resources], so an image is decoded once however many windows
+/// draw it. Decoded images are uploaded to the canvas of each window that draws them at the start
+/// of its next frame. Once an image is decoded the windows are woken up, and the nodes that
+/// registered [`Register::ImageLoad`][crate::event::Register::ImageLoad] are sent an
+/// [`ImageLoad`][crate::event::ImageLoad] event.
+///
+/// Images are kept until they are [unloaded][ImageLoader#method.unload], or evicted when they take
+/// more than the [budget][ImageLoader#method.set_budget] of a window: evicted images are loaded
+/// again when they are drawn again. The decoded pixels are kept while the image is loaded, so that
+/// windows opened later upload it without decoding it again.
+///
+/// Each image is decoded from the [variant][AssetParams#method.variant] closest to the highest
+/// scale factor of the windows, and decoded again when it changes to one closer to another variant.
+///
+/// [Animated][AssetParams#method.animated] images keep all their frames in memory, and the frame
+/// drawn is updated on the canvas in place.
+///
+/// Images can also be made from pixels in memory with [`from_pixels`][ImageLoader#method.from_pixels],
+/// which are never evicted.
+#[derive(Clone)]
+pub struct ImageLoader {
+    jobs: Sender<(String, AssetParams)>,
+    status: Arc<RwLock<HashMap<String, ImageStatus>>>,
+    /// What each image was loaded from, to load it again after it was evicted
+    sources: Arc<RwLock<HashMap<String, AssetParams>>>,
+    /// Scale of the variant each image was last decoded from
+    densities: Arc<RwLock<HashMap<String, f32>>>,
+    /// The highest scale factor of the windows
+    scale_factor: Arc<RwLock<f32>>,
+    /// Where images with a blur, filter or transforms are cached
+    cache_dir: Arc<RwLock<Option<PathBuf>>>,
+    budget: Arc<RwLock<Option<usize>>>,
+    /// Pixels of the still images, decoded or made from pixels
+    pixels: Arc<RwLock<HashMap<String, Pixels>>>,
+    generation: Arc<AtomicU64>,
+    animations: Arc<RwLock<HashMap<String, Animation>>>,
+    /// Names of the images made from pixels
+    streams: Arc<RwLock<HashSet<String>>>,
+    targets: Arc<RwLock<HashMap<usize, Target>>>,
+    next_target: Arc<AtomicUsize>,
+}
+
+impl fmt::Debug for ImageLoader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ImageLoader")
+            .field("status", &self.status)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for ImageLoader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageLoader {
+    /// Starts the workers, which wake the windows after decoding each image.
+    pub(crate) fn new() -> Self {
+        let (jobs, jobs_receiver) = unbounded::<(String, AssetParams)>();
+        let loader = Self {
+            jobs,
+            status: Default::default(),
+            sources: Default::default(),
+            densities: Default::default(),
+            scale_factor: Arc::new(RwLock::new(1.0)),
+            cache_dir: Default::default(),
+            budget: Default::default(),
+            pixels: Default::default(),
+            generation: Default::default(),
+            animations: Default::default(),
+            streams: Default::default(),
+            targets: Default::default(),
+            next_target: Default::default(),
+        };
+
+        let workers = thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(2)
+            .clamp(1, 4);
+        for _ in 0..workers {
+            let jobs_receiver = jobs_receiver.clone();
+            let worker = Worker {
+                status: loader.status.clone(),
+                densities: loader.densities.clone(),
+                scale_factor: loader.scale_factor.clone(),
+                cache_dir: loader.cache_dir.clone(),
+                pixels: loader.pixels.clone(),
+                generation: loader.generation.clone(),
+                animations: loader.animations.clone(),
+                targets: loader.targets.clone(),
+            };
+
+            // Workers stop once every loader is dropped
+            thread::spawn(move || {
+                for (name, params) in jobs_receiver.iter() {
+                    worker.decode(name, params);
+                }
+            });
+        }
+
+        loader.set_disk_cache(image_pipeline::default_cache_dir());
+        loader
+    }
+
+    /// Queues the image to be decoded, unless it is already loaded or queued under `name`. An
+    /// image that failed to load is queued again.
+    pub fn load<S: Into<String>>(&self, name: S, params: AssetParams) {
+        let name = name.into();
+        {
+            let mut status = self.status.write().unwrap();
+            if matches!(
+                status.get(&name),
+                Some(ImageStatus::Loading | ImageStatus::Loaded)
+            ) {
+                return;
+            }
+            status.insert(name.clone(), ImageStatus::Loading);
+        }
+        self.sources
+            .write()
+            .unwrap()
+            .insert(name.clone(), params.clone());
+        let _ = self.jobs.send((name, params));
+    }
+
+    /// Loads the image again from where it was last loaded, if it is not loaded or queued.
+    pub fn reload(&self, name: &str) {
+        let params = self.sources.read().unwrap().get(name).cloned();
+        if let Some(params) = params {
+            self.load(name, params);
+        }
+    }
+
+    /// Deletes the image from the canvases on their next frame. It is loaded again if it is drawn again.
+    pub fn unload(&self, name: &str) {
+        self.status.write().unwrap().remove(name);
+        self.pixels.write().unwrap().remove(name);
+        self.streams.write().unwrap().remove(name);
+        self.animations.write().unwrap().remove(name);
+        for target in self.targets.read().unwrap().values() {
+            let _ = target.unloads.send(name.to_string());
+            (target.wake)();
+        }
+    }
+
+    /// Evict the least recently used images that no node draws, once the images of a window take
+    /// more than `bytes` of GPU memory. Unlimited when `None`, the default.
+    pub fn set_budget(&self, bytes: Option<usize>) {
+        *self.budget.write().unwrap() = bytes;
+    }
+
+    /// Cache images with a blur, filter or transforms in `dir`, instead of `$XDG_CACHE_HOME/mctk/images`.
+    /// Not cached when `None`. The oldest images are deleted in the background once the cache takes
+    /// more than 256 MiB, and any written more than 30 days ago.
+    pub fn set_disk_cache(&self, dir: Option<PathBuf>) {
+        if let Some(dir) = dir.clone() {
+            thread::spawn(move || {
+                image_pipeline::prune_cache(
+                    &dir,
+                    image_pipeline::MAX_CACHE_BYTES,
+                    image_pipeline::MAX_CACHE_AGE,
+                )
+            });
+        }
+        *self.cache_dir.write().unwrap() = dir;
+    }
+
+    /// Registers an image made from raw pixels under `name`, and returns a handle to update its pixels.
+    pub fn from_pixels<S: Into<String>>(&self, name: S, pixels: PixelBuffer) -> ImageHandle {
+        let name = name.into();
+        self.streams.write().unwrap().insert(name.clone());
+        self.status
+            .write()
+            .unwrap()
+            .insert(name.clone(), ImageStatus::Loaded);
+        let handle = ImageHandle {
+            name,
+            loader: self.clone(),
+        };
+        handle.push(pixels);
+        handle
+    }
+
+    /// Registers a decoded image under `name`, see [`from_pixels`][ImageLoader#method.from_pixels].
+    pub fn from_image<S: Into<String>>(&self, name: S, image: &DynamicImage) -> ImageHandle {
+        self.from_pixels(name, PixelBuffer::from_image(image))
+    }
+
+    /// Memory used by the images on the canvases of the windows, as of their last frame.
+    pub fn stats(&self) -> ImageStats {
+        let budget = *self.budget.read().unwrap();
+        self.targets.read().unwrap().values().fold(
+            ImageStats {
+                budget,
+                ..Default::default()
+            },
+            |total, target| ImageStats {
+                count: total.count + target.stats.count,
+                bytes: total.bytes + target.stats.bytes,
+                in_use: total.in_use + target.stats.in_use,
+                budget,
+            },
+        )
+    }
+
+    /// `None` if the image was never queued.
+    pub fn status(&self, name: &str) -> Option<ImageStatus> {
+        self.status.read().unwrap().get(name).cloned()
+    }
+
+    /// Sends the [`ImageLoad`] of `name` again, to the nodes made after it finished loading.
+    /// Nothing is sent while it is loading.
+    pub(crate) fn replay(&self, name: &str) {
+        let result = match self.status(name) {
+            Some(ImageStatus::Loaded) => Ok(()),
+            Some(ImageStatus::Failed(e)) => Err(e),
+            _ => return,
+        };
+        let frame_delays = self
+            .animations
+            .read()
+            .unwrap()
+            .get(name)
+            .map(|animation| animation.frames.iter().map(|frame| frame.delay).collect())
+            .unwrap_or_default();
+        let load = ImageLoad {
+            name: name.to_string(),
+            result,
+            frame_delays,
+        };
+        for target in self.targets.read().unwrap().values() {
+            let _ = target.finished.send(load.clone());
+            (target.wake)();
+        }
+    }
+
+    /// Uploads the images to the canvas of a window, which `wake` wakes up.
+    pub(crate) fn attach(&self, wake: Waker, scale_factor: f32) -> ImageTarget {
+        let id = self.next_target.fetch_add(1, Ordering::SeqCst);
+        let (finished_sender, finished) = unbounded();
+        let (unloads_sender, unloads) = unbounded();
+        let updated: Arc<AtomicBool> = Default::default();
+        let target = Target {
+            wake,
+            finished: finished_sender,
+            unloads: unloads_sender,
+            updated: updated.clone(),
+            scale_factor,
+            stats: Default::default(),
+        };
+        self.targets.write().unwrap().insert(id, target);
+        self.update_scale_factor();
+
+        ImageTarget {
+            id,
+            loader: self.clone(),
+            finished,
+            unloads,
+            updated,
+            uploaded: HashMap::new(),
+            requested: HashSet::new(),
+            shown: HashMap::new(),
+        }
+    }
+
+    fn detach(&self, id: usize) {
+        self.targets.write().unwrap().remove(&id);
+        for pixels in self.pixels.write().unwrap().values_mut() {
+            pixels.holders.remove(&id);
+        }
+        self.update_scale_factor();
+    }
+
+    /// Decodes the images again whose closest variant is a different one at the highest scale
+    /// factor of the windows. The images decoded before stay on the canvases until they are replaced.
+    fn update_scale_factor(&self) {
+        let scale_factor = self
+            .targets
+            .read()
+            .unwrap()
+            .values()
+            .map(|target| target.scale_factor)
+            .reduce(f32::max);
+        let Some(scale_factor) = scale_factor else {
+            return;
+        };
+        {
+            let mut current = self.scale_factor.write().unwrap();
+            if *current == scale_factor {
+                return;
+            }
+            *current = scale_factor;
+        }
+
+        let sources = self.sources.read().unwrap().clone();
+        let densities = self.densities.read().unwrap().clone();
+        for (name, params) in sources {
+            let Some(density) = densities.get(&name) else {
+                // Not decoded yet, so it will be decoded at the new scale factor
+                continue;
+            };
+            if !matches!(self.status(&name), Some(ImageStatus::Loaded)) {
+                continue;
+            }
+            if params.select(scale_factor).0 != *density {
+                self.status
+                    .write()
+                    .unwrap()
+                    .insert(name.clone(), ImageStatus::Loading);
+                let _ = self.jobs.send((name, params));
+            }
+        }
+    }
+
+    fn next_generation(&self) -> u64 {
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+}
+
+/// What the workers decode images into.
+struct Worker {
+    status: Arc<RwLock<HashMap<String, ImageStatus>>>,
+    densities: Arc<RwLock<HashMap<String, f32>>>,
+    scale_factor: Arc<RwLock<f32>>,
+    cache_dir: Arc<RwLock<Option<PathBuf>>>,
+    pixels: Arc<RwLock<HashMap<String, Pixels>>>,
+    generation: Arc<AtomicU64>,
+    animations: Arc<RwLock<HashMap<String, Animation>>>,
+    targets: Arc<RwLock<HashMap<usize, Target>>>,
+}
+
+impl Worker {
+    fn decode(&self, name: String, params: AssetParams) {
+        let (density, path) = params.select(*self.scale_factor.read().unwrap());
+        let mut frame_delays = vec![];
+        let decoded = if params.animated {
+            image_animation::decode_frames(&path).map(|frames| {
+                frame_delays = frames.iter().map(|frame| frame.delay).collect();
+                let animation = Animation { frames, density };
+                self.animations
+                    .write()
+                    .unwrap()
+                    .insert(name.clone(), animation);
+            })
+        } else {
+            let cache_dir = self.cache_dir.read().unwrap().clone();
+            image_pipeline::load(&path, density, &params, cache_dir.as_deref()).map(|image| {
+                let pixels = Pixels {
+                    source: Source::Decoded(Arc::new(image)),
+                    density,
+                    generation: self.generation.fetch_add(1, Ordering::SeqCst) + 1,
+                    holders: HashSet::new(),
+                };
+                self.pixels.write().unwrap().insert(name.clone(), pixels);
+            })
+        };
+        let result = match decoded {
+            Ok(()) => {
+                self.densities
+                    .write()
+                    .unwrap()
+                    .insert(name.clone(), density);
+                self.status
+                    .write()
+                    .unwrap()
+                    .insert(name.clone(), ImageStatus::Loaded);
+                Ok(())
+            }
+            Err(e) => {
+                println!("Error while loading image {:?} error: {:?}", name, e);
+                self.status
+                    .write()
+                    .unwrap()
+                    .insert(name.clone(), ImageStatus::Failed(e.clone()));
+                Err(e)
+            }
+        };
+
+        let load = ImageLoad {
+            name,
+            result,
+            frame_delays,
+        };
+        for target in self.targets.read().unwrap().values() {
+            let _ = target.finished.send(load.clone());
+            (target.wake)();
+        }
+    }
+}
+
+/// The images of the canvas of one window, uploaded from the [`ImageLoader`] of its application.
+///
+/// An image is uploaded once it is drawn, and again whenever its pixels change.
+pub(crate) struct ImageTarget {
+    id: usize,
+    loader: ImageLoader,
+    /// Loads that finished since they were last taken, read by the UI
+    finished: Receiver<ImageLoad>,
+    unloads: Receiver<String>,
+    /// Pixels were pushed since this was last taken, read by the UI
+    updated: Arc<AtomicBool>,
+    /// Generation of the pixels of each still image on the canvas
+    uploaded: HashMap<String, u64>,
+    /// Images drawn before they were on the canvas
+    requested: HashSet<String>,
+    /// The frame of each animated image on the canvas
+    shown: HashMap<String, usize>,
+}
+
+impl fmt::Debug for ImageTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ImageTarget")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for ImageTarget {
+    fn drop(&mut self) {
+        self.loader.detach(self.id);
+    }
+}
+
+impl ImageTarget {
+    /// Decodes the images again whose closest variant is a different one at `scale_factor`, if
+    /// the window has the highest scale factor.
+    pub(crate) fn set_scale_factor(&self, scale_factor: f32) {
+        if let Some(target) = self.loader.targets.write().unwrap().get_mut(&self.id) {
+            target.scale_factor = scale_factor;
+        }
+        self.loader.update_scale_factor();
+    }
+
+    /// Whether pixels were pushed to a handle since this was last called.
+    pub(crate) fn take_updated(&self) -> bool {
+        self.updated.swap(false, Ordering::SeqCst)
+    }
+
+    /// The loads that finished since this was last called.
+    pub(crate) fn take_finished(&self) -> Vec<ImageLoad> {
+        self.finished.try_iter().collect()
+    }
+
+    /// Uploads the image `name`, which is drawn but not on the canvas, from `params` or where it
+    /// was last loaded from. Images that failed to load are not queued again.
+    pub(crate) fn request(&mut self, name: &str, params: Option<AssetParams>) {
+        self.requested.insert(name.to_string());
+        let loader = &self.loader;
+        if loader.pixels.read().unwrap().contains_key(name) {
+            // Decoded for another window, and uploaded on the next frame
+            if let Some(target) = loader.targets.read().unwrap().get(&self.id) {
+                (target.wake)();
+            }
+            return;
+        }
+
+        // Loading, failed, or an animation, which is shown by `show_frame`
+        if loader.status(name).is_some() {
+            return;
+        }
+        let params = params.or_else(|| loader.sources.read().unwrap().get(name).cloned());
+        if let Some(params) = params {
+            loader.load(name, params);
+        }
+    }
+
+    /// Deletes the images unloaded, and uploads the images drawn whose pixels changed, since this
+    /// was last called.
+    pub(crate) fn upload(&mut self, canvas: &mut Canvas<OpenGl>, images: &mut ImageCache) {
+        for name in self.unloads.try_iter() {
+            images.remove(canvas, &name);
+            self.uploaded.remove(&name);
+            self.shown.remove(&name);
+        }
+
+        let mut pixels = self.loader.pixels.write().unwrap();
+        for (name, image) in pixels.iter_mut() {
+            let uploaded = self.uploaded.get(name).copied();
+            if uploaded == Some(image.generation) {
+                continue;
+            }
+            if uploaded.is_none() && !self.requested.remove(name) {
+                continue;
+            }
+
+            let result = match &image.source {
+                // Decoded again for another window, with the same pixels as on the canvas
+                Source::Decoded(_)
+                    if images.contains(name) && images.density(name) == image.density =>
+                {
+                    Ok(())
+                }
+                Source::Decoded(decoded) => ImageSource::try_from(decoded.as_ref())
+                    .map_err(|e| format!("{:?}", e))
+                    .and_then(|src| {
+                        canvas
+                            .create_image(src, ImageFlags::empty())
+                            .map_err(|e| format!("{:?}", e))
+                    })
+                    .map(|image_id| images.insert(canvas, name.clone(), image_id, image.density)),
+                Source::Pixels(buffer) => {
+                    upload_pixels(canvas, images, name.clone(), buffer, image.density)
+                }
+            };
+            match result {
+                Ok(()) => {
+                    self.uploaded.insert(name.clone(), image.generation);
+                    image.holders.insert(self.id);
+                }
+                Err(e) => println!("Error while creating image {:?} error: {:?}", name, e),
+            }
+        }
+    }
+
+    /// Puts `frame` of the animated image on the canvas, if it is decoded and not shown already.
+    /// There is one texture per name, so nodes drawing other frames of it replace the frame.
+    pub(crate) fn show_frame(
+        &mut self,
+        canvas: &mut Canvas<OpenGl>,
+        images: &mut ImageCache,
+        name: &str,
+        frame: usize,
+    ) {
+        let animations = self.loader.animations.read().unwrap();
+        let Some(animation) = animations.get(name) else {
+            return;
+        };
+        let frame = frame.min(animation.frames.len() - 1);
+        if self.shown.get(name) == Some(&frame) && images.contains(name) {
+            return;
+        }
+        let pixels = &animation.frames[frame].pixels;
+        match upload_pixels(canvas, images, name.to_string(), pixels, animation.density) {
+            Ok(()) => {
+                self.shown.insert(name.to_string(), frame);
+            }
+            Err(e) => println!("Error while updating image {:?} error: {:?}", name, e),
+        }
+    }
+
+    /// Evicts images over the budget, given how many nodes draw each image, and updates the stats.
+    pub(crate) fn collect(
+        &mut self,
+        canvas: &mut Canvas<OpenGl>,
+        images: &mut ImageCache,
+        refs: &HashMap<String, usize>,
+    ) {
+        let loader = &self.loader;
+        let budget = *loader.budget.read().unwrap();
+        if let Some(budget) = budget {
+            // Images made from pixels can't be loaded again
+            let mut refs = refs.clone();
+            for name in loader.streams.read().unwrap().iter() {
+                *refs.entry(name.clone()).or_default() += 1;
+            }
+            let evicted = images.evict(canvas, &refs, budget);
+            let mut pixels = loader.pixels.write().unwrap();
+            let mut status = loader.status.write().unwrap();
+            for name in evicted.iter() {
+                self.uploaded.remove(name);
+                self.shown.remove(name);
+                // The pixels are dropped once no window has the image
+                let Some(image) = pixels.get_mut(name) else {
+                    continue;
+                };
+                image.holders.remove(&self.id);
+                if image.holders.is_empty() {
+                    pixels.remove(name);
+                    status.remove(name);
+                }
+            }
+        }
+        let stats = images.stats(refs, budget);
+        if let Some(target) = loader.targets.write().unwrap().get_mut(&self.id) {
+            target.stats = stats;
+        }
+    }
+}
+
+/// Updates the pixels of an image made with [`ImageLoader#from_pixels`], in place while its size stays the same.
+#[derive(Clone, Debug)]
+pub struct ImageHandle {
+    name: String,
+    loader: ImageLoader,
+}
+
+impl ImageHandle {
+    /// The name to draw the image with.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Replaces the pixels on the next frame. Pixels pushed faster than frames are drawn are dropped.
+    pub fn push(&self, pixels: PixelBuffer) {
+        let loader = &self.loader;
+        if !loader.streams.read().unwrap().contains(&self.name) {
+            // Unloaded
+            return;
+        }
+        let generation = loader.next_generation();
+        {
+            let mut images = loader.pixels.write().unwrap();
+            let holders = images
+                .remove(&self.name)
+                .map(|image| image.holders)
+                .unwrap_or_default();
+            let image = Pixels {
+                source: Source::Pixels(Arc::new(pixels)),
+                density: 1.0,
+                generation,
+                holders,
+            };
+            images.insert(self.name.clone(), image);
+        }
+        for target in loader.targets.read().unwrap().values() {
+            target.updated.store(true, Ordering::SeqCst);
+            (target.wake)();
+        }
+    }
+}
+
+/// Updates the image on the canvas, or creates it when its size changed.
+fn upload_pixels(
+    canvas: &mut Canvas<OpenGl>,
+    images: &mut ImageCache,
+    name: String,
+    pixels: &PixelBuffer,
+    density: f32,
+) -> Result<(), String> {
+    let rgba = pixels.to_rgba()?;
+    let src = ImgRef::new(&rgba, pixels.width, pixels.height);
+
+    if let Some(id) = images.get(&name) {
+        if canvas.image_size(id).ok() == Some((pixels.width, pixels.height)) {
+            return canvas
+                .update_image(id, src, 0, 0)
+                .map_err(|e| format!("{:?}", e));
+        }
+    }
+    let id = canvas
+        .create_image(src, ImageFlags::empty())
+        .map_err(|e| format!("{:?}", e))?;
+    images.insert(canvas, name, id, density);
+    Ok(())
+}
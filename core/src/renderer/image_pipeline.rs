@@ -0,0 +1,473 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use sha2::{Digest, Sha256};
+
+use crate::{AssetParams, ImgFilter, ImgTransform};
+
+/// Decodes the image at `path`, one of the variants of `params` made for `density`, and applies its
+/// blur, filter and transforms.
+///
+/// Images with any of those are cached in `cache_dir`, keyed by the file, its modification time and
+/// the transforms, so they are only transformed once.
+pub(crate) fn load(
+    path: &str,
+    density: f32,
+    params: &AssetParams,
+    cache_dir: Option<&Path>,
+) -> Result<DynamicImage, String> {
+    let derived = params.blur.is_some()
+        || matches!(params.filter, ImgFilter::GRAY)
+        || !params.transforms.is_empty();
+    let cached = match cache_dir {
+        Some(dir) if derived => cache_key(path, density, params).map(|key| dir.join(key)),
+        _ => None,
+    };
+
+    if let Some(cached) = cached.as_ref() {
+        if let Ok(image) = image::open(cached) {
+            return Ok(DynamicImage::ImageRgba8(image.into_rgba8()));
+        }
+    }
+
+    let image = image::open(path).map_err(|e| e.to_string())?;
+    let image = DynamicImage::ImageRgba8(apply(image, density, params));
+
+    if let Some(cached) = cached {
+        if let Err(e) = write_cached(&cached, &image) {
+            println!("Error while caching image {:?} error: {:?}", path, e);
+        }
+    }
+    Ok(image)
+}
+
+/// Cached images are pruned down to this size when a loader starts using the cache.
+pub(crate) const MAX_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+/// Cached images written longer ago than this are pruned.
+pub(crate) const MAX_CACHE_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// The default directory of derived images, `$XDG_CACHE_HOME/mctk/images` or `~/.cache/mctk/images`.
+pub(crate) fn default_cache_dir() -> Option<PathBuf> {
+    let cache_home = std::env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))?;
+    Some(cache_home.join("mctk").join("images"))
+}
+
+/// Name of the cached file for the image at `path` after `params`, `None` if the file can't be read.
+fn cache_key(path: &str, density: f32, params: &AssetParams) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    let path = fs::canonicalize(path).ok()?;
+
+    let mut key = CacheKey::default();
+    key.bytes(path.to_string_lossy().as_bytes());
+    key.u64(modified.as_secs());
+    key.u32(modified.subsec_nanos());
+    key.u64(metadata.len());
+    key.params(density, params);
+    Some(format!("{}.png", key.digest()))
+}
+
+/// The explicit serialization of what a cached image is made from, so that its name stays the same
+/// across builds and versions of Rust. Every field has a fixed size, and the strings are prefixed by
+/// their lengths, so that different inputs can't serialize the same.
+#[derive(Default)]
+struct CacheKey(Vec<u8>);
+
+impl CacheKey {
+    /// Bump to drop the images cached by older versions, when the transforms change.
+    const VERSION: u32 = 1;
+
+    fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn f32(&mut self, value: f32) {
+        self.u32(value.to_bits());
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.u64(bytes.len() as u64);
+        self.0.extend_from_slice(bytes);
+    }
+
+    fn params(&mut self, density: f32, params: &AssetParams) {
+        self.u32(Self::VERSION);
+        self.f32(density);
+        self.u8(match params.filter {
+            ImgFilter::RGB => 0,
+            ImgFilter::GRAY => 1,
+        });
+        match params.blur {
+            None => self.u8(0),
+            Some(sigma) => {
+                self.u8(1);
+                self.f32(sigma);
+            }
+        }
+        self.u64(params.transforms.len() as u64);
+        for transform in params.transforms.iter() {
+            match *transform {
+                ImgTransform::Brightness(amount) => {
+                    self.u8(0);
+                    self.f32(amount);
+                }
+                ImgTransform::Contrast(amount) => {
+                    self.u8(1);
+                    self.f32(amount);
+                }
+                ImgTransform::Tint(color, amount) => {
+                    self.u8(2);
+                    for channel in [color.r, color.g, color.b, color.a, amount] {
+                        self.f32(channel);
+                    }
+                }
+                ImgTransform::Sepia(amount) => {
+                    self.u8(3);
+                    self.f32(amount);
+                }
+                ImgTransform::Crop {
+                    x,
+                    y,
+                    width,
+                    height,
+                } => {
+                    self.u8(4);
+                    for value in [x, y, width, height] {
+                        self.u32(value);
+                    }
+                }
+                ImgTransform::Resize { width, height } => {
+                    self.u8(5);
+                    self.u32(width);
+                    self.u32(height);
+                }
+                ImgTransform::RoundedMask(radius) => {
+                    self.u8(6);
+                    self.f32(radius);
+                }
+                ImgTransform::Blur(sigma) => {
+                    self.u8(7);
+                    self.f32(sigma);
+                }
+            }
+        }
+    }
+
+    /// SHA-256 of the serialization, in hex.
+    fn digest(&self) -> String {
+        Sha256::digest(&self.0)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+/// Deletes the cached images last written more than `max_age` ago, then the oldest ones until the
+/// rest take at most `max_bytes`.
+pub(crate) fn prune_cache(dir: &Path, max_bytes: u64, max_age: Duration) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let files = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let metadata = entry.metadata().ok()?;
+            if !metadata.is_file() {
+                return None;
+            }
+            Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+        })
+        .collect();
+    for path in to_prune(files, SystemTime::now(), max_bytes, max_age) {
+        if let Err(e) = fs::remove_file(&path) {
+            println!("Error while pruning cached image {:?} error: {:?}", path, e);
+        }
+    }
+}
+
+/// Which of the `(path, size, modified)` files to delete, see [`prune_cache`].
+fn to_prune(
+    mut files: Vec<(PathBuf, u64, SystemTime)>,
+    now: SystemTime,
+    max_bytes: u64,
+    max_age: Duration,
+) -> Vec<PathBuf> {
+    // Newest first
+    files.sort_by(|a, b| b.2.cmp(&a.2));
+    let mut kept = 0;
+    files
+        .into_iter()
+        .filter_map(|(path, size, modified)| {
+            // Written in the future counts as new
+            let age = now.duration_since(modified).unwrap_or_default();
+            kept += size;
+            if age <= max_age && kept <= max_bytes {
+                None
+            } else {
+                Some(path)
+            }
+        })
+        .collect()
+}
+
+fn write_cached(cached: &Path, image: &DynamicImage) -> Result<(), String> {
+    if let Some(dir) = cached.parent() {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    // Written next to it first, so that other processes never read half a file
+    let partial = cached.with_extension("png.partial");
+    image
+        .save_with_format(&partial, ImageFormat::Png)
+        .map_err(|e| e.to_string())?;
+    fs::rename(&partial, cached).map_err(|e| e.to_string())
+}
+
+/// Applies the blur and filter of `params`, then its transforms in order. Sizes are in pixels of the
+/// 1x variant, and scaled by `density`.
+fn apply(mut image: DynamicImage, density: f32, params: &AssetParams) -> RgbaImage {
+    if let Some(sigma) = params.blur {
+        image = image.blur(sigma * density);
+    }
+
+    // femtovg only takes 8 bit RGB(A) and grey images, and grey was not rendering
+    let mut image = match params.filter {
+        ImgFilter::RGB => image.into_rgba8(),
+        ImgFilter::GRAY => image.grayscale().into_rgba8(),
+    };
+
+    let px = |size: u32| (size as f32 * density).round() as u32;
+    for transform in params.transforms.iter() {
+        image = match *transform {
+            ImgTransform::Brightness(amount) => map_pixels(image, |p| brightness(p, amount)),
+            ImgTransform::Contrast(amount) => map_pixels(image, |p| contrast(p, amount)),
+            ImgTransform::Tint(color, amount) => {
+                let color = [color.r, color.g, color.b];
+                map_pixels(image, |p| tint(p, color, amount))
+            }
+            ImgTransform::Sepia(amount) => map_pixels(image, |p| sepia(p, amount)),
+            ImgTransform::Crop {
+                x,
+                y,
+                width,
+                height,
+            } => image::imageops::crop_imm(&image, px(x), px(y), px(width), px(height)).to_image(),
+            ImgTransform::Resize { width, height } => image::imageops::resize(
+                &image,
+                px(width).max(1),
+                px(height).max(1),
+                FilterType::Lanczos3,
+            ),
+            ImgTransform::RoundedMask(radius) => rounded_mask(image, radius * density),
+            ImgTransform::Blur(sigma) => image::imageops::blur(&image, sigma * density),
+        };
+    }
+    image
+}
+
+fn map_pixels(mut image: RgbaImage, f: impl Fn(Rgba<u8>) -> Rgba<u8>) -> RgbaImage {
+    for pixel in image.pixels_mut() {
+        *pixel = f(*pixel);
+    }
+    image
+}
+
+fn channel(c: f32) -> u8 {
+    c.round().clamp(0.0, 255.0) as u8
+}
+
+/// Maps the color channels of the pixel, keeping its alpha.
+fn map_rgb(pixel: Rgba<u8>, f: impl Fn(f32) -> f32) -> Rgba<u8> {
+    let [r, g, b, a] = pixel.0;
+    Rgba([
+        channel(f(r as f32)),
+        channel(f(g as f32)),
+        channel(f(b as f32)),
+        a,
+    ])
+}
+
+fn brightness(pixel: Rgba<u8>, amount: f32) -> Rgba<u8> {
+    map_rgb(pixel, |c| c + amount * 255.0)
+}
+
+fn contrast(pixel: Rgba<u8>, amount: f32) -> Rgba<u8> {
+    map_rgb(pixel, |c| (c - 127.5) * amount + 127.5)
+}
+
+fn tint(pixel: Rgba<u8>, color: [f32; 3], amount: f32) -> Rgba<u8> {
+    let [r, g, b, a] = pixel.0;
+    let mix = |c: u8, t: f32| channel(c as f32 + (t - c as f32) * amount);
+    Rgba([mix(r, color[0]), mix(g, color[1]), mix(b, color[2]), a])
+}
+
+fn sepia(pixel: Rgba<u8>, amount: f32) -> Rgba<u8> {
+    let [r, g, b, a] = pixel.0;
+    let (r, g, b) = (r as f32, g as f32, b as f32);
+    let mix = |c: f32, s: f32| channel(c + (s - c) * amount);
+    Rgba([
+        mix(r, 0.393 * r + 0.769 * g + 0.189 * b),
+        mix(g, 0.349 * r + 0.686 * g + 0.168 * b),
+        mix(b, 0.272 * r + 0.534 * g + 0.131 * b),
+        a,
+    ])
+}
+
+/// Makes the corners outside of a rounded rectangle of `radius` transparent, antialiased over a pixel.
+fn rounded_mask(mut image: RgbaImage, radius: f32) -> RgbaImage {
+    let (width, height) = (image.width() as f32, image.height() as f32);
+    let radius = radius.min(width / 2.0).min(height / 2.0);
+    if radius <= 0.0 {
+        return image;
+    }
+
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let coverage = corner_coverage(x as f32 + 0.5, y as f32 + 0.5, width, height, radius);
+        pixel.0[3] = channel(pixel.0[3] as f32 * coverage);
+    }
+    image
+}
+
+/// How much of the pixel centered at `(x, y)` is inside the rounded rectangle.
+fn corner_coverage(x: f32, y: f32, width: f32, height: f32, radius: f32) -> f32 {
+    // Distance from the center of the nearest corner's circle, when in a corner
+    let dx = (radius - x).max(x - (width - radius)).max(0.0);
+    let dy = (radius - y).max(y - (height - radius)).max(0.0);
+    if dx == 0.0 || dy == 0.0 {
+        return 1.0;
+    }
+    let distance = (dx * dx + dy * dy).sqrt();
+    (radius - distance + 0.5).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pixel_transforms() {
+        let grey = Rgba([100, 100, 100, 50]);
+        assert_eq!(brightness(grey, 0.2), Rgba([151, 151, 151, 50]));
+        assert_eq!(brightness(grey, -1.0), Rgba([0, 0, 0, 50]));
+        assert_eq!(contrast(grey, 1.0), grey);
+        assert_eq!(
+            contrast(Rgba([0, 128, 255, 255]), 0.0),
+            Rgba([128, 128, 128, 255])
+        );
+        assert_eq!(
+            tint(grey, [200.0, 0.0, 100.0], 0.5),
+            Rgba([150, 50, 100, 50])
+        );
+        assert_eq!(sepia(grey, 0.0), grey);
+        let Rgba([r, g, b, _]) = sepia(grey, 1.0);
+        assert!(r > g && g > b);
+    }
+
+    #[test]
+    fn test_corner_coverage() {
+        // Middle of an edge, and the center
+        assert_eq!(corner_coverage(0.5, 50.0, 100.0, 100.0, 10.0), 1.0);
+        assert_eq!(corner_coverage(50.0, 50.0, 100.0, 100.0, 10.0), 1.0);
+        // The very corners are cut
+        assert_eq!(corner_coverage(0.5, 0.5, 100.0, 100.0, 10.0), 0.0);
+        assert_eq!(corner_coverage(99.5, 99.5, 100.0, 100.0, 10.0), 0.0);
+        // Inside the corner's circle
+        assert_eq!(corner_coverage(5.0, 5.0, 100.0, 100.0, 10.0), 1.0);
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let key = |density: f32, params: &AssetParams| {
+            let mut key = CacheKey::default();
+            key.params(density, params);
+            key.0
+        };
+        let params = AssetParams::new("".to_string())
+            .transform(ImgTransform::Brightness(0.5))
+            .transform(ImgTransform::Resize {
+                width: 5,
+                height: 5,
+            });
+        assert_eq!(key(1.0, &params), key(1.0, &params.clone()));
+        assert_ne!(key(1.0, &params), key(2.0, &params));
+        // Same numbers in another transform
+        let contrast = AssetParams::new("".to_string())
+            .transform(ImgTransform::Contrast(0.5))
+            .transform(ImgTransform::Resize {
+                width: 5,
+                height: 5,
+            });
+        assert_ne!(key(1.0, &params), key(1.0, &contrast));
+        assert_ne!(
+            key(1.0, &AssetParams::new("".to_string())),
+            key(
+                1.0,
+                &AssetParams::new("".to_string()).transform(ImgTransform::Blur(0.0))
+            )
+        );
+
+        // The names of cached images must not change between builds
+        assert_eq!(
+            CacheKey(b"abc".to_vec()).digest(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_to_prune() {
+        let now = UNIX_EPOCH + Duration::from_secs(1000);
+        let file = |name: &str, size: u64, age: u64| {
+            (PathBuf::from(name), size, now - Duration::from_secs(age))
+        };
+        let files = vec![
+            file("old", 1, 500),
+            file("new", 10, 0),
+            file("older", 1, 600),
+            file("middle", 10, 100),
+        ];
+        let max_age = Duration::from_secs(550);
+        let prune = |max_bytes| to_prune(files.clone(), now, max_bytes, max_age);
+        assert_eq!(prune(100), vec![PathBuf::from("older")]);
+        // The oldest go first, even if a smaller one would fit
+        assert_eq!(
+            prune(15),
+            vec![
+                PathBuf::from("middle"),
+                PathBuf::from("old"),
+                PathBuf::from("older")
+            ]
+        );
+        assert_eq!(prune(0).len(), 4);
+    }
+
+    #[test]
+    fn test_apply_scales_sizes() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(40, 20));
+        let params = AssetParams::new("".to_string())
+            .transform(ImgTransform::Crop {
+                x: 5,
+                y: 0,
+                width: 10,
+                height: 10,
+            })
+            .transform(ImgTransform::Resize {
+                width: 5,
+                height: 5,
+            });
+        assert_eq!(apply(image.clone(), 1.0, &params).dimensions(), (5, 5));
+        assert_eq!(apply(image, 2.0, &params).dimensions(), (10, 10));
+    }
+}
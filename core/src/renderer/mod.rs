@@ -1,9 +1,16 @@
 pub mod canvas;
 pub mod gl;
+pub mod image_animation;
+pub mod image_cache;
+pub mod image_loader;
+mod image_pipeline;
+pub mod pixel_buffer;
 pub mod svg;
 pub mod text;
 
 use canvas::GlCanvasContext;
+use image_loader::ImageLoader;
+use svg::SvgLoader;
 
 use crate::{font_cache::FontCache, window::Window, Node, PixelSize};
 use std::{
@@ -26,6 +33,10 @@ use std::{
 pub struct Caches {
     /// Font cache
     pub font: Arc<RwLock<FontCache>>,
+    /// Decodes images in the background
+    pub images: ImageLoader,
+    /// Adds, replaces and removes SVGs
+    pub svgs: SvgLoader,
 }
 
 pub trait RendererContext {}
@@ -0,0 +1,146 @@
+use image::DynamicImage;
+use rgb::RGBA8;
+
+/// Layout of the bytes of a [`PixelBuffer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BufferFormat {
+    /// 4 bytes a pixel: red, green, blue and alpha
+    Rgba8,
+    /// 4 bytes a pixel: blue, green, red and alpha
+    Bgra8,
+    /// Planar YUV 4:2:0, BT.601: a Y plane, then a U plane and a V plane at half the width and height
+    I420,
+    /// Semi-planar YUV 4:2:0, BT.601: a Y plane, then a plane of interleaved U and V at half the width and height
+    Nv12,
+}
+
+/// Raw pixels of an image, like the frames of a camera, see [`ImageLoader#from_pixels`][super::image_loader::ImageLoader#method.from_pixels].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelBuffer {
+    pub width: usize,
+    pub height: usize,
+    pub format: BufferFormat,
+    pub data: Vec<u8>,
+}
+
+impl PixelBuffer {
+    pub fn new(width: usize, height: usize, format: BufferFormat, data: Vec<u8>) -> Self {
+        Self {
+            width,
+            height,
+            format,
+            data,
+        }
+    }
+
+    pub fn from_image(image: &DynamicImage) -> Self {
+        let image = image.to_rgba8();
+        Self::new(
+            image.width() as usize,
+            image.height() as usize,
+            BufferFormat::Rgba8,
+            image.into_raw(),
+        )
+    }
+
+    /// Bytes `data` should have for the size and format.
+    pub fn expected_len(&self) -> usize {
+        let (chroma_width, chroma_height) = ((self.width + 1) / 2, (self.height + 1) / 2);
+        match self.format {
+            BufferFormat::Rgba8 | BufferFormat::Bgra8 => self.width * self.height * 4,
+            BufferFormat::I420 | BufferFormat::Nv12 => {
+                self.width * self.height + chroma_width * chroma_height * 2
+            }
+        }
+    }
+
+    /// The pixels as RGBA, which is what the canvas takes.
+    pub(crate) fn to_rgba(&self) -> Result<Vec<RGBA8>, String> {
+        if self.data.len() < self.expected_len() {
+            return Err(format!(
+                "{:?} buffer of {}x{} has {} bytes, expected {}",
+                self.format,
+                self.width,
+                self.height,
+                self.data.len(),
+                self.expected_len()
+            ));
+        }
+
+        let pixels = self.width * self.height;
+        let rgba = match self.format {
+            BufferFormat::Rgba8 => self.data[..pixels * 4]
+                .chunks_exact(4)
+                .map(|p| RGBA8::new(p[0], p[1], p[2], p[3]))
+                .collect(),
+            BufferFormat::Bgra8 => self.data[..pixels * 4]
+                .chunks_exact(4)
+                .map(|p| RGBA8::new(p[2], p[1], p[0], p[3]))
+                .collect(),
+            BufferFormat::I420 | BufferFormat::Nv12 => {
+                let chroma_width = (self.width + 1) / 2;
+                let chroma_size = chroma_width * ((self.height + 1) / 2);
+                let (luma, chroma) = self.data.split_at(pixels);
+                let mut rgba = Vec::with_capacity(pixels);
+                for y in 0..self.height {
+                    for x in 0..self.width {
+                        let c = (y / 2) * chroma_width + x / 2;
+                        let (u, v) = match self.format {
+                            BufferFormat::I420 => (chroma[c], chroma[chroma_size + c]),
+                            _ => (chroma[c * 2], chroma[c * 2 + 1]),
+                        };
+                        rgba.push(yuv_to_rgba(luma[y * self.width + x], u, v));
+                    }
+                }
+                rgba
+            }
+        };
+        Ok(rgba)
+    }
+}
+
+/// BT.601, limited range.
+fn yuv_to_rgba(y: u8, u: u8, v: u8) -> RGBA8 {
+    let y = (y as f32 - 16.0) * 1.164;
+    let u = u as f32 - 128.0;
+    let v = v as f32 - 128.0;
+    let channel = |c: f32| c.round().clamp(0.0, 255.0) as u8;
+    RGBA8::new(
+        channel(y + 1.596 * v),
+        channel(y - 0.392 * u - 0.813 * v),
+        channel(y + 2.017 * u),
+        255,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bgra() {
+        let buffer = PixelBuffer::new(1, 1, BufferFormat::Bgra8, vec![1, 2, 3, 4]);
+        assert_eq!(buffer.to_rgba(), Ok(vec![RGBA8::new(3, 2, 1, 4)]));
+    }
+
+    #[test]
+    fn test_yuv() {
+        // 2x2 of white, with one chroma sample
+        let white = PixelBuffer::new(2, 2, BufferFormat::I420, vec![235, 235, 235, 235, 128, 128]);
+        assert_eq!(white.to_rgba(), Ok(vec![RGBA8::new(255, 255, 255, 255); 4]));
+
+        // Pure red, in both layouts
+        let (y, u, v) = (81, 90, 240);
+        let i420 = PixelBuffer::new(1, 1, BufferFormat::I420, vec![y, u, v]);
+        let nv12 = PixelBuffer::new(1, 1, BufferFormat::Nv12, vec![y, u, v]);
+        let red = i420.to_rgba().unwrap()[0];
+        assert!(red.r > 250 && red.g < 5 && red.b < 5, "{:?}", red);
+        assert_eq!(i420.to_rgba(), nv12.to_rgba());
+    }
+
+    #[test]
+    fn test_too_short() {
+        let buffer = PixelBuffer::new(2, 2, BufferFormat::Rgba8, vec![0; 15]);
+        assert!(buffer.to_rgba().is_err());
+    }
+}
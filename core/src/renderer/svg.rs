@@ -1,114 +1,1477 @@
+use super::canvas::{create_offscreen_grey_image, create_offscreen_image};
 use crate::Scale;
-use femtovg::{Color, Paint, Path};
-use std::{borrow::Borrow, collections::HashMap};
+use femtovg::renderer::OpenGl;
+use femtovg::{
+    Canvas, Color, CompositeOperation, FillRule, ImageFlags, ImageId, ImageSource, Paint, Path,
+    RenderTarget, Transform2D,
+};
+use image::DynamicImage;
+use std::{
+    borrow::{Borrow, Cow},
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
+    sync::{Arc, RwLock},
+};
 use usvg::{fontdb::Database, tiny_skia_path::PathSegment, Transform};
 
+/// Patterns that would take more tiles than this to fill a shape are not drawn.
+const MAX_PATTERN_TILES: usize = 4096;
+
+/// What `currentColor` resolves to while loading, so that the paints using it can be told apart,
+/// unless the SVG uses that color itself.
+const CURRENT_COLOR: usvg::Color = usvg::Color {
+    red: 1,
+    green: 254,
+    blue: 3,
+};
+
 #[derive(Debug)]
 pub struct SvgData {
-    pub paths: Vec<(Path, Option<Paint>, Option<Paint>, Transform)>,
+    pub root: SvgGroup,
     pub scale: Scale,
 }
 
-fn render_nodes_to_paths(
-    nodes: &[usvg::Node],
-) -> Vec<(Path, Option<Paint>, Option<Paint>, Transform)> {
-    let mut paths = Vec::new();
+impl SvgData {
+    /// Converts a parsed SVG, whose nodes are in the coordinates of its view box, and whose paints
+    /// using `currentColor` have `current_color`.
+    fn from_tree(tree: &usvg::Tree, current_color: Option<usvg::Color>) -> Self {
+        let view_box = tree.view_box().to_transform(tree.size());
+        Self {
+            root: SvgGroup::new(convert_nodes(
+                tree.root().children(),
+                view_box,
+                false,
+                current_color,
+                false,
+            )),
+            scale: Scale {
+                width: tree.size().width(),
+                height: tree.size().height(),
+            },
+        }
+    }
 
-    for node in nodes {
-        let mut path = Path::new();
+    /// Deletes the embedded images that were uploaded to the canvas.
+    pub(crate) fn delete_images(&self, canvas: &mut Canvas<OpenGl>) {
+        delete_group_images(&self.root, canvas);
+    }
+}
 
-        match &*node.borrow() {
-            usvg::Node::Group(child_group) => {
-                let mut child_paths = render_nodes_to_paths(child_group.children());
-                paths.append(&mut child_paths);
-            }
-            usvg::Node::Path(svg_path) => {
-                for command in svg_path.data().segments() {
-                    match command {
-                        PathSegment::MoveTo(p) => path.move_to(p.x as f32, p.y as f32),
-                        PathSegment::LineTo(p) => path.line_to(p.x as f32, p.y as f32),
-                        PathSegment::QuadTo(p1, p2) => path.quad_to(p1.x, p1.y, p2.x, p2.y),
-                        PathSegment::CubicTo(p1, p2, p3) => {
-                            path.bezier_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y)
-                        }
-                        PathSegment::Close => path.close(),
+fn delete_group_images(group: &SvgGroup, canvas: &mut Canvas<OpenGl>) {
+    fn delete_nodes(nodes: &[SvgNode], canvas: &mut Canvas<OpenGl>) {
+        for node in nodes.iter() {
+            match node {
+                SvgNode::Path(_) => (),
+                SvgNode::Group(group) => delete_group_images(group, canvas),
+                SvgNode::Image(image) => {
+                    if let Some(id) = image.id {
+                        canvas.delete_image(id);
                     }
                 }
+                SvgNode::Tiles { content, .. } => delete_nodes(content, canvas),
+            }
+        }
+    }
+    delete_nodes(&group.children, canvas);
+    for coverage in [group.clip.as_deref(), group.mask.as_deref()]
+        .into_iter()
+        .flatten()
+    {
+        delete_group_images(coverage, canvas);
+    }
+}
 
-                let to_femto_color = |usvg_paint: &usvg::Paint| match usvg_paint {
-                    usvg::Paint::Color(usvg::Color { red, green, blue }) => {
-                        Some(Color::rgb(*red, *green, *blue))
-                    }
-                    _ => None,
+/// What an SVG is drawn with, in order.
+#[derive(Debug)]
+pub enum SvgNode {
+    Path(SvgPath),
+    Group(SvgGroup),
+    Image(SvgImage),
+    /// The same nodes drawn with each of the transforms, for the tiles of a pattern
+    Tiles {
+        content: Vec<SvgNode>,
+        transforms: Vec<Transform>,
+    },
+}
+
+#[derive(Debug)]
+pub struct SvgPath {
+    pub path: Path,
+    pub fill: Option<Paint>,
+    pub stroke: Option<Paint>,
+    /// The stroke is drawn under the fill
+    pub stroke_first: bool,
+    pub transform: Transform,
+    /// How the fill and stroke are recolored, `None` for shapes that only cover, like clip paths
+    pub fill_recolor: Option<Recolor>,
+    pub stroke_recolor: Option<Recolor>,
+}
+
+/// How a fill or stroke is drawn with the color of the SVG.
+#[derive(Debug, Clone, Copy)]
+pub struct Recolor {
+    /// The paint uses `currentColor`, and takes the color even when the SVG is not tinted
+    pub current_color: bool,
+    /// Kept when recolored
+    pub opacity: f32,
+}
+
+/// Nodes composited together, through an offscreen image when they have an opacity, clip or mask.
+#[derive(Debug)]
+pub struct SvgGroup {
+    pub children: Vec<SvgNode>,
+    pub opacity: f32,
+    /// Only what the shapes of this group cover is kept
+    pub clip: Option<Box<SvgGroup>>,
+    /// Only what this group covers is kept, by its alpha or its luminance
+    pub mask: Option<Box<SvgGroup>>,
+    /// Drawn in greys, and covers by their luminance instead of its alpha, for luminance masks
+    pub luminance: bool,
+}
+
+impl SvgGroup {
+    fn new(children: Vec<SvgNode>) -> Self {
+        Self {
+            children,
+            opacity: 1.0,
+            clip: None,
+            mask: None,
+            luminance: false,
+        }
+    }
+
+    /// Whether it has to be drawn onto an offscreen image.
+    fn is_composited(&self) -> bool {
+        self.opacity < 1.0 || self.clip.is_some() || self.mask.is_some()
+    }
+}
+
+/// A raster image embedded in an SVG.
+#[derive(Debug)]
+pub struct SvgImage {
+    /// Decoded, until it is uploaded to the canvas
+    pub image: Option<DynamicImage>,
+    pub id: Option<ImageId>,
+    pub size: (f32, f32),
+    pub transform: Transform,
+    /// The viewport of the image, as `(x, y, width, height)`
+    pub viewport: (f32, f32, f32, f32),
+    /// Fits the image within the viewport
+    pub fit: Transform,
+}
+
+/// How a paint of usvg is drawn.
+enum SvgPaint {
+    Paint(Paint),
+    Pattern(Arc<usvg::Pattern>),
+}
+
+fn to_transform_2d(t: &Transform) -> Transform2D {
+    Transform2D([t.sx, t.ky, t.kx, t.sy, t.tx, t.ty])
+}
+
+fn map_point(t: &Transform, x: f32, y: f32) -> (f32, f32) {
+    (t.sx * x + t.kx * y + t.tx, t.ky * x + t.sy * y + t.ty)
+}
+
+fn to_femtovg_path(data: &usvg::tiny_skia_path::Path) -> Path {
+    let mut path = Path::new();
+    for command in data.segments() {
+        match command {
+            PathSegment::MoveTo(p) => path.move_to(p.x, p.y),
+            PathSegment::LineTo(p) => path.line_to(p.x, p.y),
+            PathSegment::QuadTo(p1, p2) => path.quad_to(p1.x, p1.y, p2.x, p2.y),
+            PathSegment::CubicTo(p1, p2, p3) => path.bezier_to(p1.x, p1.y, p2.x, p2.y, p3.x, p3.y),
+            PathSegment::Close => path.close(),
+        }
+    }
+    path
+}
+
+fn to_color(color: usvg::Color, opacity: f32) -> Color {
+    Color::rgba(
+        color.red,
+        color.green,
+        color.blue,
+        (opacity.clamp(0.0, 1.0) * 255.0).round() as u8,
+    )
+}
+
+/// The grey of the luminance of `color`, which is what luminance masks cover by.
+fn luminance(color: usvg::Color) -> usvg::Color {
+    let luma = 0.2125 * color.red as f32 + 0.7154 * color.green as f32 + 0.0721 * color.blue as f32;
+    let luma = luma.round().min(255.0) as u8;
+    usvg::Color::new_rgb(luma, luma, luma)
+}
+
+fn gradient_stops(
+    gradient: &usvg::BaseGradient,
+    opacity: f32,
+    current_color: Option<usvg::Color>,
+    luminance_only: bool,
+) -> Vec<(f32, Color)> {
+    gradient
+        .stops()
+        .iter()
+        .map(|stop| {
+            // Gradients are only recolored as a whole, by tints
+            let color = if Some(stop.color()) == current_color {
+                usvg::Color::black()
+            } else {
+                stop.color()
+            };
+            let color = if luminance_only {
+                luminance(color)
+            } else {
+                color
+            };
+            (
+                stop.offset().get(),
+                to_color(color, stop.opacity().get() * opacity),
+            )
+        })
+        .collect()
+}
+
+/// The start and end of the linear gradient from `start` to `end` that has `transform` applied to
+/// it. Any affine transform of a linear gradient is still one, with other ends.
+fn linear_gradient_points(
+    start: (f32, f32),
+    end: (f32, f32),
+    transform: &Transform,
+) -> ((f32, f32), (f32, f32)) {
+    let Some(inverse) = transform.invert() else {
+        return (start, end);
+    };
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length_sq = dx * dx + dy * dy;
+    if length_sq == 0.0 {
+        return (start, end);
+    }
+
+    // The position along the gradient of a point p is `a . p + c`
+    let a = (
+        (inverse.sx * dx + inverse.ky * dy) / length_sq,
+        (inverse.kx * dx + inverse.sy * dy) / length_sq,
+    );
+    let c = ((inverse.tx - start.0) * dx + (inverse.ty - start.1) * dy) / length_sq;
+    let a_sq = a.0 * a.0 + a.1 * a.1;
+    if a_sq == 0.0 {
+        return (start, end);
+    }
+    let from = (-c * a.0 / a_sq, -c * a.1 / a_sq);
+    (from, (from.0 + a.0 / a_sq, from.1 + a.1 / a_sq))
+}
+
+/// Only plain colors, linear gradients and concentric radial gradients can be drawn as a paint.
+/// The focal point and spread method of gradients are ignored. Colors are greys of their luminance
+/// when `luminance_only`.
+fn to_paint(
+    paint: &usvg::Paint,
+    opacity: f32,
+    current_color: Option<usvg::Color>,
+    luminance_only: bool,
+) -> SvgPaint {
+    match paint {
+        usvg::Paint::Color(color) if luminance_only => {
+            SvgPaint::Paint(Paint::color(to_color(luminance(*color), opacity)))
+        }
+        usvg::Paint::Color(color) => SvgPaint::Paint(Paint::color(to_color(*color, opacity))),
+        usvg::Paint::LinearGradient(gradient) => {
+            let ((x1, y1), (x2, y2)) = linear_gradient_points(
+                (gradient.x1(), gradient.y1()),
+                (gradient.x2(), gradient.y2()),
+                &gradient.transform(),
+            );
+            SvgPaint::Paint(Paint::linear_gradient_stops(
+                x1,
+                y1,
+                x2,
+                y2,
+                gradient_stops(gradient, opacity, current_color, luminance_only),
+            ))
+        }
+        usvg::Paint::RadialGradient(gradient) => {
+            let transform = gradient.transform();
+            let (cx, cy) = map_point(&transform, gradient.cx(), gradient.cy());
+            // Skews and uneven scales would make it an ellipse
+            let scale = (transform.sx * transform.sy - transform.kx * transform.ky)
+                .abs()
+                .sqrt();
+            SvgPaint::Paint(Paint::radial_gradient_stops(
+                cx,
+                cy,
+                0.0,
+                gradient.r().get() * scale,
+                gradient_stops(gradient, opacity, current_color, luminance_only),
+            ))
+        }
+        usvg::Paint::Pattern(pattern) => SvgPaint::Pattern(pattern.clone()),
+    }
+}
+
+fn recolor(paint: &usvg::Paint, opacity: f32, current_color: Option<usvg::Color>) -> Recolor {
+    Recolor {
+        current_color: matches!(paint, usvg::Paint::Color(color) if Some(*color) == current_color),
+        opacity,
+    }
+}
+
+fn to_fill_rule(rule: usvg::FillRule) -> FillRule {
+    match rule {
+        usvg::FillRule::NonZero => FillRule::NonZero,
+        usvg::FillRule::EvenOdd => FillRule::EvenOdd,
+    }
+}
+
+fn with_stroke_style(mut paint: Paint, stroke: &usvg::Stroke) -> Paint {
+    paint.set_line_width(stroke.width().get());
+    paint.set_line_cap(match stroke.linecap() {
+        usvg::LineCap::Butt => femtovg::LineCap::Butt,
+        usvg::LineCap::Round => femtovg::LineCap::Round,
+        usvg::LineCap::Square => femtovg::LineCap::Square,
+    });
+    paint.set_line_join(match stroke.linejoin() {
+        usvg::LineJoin::Miter => femtovg::LineJoin::Miter,
+        usvg::LineJoin::Round => femtovg::LineJoin::Round,
+        usvg::LineJoin::Bevel => femtovg::LineJoin::Bevel,
+        usvg::LineJoin::MiterClip => femtovg::LineJoin::Miter,
+    });
+    paint.set_miter_limit(stroke.miterlimit().get());
+    paint.set_anti_alias(true);
+    paint
+}
+
+/// The transforms of the tiles of `pattern` that cover `bounds`, in the coordinates of the shape.
+fn pattern_tiles(pattern: &usvg::Pattern, bounds: usvg::Rect) -> Option<Vec<Transform>> {
+    let rect = pattern.rect();
+    let (x, y, width, height) = (rect.x(), rect.y(), rect.width(), rect.height());
+    let to_pattern = pattern.transform().invert()?;
+    let corners = [
+        (bounds.left(), bounds.top()),
+        (bounds.right(), bounds.top()),
+        (bounds.right(), bounds.bottom()),
+        (bounds.left(), bounds.bottom()),
+    ]
+    .map(|(px, py)| map_point(&to_pattern, px, py));
+    let min_x = corners.iter().map(|p| p.0).fold(f32::MAX, f32::min);
+    let max_x = corners.iter().map(|p| p.0).fold(f32::MIN, f32::max);
+    let min_y = corners.iter().map(|p| p.1).fold(f32::MAX, f32::min);
+    let max_y = corners.iter().map(|p| p.1).fold(f32::MIN, f32::max);
+
+    let columns = ((min_x - x) / width).floor() as i64..((max_x - x) / width).ceil() as i64;
+    let rows = ((min_y - y) / height).floor() as i64..((max_y - y) / height).ceil() as i64;
+    let count = (columns.end - columns.start).max(0) * (rows.end - rows.start).max(0);
+    if count as usize > MAX_PATTERN_TILES {
+        println!("error: svg pattern {:?} has too many tiles", pattern.id());
+        return None;
+    }
+
+    let view_box = pattern
+        .view_box()
+        .map(|view_box| view_box.to_transform(rect.size()))
+        .unwrap_or_default();
+    let mut tiles = vec![];
+    for row in rows {
+        for column in columns.clone() {
+            let offset =
+                Transform::from_translate(x + column as f32 * width, y + row as f32 * height);
+            tiles.push(pattern.transform().pre_concat(offset).pre_concat(view_box));
+        }
+    }
+    Some(tiles)
+}
+
+/// Fills the shape of `shape` with the tiles of `pattern`.
+fn pattern_node(
+    pattern: &usvg::Pattern,
+    shape: SvgPath,
+    bounds: usvg::Rect,
+    opacity: f32,
+    current_color: Option<usvg::Color>,
+    luminance_only: bool,
+) -> SvgNode {
+    let content = convert_nodes(
+        pattern.root().children(),
+        Transform::default(),
+        false,
+        current_color,
+        luminance_only,
+    );
+    // Tiles are in the coordinates of the shape
+    let transforms = pattern_tiles(pattern, bounds)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|tile| shape.transform.pre_concat(tile))
+        .collect();
+    SvgNode::Group(SvgGroup {
+        opacity,
+        clip: Some(Box::new(SvgGroup::new(vec![SvgNode::Path(shape)]))),
+        ..SvgGroup::new(vec![SvgNode::Tiles {
+            content,
+            transforms,
+        }])
+    })
+}
+
+/// Places an image of `size` within the rect of `view_box`, following its `preserveAspectRatio`.
+fn image_fit(view_box: &usvg::ViewBox, size: (f32, f32)) -> Transform {
+    use usvg::Align;
+
+    let rect = view_box.rect;
+    let (width, height) = size;
+    let (mut sx, mut sy) = (rect.width() / width, rect.height() / height);
+    let (ax, ay) = match view_box.aspect.align {
+        Align::None => (0.0, 0.0),
+        Align::XMinYMin => (0.0, 0.0),
+        Align::XMidYMin => (0.5, 0.0),
+        Align::XMaxYMin => (1.0, 0.0),
+        Align::XMinYMid => (0.0, 0.5),
+        Align::XMidYMid => (0.5, 0.5),
+        Align::XMaxYMid => (1.0, 0.5),
+        Align::XMinYMax => (0.0, 1.0),
+        Align::XMidYMax => (0.5, 1.0),
+        Align::XMaxYMax => (1.0, 1.0),
+    };
+    if view_box.aspect.align != Align::None {
+        let scale = if view_box.aspect.slice {
+            sx.max(sy)
+        } else {
+            sx.min(sy)
+        };
+        (sx, sy) = (scale, scale);
+    }
+    Transform::from_row(
+        sx,
+        0.0,
+        0.0,
+        sy,
+        rect.x() + (rect.width() - width * sx) * ax,
+        rect.y() + (rect.height() - height * sy) * ay,
+    )
+}
+
+fn convert_path(
+    svg_path: &usvg::Path,
+    base: Transform,
+    solid: bool,
+    current_color: Option<usvg::Color>,
+    luminance_only: bool,
+) -> Vec<SvgNode> {
+    if svg_path.visibility() != usvg::Visibility::Visible {
+        return vec![];
+    }
+    let path = to_femtovg_path(svg_path.data());
+    let transform = base.pre_concat(svg_path.abs_transform());
+    let bounds = svg_path.data().bounds();
+
+    // Clip paths only cover, with their shapes
+    if solid {
+        return vec![SvgNode::Path(SvgPath {
+            path,
+            fill: svg_path
+                .fill()
+                .map(|fill| Paint::color(Color::black()).with_fill_rule(to_fill_rule(fill.rule()))),
+            stroke: svg_path
+                .stroke()
+                .map(|stroke| with_stroke_style(Paint::color(Color::black()), stroke)),
+            stroke_first: false,
+            transform,
+            fill_recolor: None,
+            stroke_recolor: None,
+        })];
+    }
+
+    let mut fill = None;
+    let mut fill_recolor = None;
+    let mut fill_node = None;
+    if let Some(svg_fill) = svg_path.fill() {
+        let rule = to_fill_rule(svg_fill.rule());
+        match to_paint(
+            svg_fill.paint(),
+            svg_fill.opacity().get(),
+            current_color,
+            luminance_only,
+        ) {
+            SvgPaint::Paint(paint) => {
+                fill = Some(paint.with_fill_rule(rule));
+                // Masks are not recolored
+                fill_recolor = (!luminance_only)
+                    .then(|| recolor(svg_fill.paint(), svg_fill.opacity().get(), current_color));
+            }
+            SvgPaint::Pattern(pattern) => {
+                let shape = SvgPath {
+                    path: path.clone(),
+                    fill: Some(Paint::color(Color::black()).with_fill_rule(rule)),
+                    stroke: None,
+                    stroke_first: false,
+                    transform,
+                    fill_recolor: None,
+                    stroke_recolor: None,
                 };
+                fill_node = Some(pattern_node(
+                    &pattern,
+                    shape,
+                    bounds,
+                    svg_fill.opacity().get(),
+                    current_color,
+                    luminance_only,
+                ));
+            }
+        }
+    }
 
-                let fill = svg_path
-                    .fill()
-                    .as_ref()
-                    .and_then(|fill| to_femto_color(&fill.paint()))
-                    .map(|col| Paint::color(col).with_anti_alias(true));
-
-                let stroke = svg_path.stroke().and_then(|stroke| {
-                    to_femto_color(&stroke.paint()).map(|paint| {
-                        let mut stroke_paint = Paint::color(paint);
-                        stroke_paint.set_line_width(stroke.width().get() as f32);
-                        stroke_paint.set_anti_alias(true);
-                        stroke_paint.set_line_cap(match &stroke.linecap() {
-                            usvg::LineCap::Butt => femtovg::LineCap::Butt,
-                            usvg::LineCap::Round => femtovg::LineCap::Round,
-                            usvg::LineCap::Square => femtovg::LineCap::Square,
-                        });
-                        stroke_paint.set_line_join(match &stroke.linejoin() {
-                            usvg::LineJoin::Miter => femtovg::LineJoin::Miter,
-                            usvg::LineJoin::Round => femtovg::LineJoin::Round,
-                            usvg::LineJoin::Bevel => femtovg::LineJoin::Bevel,
-                            usvg::LineJoin::MiterClip => femtovg::LineJoin::Miter,
-                        });
-                        stroke_paint.set_miter_limit(stroke.miterlimit().get() as f32);
-                        stroke_paint
-                    })
+    let mut stroke = None;
+    let mut stroke_recolor = None;
+    let mut stroke_node = None;
+    if let Some(svg_stroke) = svg_path.stroke() {
+        match to_paint(
+            svg_stroke.paint(),
+            svg_stroke.opacity().get(),
+            current_color,
+            luminance_only,
+        ) {
+            SvgPaint::Paint(paint) => {
+                stroke = Some(with_stroke_style(paint, svg_stroke));
+                stroke_recolor = (!luminance_only).then(|| {
+                    recolor(
+                        svg_stroke.paint(),
+                        svg_stroke.opacity().get(),
+                        current_color,
+                    )
                 });
+            }
+            SvgPaint::Pattern(pattern) => {
+                let shape = SvgPath {
+                    path: path.clone(),
+                    fill: None,
+                    stroke: Some(with_stroke_style(Paint::color(Color::black()), svg_stroke)),
+                    stroke_first: false,
+                    transform,
+                    fill_recolor: None,
+                    stroke_recolor: None,
+                };
+                // Outside of the shape, as far as the stroke goes
+                let half_width = svg_stroke.width().get() / 2.0;
+                let bounds = usvg::Rect::from_ltrb(
+                    bounds.left() - half_width,
+                    bounds.top() - half_width,
+                    bounds.right() + half_width,
+                    bounds.bottom() + half_width,
+                )
+                .unwrap_or(bounds);
+                stroke_node = Some(pattern_node(
+                    &pattern,
+                    shape,
+                    bounds,
+                    svg_stroke.opacity().get(),
+                    current_color,
+                    luminance_only,
+                ));
+            }
+        }
+    }
 
-                let transform = svg_path.abs_transform();
+    let stroke_first = svg_path.paint_order() == usvg::PaintOrder::StrokeAndFill;
+    let mut nodes = vec![SvgNode::Path(SvgPath {
+        path,
+        fill,
+        stroke,
+        stroke_first,
+        transform,
+        fill_recolor,
+        stroke_recolor,
+    })];
+    let patterns = if stroke_first {
+        [stroke_node, fill_node]
+    } else {
+        [fill_node, stroke_node]
+    };
+    nodes.extend(patterns.into_iter().flatten());
+    nodes
+}
 
-                paths.push((path, fill, stroke, transform))
+fn convert_image(
+    image: &usvg::Image,
+    base: Transform,
+    solid: bool,
+    luminance_only: bool,
+) -> Vec<SvgNode> {
+    if image.visibility() != usvg::Visibility::Visible {
+        return vec![];
+    }
+    let transform = base.pre_concat(image.abs_transform());
+    let view_box = image.view_box();
+    let rect = view_box.rect;
+    let data = match image.kind() {
+        usvg::ImageKind::SVG(tree) => {
+            let size = (tree.size().width(), tree.size().height());
+            let base = transform
+                .pre_concat(image_fit(&view_box, size))
+                .pre_concat(tree.view_box().to_transform(tree.size()));
+            // It resolved its own `currentColor`, to black
+            let mut group = SvgGroup::new(convert_nodes(
+                tree.root().children(),
+                base,
+                solid,
+                None,
+                luminance_only,
+            ));
+            // Cropped to the viewport
+            let mut viewport = Path::new();
+            viewport.rect(rect.x(), rect.y(), rect.width(), rect.height());
+            group.clip = Some(Box::new(SvgGroup::new(vec![SvgNode::Path(SvgPath {
+                path: viewport,
+                fill: Some(Paint::color(Color::black())),
+                stroke: None,
+                stroke_first: false,
+                transform,
+                fill_recolor: None,
+                stroke_recolor: None,
+            })])));
+            return vec![SvgNode::Group(group)];
+        }
+        usvg::ImageKind::JPEG(data) | usvg::ImageKind::PNG(data) | usvg::ImageKind::GIF(data) => {
+            data
+        }
+    };
+
+    let decoded = match image::load_from_memory(data) {
+        Ok(decoded) if luminance_only => DynamicImage::ImageLumaA8(decoded.to_luma_alpha8())
+            .to_rgba8()
+            .into(),
+        Ok(decoded) => decoded,
+        Err(e) => {
+            println!("error: svg image {:?} {:?}", image.id(), e);
+            return vec![];
+        }
+    };
+    let size = (decoded.width() as f32, decoded.height() as f32);
+    vec![SvgNode::Image(SvgImage {
+        image: Some(decoded),
+        id: None,
+        size,
+        transform,
+        viewport: (rect.x(), rect.y(), rect.width(), rect.height()),
+        fit: image_fit(&view_box, size),
+    })]
+}
+
+/// Converts `nodes`, whose transforms are relative to `base`. When `solid`, they only cover what
+/// they are drawn onto, for clip paths. When `luminance_only`, they are drawn in the greys of their
+/// luminance, for luminance masks.
+fn convert_nodes(
+    nodes: &[usvg::Node],
+    base: Transform,
+    solid: bool,
+    current_color: Option<usvg::Color>,
+    luminance_only: bool,
+) -> Vec<SvgNode> {
+    let mut converted = Vec::new();
+
+    for node in nodes {
+        match &*node.borrow() {
+            usvg::Node::Group(group) => {
+                let children =
+                    convert_nodes(group.children(), base, solid, current_color, luminance_only);
+                let user_space = base.pre_concat(group.abs_transform());
+                let svg_group = SvgGroup {
+                    children,
+                    opacity: if solid { 1.0 } else { group.opacity().get() },
+                    clip: group
+                        .clip_path()
+                        .map(|clip| Box::new(convert_clip_path(clip, user_space))),
+                    mask: match solid {
+                        true => None,
+                        false => group
+                            .mask()
+                            .map(|mask| Box::new(convert_mask(mask, user_space, current_color))),
+                    },
+                    luminance: false,
+                };
+                if svg_group.is_composited() {
+                    converted.push(SvgNode::Group(svg_group));
+                } else {
+                    converted.extend(svg_group.children);
+                }
+            }
+            usvg::Node::Path(svg_path) => converted.extend(convert_path(
+                svg_path,
+                base,
+                solid,
+                current_color,
+                luminance_only,
+            )),
+            usvg::Node::Image(image) => {
+                converted.extend(convert_image(image, base, solid, luminance_only))
+            }
+            usvg::Node::Text(text) => {
+                // Already laid out as paths
+                converted.extend(convert_nodes(
+                    text.flattened().children(),
+                    base,
+                    solid,
+                    current_color,
+                    luminance_only,
+                ))
             }
-            usvg::Node::Image(_) => {}
-            usvg::Node::Text(_) => {}
         }
     }
 
-    paths
+    converted
+}
+
+fn convert_clip_path(clip: &usvg::ClipPath, user_space: Transform) -> SvgGroup {
+    let base = user_space.pre_concat(clip.transform());
+    SvgGroup {
+        clip: clip
+            .clip_path()
+            .map(|clip| Box::new(convert_clip_path(clip, user_space))),
+        ..SvgGroup::new(convert_nodes(
+            clip.root().children(),
+            base,
+            true,
+            None,
+            false,
+        ))
+    }
 }
 
-pub fn load_svg_paths(svgs: HashMap<String, String>, fonts: Database) -> HashMap<String, SvgData> {
-    let mut loaded_svgs = HashMap::new();
+/// Luminance masks, the default, are drawn in greys, which cover by their luminance.
+fn convert_mask(
+    mask: &usvg::Mask,
+    user_space: Transform,
+    current_color: Option<usvg::Color>,
+) -> SvgGroup {
+    let rect = mask.rect();
+    let mut region = Path::new();
+    region.rect(rect.x(), rect.y(), rect.width(), rect.height());
+    let region = SvgGroup::new(vec![SvgNode::Path(SvgPath {
+        path: region,
+        fill: Some(Paint::color(Color::black())),
+        stroke: None,
+        stroke_first: false,
+        transform: user_space,
+        fill_recolor: None,
+        stroke_recolor: None,
+    })]);
 
-    for (name, path) in svgs.into_iter() {
-        let svg_data = load_svg_path(path, &fonts);
-        loaded_svgs.insert(name, svg_data);
+    let luminance = mask.kind() == usvg::MaskType::Luminance;
+    SvgGroup {
+        clip: Some(Box::new(region)),
+        mask: mask
+            .mask()
+            .map(|mask| Box::new(convert_mask(mask, user_space, current_color))),
+        luminance,
+        ..SvgGroup::new(convert_nodes(
+            mask.root().children(),
+            user_space,
+            false,
+            current_color,
+            luminance,
+        ))
     }
+}
 
-    loaded_svgs
+/// Offscreen images for each level of nested groups: its content, what covers it, and what covers
+/// it by luminance.
+const LAYERS_PER_DEPTH: usize = 3;
+
+/// Draws [`SvgData`] onto the canvas, through offscreen images for the groups that are composited.
+pub(crate) struct SvgPainter<'a> {
+    pub canvas: &'a mut Canvas<OpenGl>,
+    /// Offscreen images, [`LAYERS_PER_DEPTH`] for each level of nested groups
+    pub layers: &'a mut Vec<ImageId>,
+    /// What the SVG is drawn onto
+    pub target: RenderTarget,
+    /// The color of the paints that use `currentColor`
+    pub color: Color,
+    /// Draws every fill and stroke with `color`
+    pub tint: bool,
 }
 
-pub fn load_svg_path(file_path: String, fonts: &Database) -> SvgData {
-    let svg_data = match std::fs::read(&file_path) {
-        Ok(file) => file,
-        Err(e) => {
-            println!("error {:?} path {:?}", e, file_path);
-            panic!("{:?}", e);
+impl SvgPainter<'_> {
+    pub fn draw(&mut self, svg: &mut SvgData) {
+        self.draw_group(&mut svg.root, 0);
+    }
+
+    fn draw_nodes(&mut self, nodes: &mut [SvgNode], depth: usize) {
+        for node in nodes.iter_mut() {
+            match node {
+                SvgNode::Path(path) => self.draw_path(path),
+                SvgNode::Group(group) => self.draw_group(group, depth),
+                SvgNode::Image(image) => self.draw_image(image),
+                SvgNode::Tiles {
+                    content,
+                    transforms,
+                } => {
+                    for transform in transforms.iter() {
+                        self.canvas.save();
+                        self.canvas.set_transform(&to_transform_2d(transform));
+                        self.draw_nodes(content, depth);
+                        self.canvas.restore();
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_path(&mut self, svg_path: &SvgPath) {
+        self.canvas.save();
+        self.canvas
+            .set_transform(&to_transform_2d(&svg_path.transform));
+        let fill = svg_path
+            .fill
+            .as_ref()
+            .map(|fill| self.recolored(fill, svg_path.fill_recolor));
+        let stroke = svg_path
+            .stroke
+            .as_ref()
+            .map(|stroke| self.recolored(stroke, svg_path.stroke_recolor));
+        if svg_path.stroke_first {
+            if let Some(stroke) = stroke.as_ref() {
+                self.canvas.stroke_path(&svg_path.path, stroke);
+            }
+        }
+        if let Some(fill) = fill.as_ref() {
+            self.canvas.fill_path(&svg_path.path, fill);
+        }
+        if !svg_path.stroke_first {
+            if let Some(stroke) = stroke.as_ref() {
+                self.canvas.stroke_path(&svg_path.path, stroke);
+            }
         }
+        self.canvas.restore();
+    }
+
+    /// `paint`, in the color of the SVG when it takes it.
+    fn recolored<'p>(&self, paint: &'p Paint, recolor: Option<Recolor>) -> Cow<'p, Paint> {
+        match recolor {
+            Some(recolor) if self.tint || recolor.current_color => {
+                let mut color = self.color;
+                color.a *= recolor.opacity;
+                Cow::Owned(paint.clone().with_color(color))
+            }
+            _ => Cow::Borrowed(paint),
+        }
+    }
+
+    fn draw_image(&mut self, image: &mut SvgImage) {
+        if image.id.is_none() {
+            let decoded = image.image.take();
+            image.id = decoded.and_then(|decoded| {
+                ImageSource::try_from(&decoded)
+                    .ok()
+                    .and_then(|src| self.canvas.create_image(src, ImageFlags::empty()).ok())
+            });
+        }
+        let Some(id) = image.id else {
+            return;
+        };
+
+        let (x, y, width, height) = image.viewport;
+        let (image_width, image_height) = image.size;
+        self.canvas.save();
+        self.canvas
+            .set_transform(&to_transform_2d(&image.transform));
+        self.canvas.intersect_scissor(x, y, width, height);
+        self.canvas.set_transform(&to_transform_2d(&image.fit));
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, image_width, image_height);
+        self.canvas.fill_path(
+            &path,
+            &Paint::image(id, 0.0, 0.0, image_width, image_height, 0.0, 1.0),
+        );
+        self.canvas.restore();
+    }
+
+    /// Draws the group onto an offscreen image if it is composited, then composites that onto
+    /// the target.
+    fn draw_group(&mut self, group: &mut SvgGroup, depth: usize) {
+        if group.opacity <= 0.0 {
+            return;
+        }
+        if !group.is_composited() {
+            self.draw_nodes(&mut group.children, depth);
+            return;
+        }
+        let Some(content) = self.layer(depth * LAYERS_PER_DEPTH) else {
+            // Better drawn without the opacity, clip and mask than not at all
+            self.draw_nodes(&mut group.children, depth);
+            return;
+        };
+
+        let target = self.target;
+        self.begin(content);
+        self.draw_nodes(&mut group.children, depth + 1);
+        if let Some(clip) = group.clip.as_deref_mut() {
+            self.keep_covered(clip, content, depth);
+        }
+        if let Some(mask) = group.mask.as_deref_mut() {
+            self.keep_covered(mask, content, depth);
+        }
+        self.target = target;
+        self.canvas.set_render_target(target);
+        self.composite(content, group.opacity, CompositeOperation::SourceOver);
+    }
+
+    /// Erases what was drawn onto `content` where `coverage` doesn't cover it.
+    fn keep_covered(&mut self, coverage: &mut SvgGroup, content: ImageId, depth: usize) {
+        // Luminance masks are drawn onto a grey image, which covers by its grey
+        let index = depth * LAYERS_PER_DEPTH + if coverage.luminance { 2 } else { 1 };
+        let Some(covered) = self.layer(index) else {
+            return;
+        };
+        self.begin(covered);
+        self.draw_group(coverage, depth + 1);
+        self.target = RenderTarget::Image(content);
+        self.canvas.set_render_target(self.target);
+        self.composite(covered, 1.0, CompositeOperation::DestinationIn);
+    }
+
+    /// Draws onto the cleared `image` from now on.
+    fn begin(&mut self, image: ImageId) {
+        self.target = RenderTarget::Image(image);
+        self.canvas.set_render_target(self.target);
+        let (width, height) = (self.canvas.width(), self.canvas.height());
+        self.canvas
+            .clear_rect(0, 0, width, height, Color::rgba(0, 0, 0, 0));
+    }
+
+    /// Draws the offscreen `image` over the whole target.
+    fn composite(&mut self, image: ImageId, alpha: f32, operation: CompositeOperation) {
+        let (width, height) = (self.canvas.width() as f32, self.canvas.height() as f32);
+        let mut path = Path::new();
+        path.rect(0.0, 0.0, width, height);
+        let paint = Paint::image(image, 0.0, 0.0, width, height, 0.0, alpha);
+        // The transforms were applied while drawing onto the image
+        self.canvas.save();
+        self.canvas.reset_transform();
+        self.canvas.global_composite_operation(operation);
+        self.canvas.fill_path(&path, &paint);
+        self.canvas.restore();
+    }
+
+    fn layer(&mut self, index: usize) -> Option<ImageId> {
+        while self.layers.len() <= index {
+            let layer = if self.layers.len() % LAYERS_PER_DEPTH == 2 {
+                create_offscreen_grey_image(self.canvas)?
+            } else {
+                create_offscreen_image(self.canvas)?
+            };
+            self.layers.push(layer);
+        }
+        Some(self.layers[index])
+    }
+}
+
+/// Parses the SVG `data`, which may be gzipped, laying out its text with `fonts`. Also returns the
+/// color that `currentColor` was resolved to, when the SVG uses it without setting it, so that the
+/// paints using it can be told apart.
+fn parse_svg(data: &[u8], fonts: &Database) -> Result<(usvg::Tree, Option<usvg::Color>), String> {
+    let decompressed;
+    let data = if data.starts_with(&[0x1f, 0x8b]) {
+        decompressed = usvg::decompress_svgz(data).map_err(|e| e.to_string())?;
+        &decompressed[..]
+    } else {
+        data
+    };
+    let text = std::str::from_utf8(data).map_err(|e| e.to_string())?;
+    let options = usvg::Options::default();
+    let tree = usvg::Tree::from_str(text, &options, fonts).map_err(|e| e.to_string())?;
+    let Some(position) = root_color_position(text) else {
+        return Ok((tree, None));
+    };
+
+    // Unset, `currentColor` is black, so any other color is one the SVG uses itself
+    let mut used = HashSet::new();
+    used_colors(tree.root().children(), &mut used);
+    let Some(current_color) = unused_color(&used) else {
+        return Ok((tree, None));
     };
+    let marked = with_root_color(text, position, current_color);
+    let tree = usvg::Tree::from_str(&marked, &options, fonts).map_err(|e| e.to_string())?;
+    Ok((tree, Some(current_color)))
+}
+
+/// Where the root element of the SVG `text` can be given a `color`, right after its name. `None`
+/// when nothing uses `currentColor`, or the root element sets its own color.
+fn root_color_position(text: &str) -> Option<usize> {
+    if !text.contains("currentColor") {
+        return None;
+    }
+    let options = usvg::roxmltree::ParsingOptions {
+        allow_dtd: true,
+        ..Default::default()
+    };
+    let document = usvg::roxmltree::Document::parse_with_options(text, options).ok()?;
+    let root = document.root_element();
+    let sets_color = root.attribute("color").is_some()
+        || root.attribute("style").map_or(false, |style| {
+            style
+                .split(';')
+                .any(|declaration| declaration.split(':').next().unwrap().trim() == "color")
+        });
+    if sets_color {
+        return None;
+    }
+
+    // Past the `<` and the name, which may have a prefix
+    let start = root.range().start + 1;
+    let name = text[start..].find(|c: char| c.is_whitespace() || c == '/' || c == '>')?;
+    Some(start + name)
+}
+
+fn with_root_color(text: &str, position: usize, color: usvg::Color) -> String {
+    format!(
+        "{} color=\"#{:02x}{:02x}{:02x}\"{}",
+        &text[..position],
+        color.red,
+        color.green,
+        color.blue,
+        &text[position..]
+    )
+}
+
+/// [`CURRENT_COLOR`], or the first color after it that is not in `used`.
+fn unused_color(used: &HashSet<(u8, u8, u8)>) -> Option<usvg::Color> {
+    (0..=u8::MAX)
+        .map(|offset| usvg::Color {
+            red: CURRENT_COLOR.red.wrapping_add(offset),
+            ..CURRENT_COLOR
+        })
+        .find(|color| !used.contains(&(color.red, color.green, color.blue)))
+}
+
+/// Adds the colors of the fills and strokes of `nodes` to `used`. Clip paths are left out, since
+/// only their shapes are drawn, and so are embedded SVGs, which resolve their own `currentColor`.
+fn used_colors(nodes: &[usvg::Node], used: &mut HashSet<(u8, u8, u8)>) {
+    fn paint_colors(paint: &usvg::Paint, used: &mut HashSet<(u8, u8, u8)>) {
+        let stops = match paint {
+            usvg::Paint::Color(color) => {
+                used.insert((color.red, color.green, color.blue));
+                return;
+            }
+            usvg::Paint::LinearGradient(gradient) => gradient.stops(),
+            usvg::Paint::RadialGradient(gradient) => gradient.stops(),
+            usvg::Paint::Pattern(pattern) => {
+                used_colors(pattern.root().children(), used);
+                return;
+            }
+        };
+        for stop in stops {
+            let color = stop.color();
+            used.insert((color.red, color.green, color.blue));
+        }
+    }
+
+    for node in nodes {
+        match &*node.borrow() {
+            usvg::Node::Group(group) => {
+                used_colors(group.children(), used);
+                let mut mask = group.mask();
+                while let Some(group_mask) = mask {
+                    used_colors(group_mask.root().children(), used);
+                    mask = group_mask.mask();
+                }
+            }
+            usvg::Node::Path(path) => {
+                if let Some(fill) = path.fill() {
+                    paint_colors(fill.paint(), used);
+                }
+                if let Some(stroke) = path.stroke() {
+                    paint_colors(stroke.paint(), used);
+                }
+            }
+            usvg::Node::Image(_) => {}
+            usvg::Node::Text(text) => used_colors(text.flattened().children(), used),
+        }
+    }
+}
+
+/// A parsed SVG, and how many times SVGs were changed when it was added.
+struct SvgTree {
+    tree: Arc<usvg::Tree>,
+    /// What its paints using `currentColor` have
+    current_color: Option<usvg::Color>,
+    generation: u64,
+}
+
+/// Adds, replaces and removes the SVGs of an application at runtime, from SVG text or bytes in memory.
+///
+/// There is one loader per application, shared by its windows through
+/// [`Resources`][crate::resources::Resources] and passed to components in
+/// [`Caches`][crate::renderer::Caches]. SVGs are parsed once when they are added, with the fonts of
+/// the application, and drawn by every window from its next frame. Replacing an SVG redraws every
+/// [`Svg`][crate::widgets::Svg] drawing it.
+#[derive(Clone)]
+pub struct SvgLoader {
+    fonts: Arc<Database>,
+    trees: Arc<RwLock<HashMap<String, SvgTree>>>,
+    /// Names whose file failed to load, so that it is not read again on every frame
+    failed: Arc<RwLock<HashSet<String>>>,
+    generation: Arc<AtomicU64>,
+    targets: Arc<RwLock<HashMap<usize, (Waker, Arc<AtomicBool>)>>>,
+    next_target: Arc<AtomicUsize>,
+}
+
+type Waker = Arc<dyn Fn() + Send + Sync>;
+
+impl fmt::Debug for SvgLoader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SvgLoader").finish_non_exhaustive()
+    }
+}
+
+impl SvgLoader {
+    pub(crate) fn new(fonts: Arc<Database>) -> Self {
+        Self {
+            fonts,
+            trees: Default::default(),
+            failed: Default::default(),
+            generation: Default::default(),
+            targets: Default::default(),
+            next_target: Default::default(),
+        }
+    }
+
+    /// Adds the SVG `svg` as `name`, replacing the SVG of that name. Fails when it can't be parsed,
+    /// keeping the SVG it would have replaced.
+    pub fn insert_str<S: Into<String>>(&self, name: S, svg: &str) -> Result<(), String> {
+        self.insert_data(name, svg.as_bytes())
+    }
+
+    /// Adds an SVG from its bytes, which may be gzipped, see [`insert_str`][SvgLoader#method.insert_str].
+    pub fn insert_data<S: Into<String>>(&self, name: S, data: &[u8]) -> Result<(), String> {
+        let parsed = parse_svg(data, &self.fonts)?;
+        self.change(name.into(), Some(parsed));
+        Ok(())
+    }
+
+    /// Adds the SVG file at `path`, see [`insert_str`][SvgLoader#method.insert_str].
+    pub fn insert_path<S: Into<String>>(&self, name: S, path: &str) -> Result<(), String> {
+        let data = std::fs::read(path).map_err(|e| e.to_string())?;
+        self.insert_data(name, &data)
+    }
+
+    /// Adds the SVG file at `path` as `name` for an [`Svg`][crate::renderables::Svg] loading it
+    /// itself, unless it is already added. Errors are logged, and the file is not tried again until
+    /// `name` is added or removed.
+    pub(crate) fn load_path(&self, name: &str, path: &str) {
+        if self.contains(name) || self.failed.read().unwrap().contains(name) {
+            return;
+        }
+        if let Err(e) = self.insert_path(name, path) {
+            println!("Error while loading svg {:?} error: {:?}", path, e);
+            self.failed.write().unwrap().insert(name.to_string());
+        }
+    }
+
+    pub fn remove(&self, name: &str) {
+        self.change(name.to_string(), None);
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.trees.read().unwrap().contains_key(name)
+    }
+
+    fn change(&self, name: String, parsed: Option<(usvg::Tree, Option<usvg::Color>)>) {
+        self.failed.write().unwrap().remove(&name);
+        {
+            let mut trees = self.trees.write().unwrap();
+            match parsed {
+                Some((tree, current_color)) => {
+                    let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+                    let tree = SvgTree {
+                        tree: Arc::new(tree),
+                        current_color,
+                        generation,
+                    };
+                    trees.insert(name, tree);
+                }
+                None => {
+                    trees.remove(&name);
+                }
+            }
+        }
+        for (wake, updated) in self.targets.read().unwrap().values() {
+            updated.store(true, Ordering::SeqCst);
+            wake();
+        }
+    }
+
+    /// Draws the SVGs on the canvas of a window, which `wake` wakes up when they change.
+    pub(crate) fn attach(&self, wake: Waker) -> SvgTarget {
+        let id = self.next_target.fetch_add(1, Ordering::SeqCst);
+        let updated: Arc<AtomicBool> = Default::default();
+        self.targets
+            .write()
+            .unwrap()
+            .insert(id, (wake, updated.clone()));
+        SvgTarget {
+            id,
+            loader: self.clone(),
+            updated,
+            generations: HashMap::new(),
+        }
+    }
+}
+
+/// The SVGs of the canvas of one window, converted from the [`SvgLoader`] of its application.
+pub(crate) struct SvgTarget {
+    id: usize,
+    loader: SvgLoader,
+    /// SVGs changed since this was last taken, read by the UI
+    updated: Arc<AtomicBool>,
+    /// Generation of each SVG on the canvas
+    generations: HashMap<String, u64>,
+}
+
+impl fmt::Debug for SvgTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SvgTarget")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for SvgTarget {
+    fn drop(&mut self) {
+        self.loader.targets.write().unwrap().remove(&self.id);
+    }
+}
+
+impl SvgTarget {
+    /// Whether SVGs changed since this was last called.
+    pub(crate) fn take_updated(&self) -> bool {
+        self.updated.swap(false, Ordering::SeqCst)
+    }
+
+    /// Converts the SVGs added or replaced since this was last called, and deletes the ones
+    /// removed, on the render thread.
+    pub(crate) fn apply(
+        &mut self,
+        canvas: &mut Canvas<OpenGl>,
+        svgs: &mut HashMap<String, SvgData>,
+    ) {
+        let trees = self.loader.trees.read().unwrap();
+        let generations = &mut self.generations;
+        // Only the SVGs this got from the loader
+        svgs.retain(|name, svg| {
+            let keep = !generations.contains_key(name) || trees.contains_key(name);
+            if !keep {
+                svg.delete_images(canvas);
+                generations.remove(name);
+            }
+            keep
+        });
+        for (name, svg_tree) in trees.iter() {
+            if generations.get(name) == Some(&svg_tree.generation) {
+                continue;
+            }
+            if let Some(previous) = svgs.insert(
+                name.clone(),
+                SvgData::from_tree(&svg_tree.tree, svg_tree.current_color),
+            ) {
+                previous.delete_images(canvas);
+            }
+            generations.insert(name.clone(), svg_tree.generation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(a: (f32, f32), b: (f32, f32)) {
+        assert!(
+            (a.0 - b.0).abs() < 1e-4 && (a.1 - b.1).abs() < 1e-4,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn test_linear_gradient_points() {
+        let (start, end) = ((0.0, 0.0), (10.0, 0.0));
+
+        let (from, to) = linear_gradient_points(start, end, &Transform::default());
+        assert_near(from, start);
+        assert_near(to, end);
+
+        // Any point along the lines of equal color will do
+        let (from, to) = linear_gradient_points(start, end, &Transform::from_translate(5.0, 3.0));
+        assert_near(from, (5.0, 0.0));
+        assert_near(to, (15.0, 0.0));
+
+        // A quarter turn clockwise
+        let rotate = Transform::from_row(0.0, 1.0, -1.0, 0.0, 0.0, 0.0);
+        let (from, to) = linear_gradient_points(start, end, &rotate);
+        assert_near(from, (0.0, 0.0));
+        assert_near(to, (0.0, 10.0));
+
+        // Skewed, the lines of equal color are diagonal, and the gradient runs across them
+        let skew = Transform::from_row(1.0, 0.0, 1.0, 1.0, 0.0, 0.0);
+        let (from, to) = linear_gradient_points(start, end, &skew);
+        assert_near(from, (0.0, 0.0));
+        assert_near(to, (5.0, -5.0));
+    }
+
+    #[test]
+    fn test_root_color_position() {
+        let marked = |svg: &str| {
+            root_color_position(svg).map(|position| with_root_color(svg, position, CURRENT_COLOR))
+        };
+        assert_eq!(
+            marked(r#"<svg width="4"><path fill="currentColor"/></svg>"#).unwrap(),
+            r##"<svg color="#01fe03" width="4"><path fill="currentColor"/></svg>"##
+        );
+        assert_eq!(marked(r#"<svg width="4"><path fill="red"/></svg>"#), None);
+        // Whatever comes before the root element
+        assert_eq!(
+            marked(concat!(
+                r#"<?xml version="1.0"?><!DOCTYPE svg><!-- <svg> -->"#,
+                r#"<svg:svg xmlns:svg="http://www.w3.org/2000/svg" fill="currentColor"/>"#
+            ))
+            .unwrap(),
+            concat!(
+                r#"<?xml version="1.0"?><!DOCTYPE svg><!-- <svg> -->"#,
+                r##"<svg:svg color="#01fe03" xmlns:svg="http://www.w3.org/2000/svg" fill="currentColor"/>"##
+            )
+        );
+        // The color set by the file is kept
+        assert_eq!(
+            marked(r#"<svg color="red"><path stop-color="currentColor"/></svg>"#),
+            None
+        );
+        assert_eq!(
+            marked(r#"<svg style="fill: none; color: red"><path fill="currentColor"/></svg>"#),
+            None
+        );
+        assert!(marked(r#"<svg stop-color="red"><path fill="currentColor"/></svg>"#).is_some());
+    }
+
+    #[test]
+    fn test_unused_color() {
+        let mut used = HashSet::new();
+        assert_eq!(unused_color(&used), Some(CURRENT_COLOR));
+        used.insert((1, 254, 3));
+        used.insert((2, 254, 3));
+        assert_eq!(
+            unused_color(&used),
+            Some(usvg::Color {
+                red: 3,
+                ..CURRENT_COLOR
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_current_color() {
+        let fonts = Database::new();
+        let fills = |tree: &usvg::Tree| {
+            tree.root()
+                .children()
+                .iter()
+                .map(|node| match &*node.borrow() {
+                    usvg::Node::Path(path) => match path.fill().unwrap().paint() {
+                        usvg::Paint::Color(color) => *color,
+                        _ => unreachable!(),
+                    },
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // The SVG uses the color `currentColor` would have had, so it gets another one
+        let svg = concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4">"#,
+            r#"<rect width="2" height="2" fill="currentColor"/>"#,
+            r##"<rect width="2" height="2" fill="#01fe03"/></svg>"##
+        );
+        let (tree, current_color) = parse_svg(svg.as_bytes(), &fonts).unwrap();
+        let current_color = current_color.unwrap();
+        assert_ne!(current_color, CURRENT_COLOR);
+        assert_eq!(fills(&tree), vec![current_color, CURRENT_COLOR]);
+
+        let svg = concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4" color="red">"#,
+            r#"<rect width="2" height="2" fill="currentColor"/></svg>"#
+        );
+        let (tree, current_color) = parse_svg(svg.as_bytes(), &fonts).unwrap();
+        assert_eq!(current_color, None);
+        assert_eq!(fills(&tree), vec![usvg::Color::new_rgb(255, 0, 0)]);
+    }
+
+    #[test]
+    fn test_luminance() {
+        let grey = |r, g, b| luminance(usvg::Color::new_rgb(r, g, b)).red;
+        assert_eq!(grey(255, 255, 255), 255);
+        assert_eq!(grey(0, 0, 0), 0);
+        // Green is brighter than red, which is brighter than blue
+        assert_eq!(grey(0, 255, 0), 182);
+        assert_eq!(grey(255, 0, 0), 54);
+        assert_eq!(grey(0, 0, 255), 18);
+    }
+
+    #[test]
+    fn test_mask_type() {
+        let fonts = Database::new();
+        let luminance = |mask_type: &str| {
+            let svg = format!(
+                concat!(
+                    r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4">"#,
+                    r#"<mask id="m" {}><rect width="4" height="4" fill="white"/>"#,
+                    r#"<rect width="2" height="2" fill="black"/></mask>"#,
+                    r#"<rect width="4" height="4" mask="url(#m)"/></svg>"#
+                ),
+                mask_type
+            );
+            let (tree, current_color) = parse_svg(svg.as_bytes(), &fonts).unwrap();
+            let svg = SvgData::from_tree(&tree, current_color);
+            match &svg.root.children[..] {
+                [SvgNode::Group(group)] => group.mask.as_ref().unwrap().luminance,
+                nodes => panic!("{:?}", nodes),
+            }
+        };
+        // Luminance is the default
+        assert!(luminance(""));
+        assert!(!luminance(r#"mask-type="alpha""#));
+    }
+
+    #[test]
+    fn test_insert_str() {
+        let loader = SvgLoader::new(Arc::new(Database::new()));
+        let woken = Arc::new(AtomicUsize::new(0));
+        let target = {
+            let woken = woken.clone();
+            loader.attach(Arc::new(move || {
+                woken.fetch_add(1, Ordering::SeqCst);
+            }))
+        };
+
+        let dot = r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="4"><circle cx="2" cy="2" r="2"/></svg>"#;
+        loader.insert_str("dot", dot).unwrap();
+        assert!(loader.contains("dot"));
+        assert!(target.take_updated());
+        assert!(!target.take_updated());
+        assert_eq!(woken.load(Ordering::SeqCst), 1);
+
+        // The SVG it would have replaced is kept
+        assert!(loader.insert_str("dot", "<svg").is_err());
+        assert!(loader.contains("dot"));
+        assert!(!target.take_updated());
 
-    let tree = usvg::Tree::from_data(&svg_data, &usvg::Options::default(), &fonts).unwrap();
-    let width = tree.size().width() as f32;
-    let height = tree.size().height() as f32;
+        loader.remove("dot");
+        assert!(!loader.contains("dot"));
+        assert!(target.take_updated());
 
-    let paths: Vec<(Path, Option<Paint>, Option<Paint>, Transform)> =
-        render_nodes_to_paths(tree.root().children());
-    SvgData {
-        paths,
-        scale: Scale { width, height },
+        // Windows that closed are not woken
+        drop(target);
+        loader.insert_str("dot", dot).unwrap();
+        assert_eq!(woken.load(Ordering::SeqCst), 2);
     }
 }
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use cosmic_text::fontdb::Database;
+
+use crate::font_cache::FontCache;
+use crate::renderer::image_loader::ImageLoader;
+use crate::renderer::svg::SvgLoader;
+use crate::AssetParams;
+
+/// The fonts, assets, images and SVGs of an application, shared by all of its windows.
+///
+/// Clones are cheap and share everything, so a backend opening several surfaces makes one
+/// `Resources` and gives each window a clone, instead of copying the fonts and assets per surface.
+#[derive(Clone)]
+pub struct Resources {
+    fonts: Arc<Database>,
+    /// Measures text for layout, whichever window it is in
+    font_cache: Arc<RwLock<FontCache>>,
+    assets: Arc<HashMap<String, AssetParams>>,
+    images: ImageLoader,
+    svgs: SvgLoader,
+}
+
+impl fmt::Debug for Resources {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Resources")
+            .field("assets", &self.assets)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for Resources {
+    fn default() -> Self {
+        Self::new(Database::default(), HashMap::new(), HashMap::new())
+    }
+}
+
+impl Resources {
+    /// `assets` are the images loaded when a window opens, and `svgs` the paths of the SVGs by name,
+    /// which are parsed right away.
+    pub fn new(
+        fonts: Database,
+        assets: HashMap<String, AssetParams>,
+        svgs: HashMap<String, String>,
+    ) -> Self {
+        let font_cache = FontCache::new(fonts.clone());
+        let fonts = Arc::new(fonts);
+        let svg_loader = SvgLoader::new(fonts.clone());
+        for (name, path) in svgs.into_iter() {
+            if let Err(e) = svg_loader.insert_path(name, &path) {
+                println!("error {:?} path {:?}", e, path);
+                panic!("{:?}", e);
+            }
+        }
+
+        Self {
+            fonts,
+            font_cache: Arc::new(RwLock::new(font_cache)),
+            assets: Arc::new(assets),
+            images: ImageLoader::new(),
+            svgs: svg_loader,
+        }
+    }
+
+    pub fn fonts(&self) -> &Database {
+        &self.fonts
+    }
+
+    pub fn assets(&self) -> &HashMap<String, AssetParams> {
+        &self.assets
+    }
+
+    /// Decodes the images of all the windows, on one pool of worker threads.
+    pub fn images(&self) -> &ImageLoader {
+        &self.images
+    }
+
+    /// Adds, replaces and removes the SVGs of all the windows.
+    pub fn svgs(&self) -> &SvgLoader {
+        &self.svgs
+    }
+
+    pub(crate) fn font_cache(&self) -> Arc<RwLock<FontCache>> {
+        self.font_cache.clone()
+    }
+}
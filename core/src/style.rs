@@ -1,11 +1,14 @@
 //! Dynamic styling of Components.
 //!
+use std::cell::RefCell;
 use std::collections::HashMap;
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 use cosmic_text::Weight;
 
+use crate::animation::{Animatable, Easing};
 use crate::layout::*;
 use crate::types::*;
 
@@ -70,8 +73,51 @@ pub enum StyleVal {
     Int(u32),
     Bool(bool),
     String(&'static str),
+    Transition(StyleTransition),
+    /// Radii of the top left, top right, bottom right and bottom left corners
+    Corners((f32, f32, f32, f32)),
 } // Impls below
 
+/// Value of the `"transition"` style parameter of a [`Styled`] Component: changes of the listed
+/// parameters are animated from their previous value, instead of jumping to the new one.
+///
+/// Colors, numbers, points, corner radii and pixel or percent dimensions are interpolated, other
+/// values change at once.
+///
+/// ```ignore
+/// Button::new(txt!("Ok")).style(
+///     "transition",
+///     StyleTransition::new().add("background_color", Duration::from_millis(150), Easing::EaseOut),
+/// )
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StyleTransition(Vec<(&'static str, Duration, Easing)>);
+
+impl StyleTransition {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Transitions changes of `parameter`.
+    pub fn add(mut self, parameter: &'static str, duration: Duration, easing: Easing) -> Self {
+        self.0.push((parameter, duration, easing));
+        self
+    }
+
+    /// Transitions changes of every parameter, unless it was added with a transition of its own.
+    pub fn all(duration: Duration, easing: Easing) -> Self {
+        Self::new().add("*", duration, easing)
+    }
+
+    fn get(&self, parameter: &'static str) -> Option<(Duration, Easing)> {
+        self.0
+            .iter()
+            .find(|(p, _, _)| *p == parameter)
+            .or_else(|| self.0.iter().find(|(p, _, _)| *p == "*"))
+            .map(|(_, duration, easing)| (*duration, *easing))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct StyleKey {
     struct_name: &'static str,
@@ -200,6 +246,23 @@ impl Default for Style {
             ),
             (StyleKey::new("IconButton", "radius", None), 4.0.into()),
             (StyleKey::new("IconButton", "padding", None), 2.0.into()),
+            (
+                StyleKey::new("IconButton", "icon_color", None),
+                Color::BLACK.into(),
+            ),
+            (
+                StyleKey::new("IconButton", "icon_highlight_color", None),
+                Color::BLACK.into(),
+            ),
+            (
+                StyleKey::new("IconButton", "icon_active_color", None),
+                Color::BLACK.into(),
+            ),
+            (
+                StyleKey::new("IconButton", "icon_disabled_color", None),
+                Color::MID_GREY.into(),
+            ),
+            (StyleKey::new("IconButton", "icon_tint", None), false.into()),
             // RadioButton
             (
                 StyleKey::new("RadioButton", "text_color", None),
@@ -362,8 +425,97 @@ impl Default for Style {
                 StyleKey::new("Scroll", "bar_active_color", None),
                 Color::DARK_GREY.into(),
             ),
+            // Div shadow and backdrop blur
+            (
+                StyleKey::new("Scroll", "shadow_color", None),
+                Color::TRANSPARENT.into(),
+            ),
+            (
+                StyleKey::new("Scroll", "shadow_offset", None),
+                Point::new(0.0, 0.0).into(),
+            ),
+            (StyleKey::new("Scroll", "shadow_blur", None), 0.0.into()),
+            (StyleKey::new("Scroll", "shadow_spread", None), 0.0.into()),
+            (StyleKey::new("Scroll", "shadow_inset", None), false.into()),
+            (StyleKey::new("Scroll", "backdrop_blur", None), 0.0.into()),
+            (
+                StyleKey::new("Scroll", "background_color", None),
+                Color::TRANSPARENT.into(),
+            ),
+            (
+                StyleKey::new("Scroll", "radius", None),
+                (0.0, 0.0, 0.0, 0.0).into(),
+            ),
             //Image
             (StyleKey::new("Image", "radius", None), 0.0.into()),
+            (
+                StyleKey::new("Image", "placeholder_color", None),
+                Color::TRANSPARENT.into(),
+            ),
+            (StyleKey::new("Image", "fit", None), "fill".into()),
+            (
+                StyleKey::new("Image", "align", None),
+                Point::new(0.5, 0.5).into(),
+            ),
+            (StyleKey::new("Image", "repeat", None), false.into()),
+            (StyleKey::new("Image", "slice", None), Rect::ZERO.into()),
+            // Svg
+            (StyleKey::new("Svg", "color", None), Color::BLACK.into()),
+            (StyleKey::new("Svg", "tint", None), false.into()),
+            // AnimatedImage
+            (StyleKey::new("AnimatedImage", "radius", None), 0.0.into()),
+            (
+                StyleKey::new("AnimatedImage", "placeholder_color", None),
+                Color::TRANSPARENT.into(),
+            ),
+            (StyleKey::new("AnimatedImage", "fit", None), "fill".into()),
+            (
+                StyleKey::new("AnimatedImage", "align", None),
+                Point::new(0.5, 0.5).into(),
+            ),
+            // TitleBar
+            (
+                StyleKey::new("TitleBar", "background_color", None),
+                Color::LIGHT_GREY.into(),
+            ),
+            (
+                StyleKey::new("TitleBar", "text_color", None),
+                Color::BLACK.into(),
+            ),
+            (StyleKey::new("TitleBar", "font_size", None), 14.0.into()),
+            (
+                StyleKey::new("TitleBar", "button_font_size", None),
+                14.0.into(),
+            ),
+            (StyleKey::new("TitleBar", "button_width", None), 36.0.into()),
+            (StyleKey::new("TitleBar", "button_radius", None), 4.0.into()),
+            (
+                StyleKey::new("TitleBar", "button_highlight_color", None),
+                Color::MID_GREY.into(),
+            ),
+            (
+                StyleKey::new("TitleBar", "button_active_color", None),
+                Color::DARK_GREY.into(),
+            ),
+            (
+                StyleKey::new("TitleBar", "close_active_color", None),
+                Color::RED.into(),
+            ),
+            (StyleKey::new("TitleBar", "height", None), 32.0.into()),
+            (StyleKey::new("TitleBar", "padding", None), 2.0.into()),
+            // WindowFrame
+            (
+                StyleKey::new("WindowFrame", "border_color", None),
+                Color::MID_GREY.into(),
+            ),
+            (
+                StyleKey::new("WindowFrame", "border_width", None),
+                1.0.into(),
+            ),
+            (
+                StyleKey::new("WindowFrame", "handle_width", None),
+                6.0.into(),
+            ),
         ]);
         Self(map)
     }
@@ -389,7 +541,145 @@ fn get_current_style(k: StyleKey) -> Option<StyleVal> {
     _current_style().lock().unwrap().get(k)
 }
 
+#[derive(Debug)]
+struct RunningTransition {
+    from: StyleVal,
+    to: StyleVal,
+    started_at: Instant,
+    duration: Duration,
+    easing: Easing,
+}
+
+impl RunningTransition {
+    fn settled(value: StyleVal, now: Instant) -> Self {
+        Self {
+            from: value.clone(),
+            to: value,
+            started_at: now,
+            duration: Duration::ZERO,
+            easing: Easing::Linear,
+        }
+    }
+
+    fn is_running(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started_at) < self.duration
+    }
+
+    fn value_at(&self, now: Instant) -> StyleVal {
+        if !self.is_running(now) {
+            return self.to.clone();
+        }
+        let t = now.saturating_duration_since(self.started_at).as_secs_f32()
+            / self.duration.as_secs_f32();
+        self.from
+            .interpolate(&self.to, self.easing.apply(t))
+            .unwrap_or_else(|| self.to.clone())
+    }
+}
+
+/// The style transitions of a Node, carried over from one view to the next.
+#[derive(Debug, Default)]
+pub(crate) struct StyleTransitions(HashMap<(&'static str, &'static str), RunningTransition>);
+
+thread_local!(
+    /// Transitions of the Node that is being viewed or rendered
+    static ACTIVE_TRANSITIONS: RefCell<Option<StyleTransitions>> = RefCell::new(None)
+);
+
+impl StyleTransitions {
+    /// Calls `f` with these transitions applied to the [`Styled#style_val`][Styled#method.style_val] calls it makes.
+    pub(crate) fn with_active<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        let outer = ACTIVE_TRANSITIONS.with(|a| a.replace(Some(std::mem::take(self))));
+        let r = f();
+        *self = ACTIVE_TRANSITIONS
+            .with(|a| a.replace(outer))
+            .unwrap_or_default();
+        r
+    }
+
+    pub(crate) fn is_running(&self) -> bool {
+        let now = Instant::now();
+        self.0.values().any(|t| t.is_running(now))
+    }
+
+    fn transition(
+        &mut self,
+        key: (&'static str, &'static str),
+        target: StyleVal,
+        transition: Option<(Duration, Easing)>,
+        now: Instant,
+    ) -> StyleVal {
+        let (duration, easing) = match transition {
+            Some(transition) => transition,
+            None => {
+                self.0.remove(&key);
+                return target;
+            }
+        };
+
+        match self.0.get_mut(&key) {
+            // First time we see this parameter, nothing to animate from
+            None => {
+                self.0
+                    .insert(key, RunningTransition::settled(target.clone(), now));
+                target
+            }
+            Some(running) if running.to == target => running.value_at(now),
+            Some(running) => {
+                let from = running.value_at(now);
+                if from.interpolate(&target, 0.0).is_none() {
+                    *running = RunningTransition::settled(target.clone(), now);
+                    return target;
+                }
+                *running = RunningTransition {
+                    from: from.clone(),
+                    to: target,
+                    started_at: now,
+                    duration,
+                    easing,
+                };
+                from
+            }
+        }
+    }
+}
+
+impl Hash for StyleTransitions {
+    /// Changes on every frame while a transition is running.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let now = Instant::now();
+        for running in self.0.values().filter(|t| t.is_running(now)) {
+            now.saturating_duration_since(running.started_at)
+                .hash(state);
+        }
+    }
+}
+
+fn transition_style_val<F>(
+    component: &'static str,
+    parameter: &'static str,
+    val: StyleVal,
+    transition: F,
+) -> StyleVal
+where
+    F: FnOnce() -> Option<StyleVal>,
+{
+    ACTIVE_TRANSITIONS.with(|active| match active.borrow_mut().as_mut() {
+        Some(transitions) => {
+            let transition = match transition() {
+                Some(StyleVal::Transition(t)) => t.get(parameter),
+                _ => None,
+            };
+            transitions.transition((component, parameter), val, transition, Instant::now())
+        }
+        None => val,
+    })
+}
+
 /// Implemented by the [`component`][macro@crate::component] attribute macro, for "Styled" Components.
+///
+/// While a Component is viewed or rendered, the parameters listed in its `"transition"` style
+/// ([`StyleTransition`]) are animated by [`style_val`][Styled#method.style_val] when they change.
 pub trait Styled: Sized {
     #[doc(hidden)]
     fn name() -> &'static str;
@@ -429,6 +719,24 @@ pub trait Styled: Sized {
     }
 
     fn style_val(&self, param: &'static str) -> Option<StyleVal> {
+        let val = self.style_val_untransitioned(param)?;
+        if param == "transition" {
+            return Some(val);
+        }
+        Some(self.transition_val(param, val))
+    }
+
+    /// Animates `val` with the transition of `param`, for values that a Component picks
+    /// from its style, e.g. a background color that depends on whether it is hovered.
+    /// Read the style with [`style_val_untransitioned`][Styled#method.style_val_untransitioned] then.
+    fn transition_val<V: Into<StyleVal>>(&self, param: &'static str, val: V) -> StyleVal {
+        transition_style_val(Self::name(), param, val.into(), || {
+            self.style_val_untransitioned("transition")
+        })
+    }
+
+    /// The value of the parameter, without its transition.
+    fn style_val_untransitioned(&self, param: &'static str) -> Option<StyleVal> {
         if let Some(v) = self.style_overrides().0.get(param) {
             Some(v.clone())
         } else if let Some(c) = self.class() {
@@ -733,6 +1041,19 @@ impl From<StyleVal> for bool {
         }
     }
 }
+impl From<(f32, f32, f32, f32)> for StyleVal {
+    fn from(c: (f32, f32, f32, f32)) -> Self {
+        Self::Corners(c)
+    }
+}
+impl From<StyleVal> for (f32, f32, f32, f32) {
+    fn from(v: StyleVal) -> Self {
+        match v {
+            StyleVal::Corners(c) => c,
+            x => panic!("Tried to coerce {x:?} into corners"),
+        }
+    }
+}
 impl From<&'static str> for StyleVal {
     fn from(c: &'static str) -> Self {
         Self::String(c)
@@ -747,7 +1068,73 @@ impl From<StyleVal> for &str {
     }
 }
 
+impl From<StyleTransition> for StyleVal {
+    fn from(c: StyleTransition) -> Self {
+        Self::Transition(c)
+    }
+}
+impl From<StyleVal> for StyleTransition {
+    fn from(v: StyleVal) -> Self {
+        match v {
+            StyleVal::Transition(c) => c,
+            x => panic!("Tried to coerce {x:?} into a StyleTransition"),
+        }
+    }
+}
+
+fn interpolate_dimension(from: Dimension, to: Dimension, t: f32) -> Option<Dimension> {
+    let lerp = |a: f64, b: f64| a + (b - a) * t as f64;
+    match (from, to) {
+        (Dimension::Px(a), Dimension::Px(b)) => Some(Dimension::Px(lerp(a, b))),
+        (Dimension::Pct(a), Dimension::Pct(b)) => Some(Dimension::Pct(lerp(a, b))),
+        (Dimension::Auto, Dimension::Auto) => Some(Dimension::Auto),
+        _ => None,
+    }
+}
+
 impl StyleVal {
+    /// The value at `t` between `self` and `to`, if values of their type can be interpolated.
+    pub fn interpolate(&self, to: &StyleVal, t: f32) -> Option<StyleVal> {
+        let lerp = |a: f64, b: f64| a + (b - a) * t as f64;
+        Some(match (self, to) {
+            (Self::Color(a), Self::Color(b)) => Self::Color(a.interpolate(b, t)),
+            (Self::Point(a), Self::Point(b)) => Self::Point(a.interpolate(b, t)),
+            (Self::Pos(a), Self::Pos(b)) => Self::Pos(Pos {
+                x: a.x.interpolate(&b.x, t),
+                y: a.y.interpolate(&b.y, t),
+                z: a.z.interpolate(&b.z, t),
+            }),
+            (Self::Float(a), Self::Float(b)) => Self::Float(lerp(*a, *b)),
+            (Self::Corners(a), Self::Corners(b)) => Self::Corners((
+                a.0.interpolate(&b.0, t),
+                a.1.interpolate(&b.1, t),
+                a.2.interpolate(&b.2, t),
+                a.3.interpolate(&b.3, t),
+            )),
+            (Self::Int(a), Self::Int(b)) => {
+                Self::Int(lerp(*a as f64, *b as f64).round().max(0.0) as u32)
+            }
+            (Self::Dimension(a), Self::Dimension(b)) => {
+                Self::Dimension(interpolate_dimension(*a, *b, t)?)
+            }
+            (Self::Size(a), Self::Size(b)) => Self::Size(Size {
+                width: interpolate_dimension(a.width, b.width, t)?,
+                height: interpolate_dimension(a.height, b.height, t)?,
+            }),
+            (Self::Rect(a), Self::Rect(b)) => Self::Rect(Rect {
+                left: interpolate_dimension(a.left, b.left, t)?,
+                right: interpolate_dimension(a.right, b.right, t)?,
+                top: interpolate_dimension(a.top, b.top, t)?,
+                bottom: interpolate_dimension(a.bottom, b.bottom, t)?,
+            }),
+            _ => return None,
+        })
+    }
+
+    pub fn transition(self) -> StyleTransition {
+        self.into()
+    }
+
     pub fn dimension(self) -> Dimension {
         self.into()
     }
@@ -811,6 +1198,10 @@ impl StyleVal {
     pub fn u32(self) -> u32 {
         self.into()
     }
+
+    pub fn corners(self) -> (f32, f32, f32, f32) {
+        self.into()
+    }
 }
 
 #[cfg(test)]
@@ -882,6 +1273,69 @@ mod tests {
         assert_eq!(c, Color::BLUE);
     }
 
+    #[test]
+    fn test_style_transition() {
+        let now = Instant::now();
+        let key = ("Widget", "color");
+        let transition = Some((Duration::from_millis(100), Easing::Linear));
+        let mut transitions = StyleTransitions::default();
+
+        // nothing to animate from the first time
+        let c = transitions.transition(key, Color::BLACK.into(), transition, now);
+        assert_eq!(c, Color::BLACK.into());
+        assert!(!transitions.is_running());
+
+        let c = transitions.transition(key, Color::WHITE.into(), transition, now);
+        assert_eq!(c, Color::BLACK.into());
+        let c = transitions.transition(
+            key,
+            Color::WHITE.into(),
+            transition,
+            now + Duration::from_millis(50),
+        );
+        assert_eq!(c, Color::BLACK.interpolate(&Color::WHITE, 0.5).into());
+        let c = transitions.transition(
+            key,
+            Color::WHITE.into(),
+            transition,
+            now + Duration::from_millis(100),
+        );
+        assert_eq!(c, Color::WHITE.into());
+
+        // values that can't be interpolated change at once
+        let key = ("Widget", "font_weight");
+        transitions.transition(key, FontWeight::Normal.into(), transition, now);
+        let w = transitions.transition(key, FontWeight::Bold.into(), transition, now);
+        assert_eq!(w, FontWeight::Bold.into());
+
+        assert_eq!(
+            StyleVal::from(Dimension::Px(10.0)).interpolate(&Dimension::Px(20.0).into(), 0.5),
+            Some(Dimension::Px(15.0).into())
+        );
+        assert_eq!(
+            StyleVal::from(Dimension::Px(10.0)).interpolate(&Dimension::Pct(20.0).into(), 0.5),
+            None
+        );
+    }
+
+    #[test]
+    fn test_style_transition_lookup() {
+        let transition = StyleTransition::all(Duration::from_millis(200), Easing::Ease).add(
+            "radius",
+            Duration::from_millis(50),
+            Easing::Linear,
+        );
+        assert_eq!(
+            transition.get("radius"),
+            Some((Duration::from_millis(50), Easing::Linear))
+        );
+        assert_eq!(
+            transition.get("background_color"),
+            Some((Duration::from_millis(200), Easing::Ease))
+        );
+        assert_eq!(StyleTransition::new().get("radius"), None);
+    }
+
     #[test]
     fn test_style_macro() {
         let s = style!(
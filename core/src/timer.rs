@@ -0,0 +1,143 @@
+//! Bookkeeping for the timers that Components register with [`Register::Timer`].
+
+use std::time::{Duration, Instant};
+
+use crate::event::Register;
+use crate::node::Registration;
+
+#[derive(Debug)]
+struct ScheduledTimer {
+    node_id: u64,
+    name: &'static str,
+    interval: Duration,
+    repeat: bool,
+    /// `None` once a one-shot timer has fired
+    deadline: Option<Instant>,
+}
+
+/// The timers of all mounted Components, synced with the registrations after every view.
+#[derive(Debug, Default)]
+pub(crate) struct Timers {
+    timers: Vec<ScheduledTimer>,
+    /// Deadline the window was last asked to wake us up at
+    scheduled: Option<Instant>,
+}
+
+impl Timers {
+    /// Starts newly registered timers and cancels the ones that are no longer registered.
+    pub(crate) fn sync(&mut self, registrations: &[Registration], now: Instant) {
+        let mut timers = Vec::with_capacity(self.timers.len());
+
+        for (register, node_id) in registrations.iter() {
+            if let Register::Timer {
+                name,
+                interval,
+                repeat,
+            } = *register
+            {
+                let existing = self
+                    .timers
+                    .iter()
+                    .position(|t| t.node_id == *node_id && t.name == name);
+                let timer = match existing {
+                    Some(i) => {
+                        let mut timer = self.timers.swap_remove(i);
+                        timer.interval = interval;
+                        timer.repeat = repeat;
+                        timer
+                    }
+                    None => ScheduledTimer {
+                        node_id: *node_id,
+                        name,
+                        interval,
+                        repeat,
+                        deadline: Some(now + interval),
+                    },
+                };
+                timers.push(timer);
+            }
+        }
+
+        self.timers = timers;
+    }
+
+    /// Returns the node ids and names of the timers that expired by `now`, and re-arms the repeating ones.
+    pub(crate) fn expire(&mut self, now: Instant) -> Vec<(u64, &'static str)> {
+        // the window woke us up, or the deadline is stale
+        if self.scheduled.map(|s| s <= now).unwrap_or(false) {
+            self.scheduled = None;
+        }
+
+        let mut expired = vec![];
+        for timer in self.timers.iter_mut() {
+            match timer.deadline {
+                Some(deadline) if deadline <= now => {
+                    expired.push((timer.node_id, timer.name));
+                    timer.deadline = if timer.repeat {
+                        // skip the intervals we missed instead of firing them all at once
+                        let mut next = deadline + timer.interval;
+                        while next <= now && !timer.interval.is_zero() {
+                            next += timer.interval;
+                        }
+                        Some(next.max(now + Duration::from_millis(1)))
+                    } else {
+                        None
+                    };
+                }
+                _ => (),
+            }
+        }
+        expired
+    }
+
+    /// Returns the earliest deadline if the window needs to be asked to wake us up for it.
+    pub(crate) fn schedule(&mut self) -> Option<Instant> {
+        let next = self.timers.iter().filter_map(|t| t.deadline).min()?;
+        match self.scheduled {
+            Some(scheduled) if scheduled <= next => None,
+            _ => {
+                self.scheduled = Some(next);
+                Some(next)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timers_fire_and_cancel() {
+        let now = Instant::now();
+        let mut timers = Timers::default();
+        let registrations = vec![
+            (Register::timeout("once", Duration::from_millis(10)), 1),
+            (Register::interval("blink", Duration::from_millis(20)), 2),
+        ];
+        timers.sync(&registrations, now);
+        assert_eq!(timers.schedule(), Some(now + Duration::from_millis(10)));
+        // already scheduled
+        assert_eq!(timers.schedule(), None);
+
+        let expired = timers.expire(now + Duration::from_millis(10));
+        assert_eq!(expired, vec![(1, "once")]);
+        assert_eq!(timers.schedule(), Some(now + Duration::from_millis(20)));
+
+        let expired = timers.expire(now + Duration::from_millis(45));
+        assert_eq!(expired, vec![(2, "blink")]);
+        assert_eq!(timers.schedule(), Some(now + Duration::from_millis(60)));
+
+        // the one-shot timer doesn't fire again while it stays registered
+        timers.sync(&registrations, now + Duration::from_millis(50));
+        assert_eq!(
+            timers.expire(now + Duration::from_millis(60)),
+            vec![(2, "blink")]
+        );
+
+        // unmounted
+        timers.sync(&[], now + Duration::from_millis(70));
+        assert!(timers.expire(now + Duration::from_secs(1)).is_empty());
+        assert_eq!(timers.schedule(), None);
+    }
+}
@@ -639,6 +639,100 @@ impl Div<f32> for AABB {
     }
 }
 
+/// A 2D transform of a node and its children, applied when they are drawn and hit tested,
+/// without laying them out again. They are scaled, then rotated, then translated, around `origin`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Transform {
+    /// In logical pixels
+    pub translate: Point,
+    pub scale: Point,
+    /// Clockwise, in radians
+    pub rotate: f32,
+    /// Relative to the size of the node, `(0.5, 0.5)` is its center
+    pub origin: Point,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            translate: Point::new(0.0, 0.0),
+            scale: Point::new(1.0, 1.0),
+            rotate: 0.0,
+            origin: Point::new(0.5, 0.5),
+        }
+    }
+}
+
+impl Transform {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn translate(mut self, x: f32, y: f32) -> Self {
+        self.translate = Point::new(x, y);
+        self
+    }
+
+    pub fn scale(mut self, scale: f32) -> Self {
+        self.scale = Point::new(scale, scale);
+        self
+    }
+
+    pub fn scale_xy(mut self, x: f32, y: f32) -> Self {
+        self.scale = Point::new(x, y);
+        self
+    }
+
+    pub fn rotate(mut self, radians: f32) -> Self {
+        self.rotate = radians;
+        self
+    }
+
+    pub fn origin(mut self, x: f32, y: f32) -> Self {
+        self.origin = Point::new(x, y);
+        self
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.translate == Point::new(0.0, 0.0)
+            && self.scale == Point::new(1.0, 1.0)
+            && self.rotate == 0.0
+    }
+
+    /// The matrix `[a, b, c, d, e, f]`, that maps `(x, y)` to `(a*x + c*y + e, b*x + d*y + f)`,
+    /// for a node laid out at `aabb`, in physical pixels.
+    pub fn matrix(&self, aabb: AABB, scale_factor: f32) -> [f32; 6] {
+        let (sin, cos) = self.rotate.sin_cos();
+        let (a, b) = (cos * self.scale.x, sin * self.scale.x);
+        let (c, d) = (-sin * self.scale.y, cos * self.scale.y);
+        let origin = Point::new(
+            aabb.pos.x + self.origin.x * aabb.width(),
+            aabb.pos.y + self.origin.y * aabb.height(),
+        );
+        [
+            a,
+            b,
+            c,
+            d,
+            origin.x + self.translate.x * scale_factor - (a * origin.x + c * origin.y),
+            origin.y + self.translate.y * scale_factor - (b * origin.x + d * origin.y),
+        ]
+    }
+}
+
+/// A shape that a node and its children are clipped to, see
+/// [`Component#clip`][crate::Component#method.clip].
+#[derive(Debug, Clone)]
+pub enum Clip {
+    /// The AABB of the node with rounded corners: top left, top right, bottom right and
+    /// bottom left radius, in physical pixels.
+    RoundedRect((f32, f32, f32, f32)),
+    /// The ellipse that fits the AABB of the node, e.g. for round avatars.
+    Ellipse,
+    /// Any path, in physical pixels.
+    Path(femtovg::Path),
+}
+
 /// RGBA color struct, used for styling and rendering. Values are normalized (0.0--1.0) floating point.
 #[derive(Debug, Copy, Clone, PartialEq, Pod, Zeroable, Serialize, Deserialize)]
 #[repr(C)]
@@ -917,6 +1011,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_transform_matrix() {
+        let aabb = AABB::new(Pos::new(10.0, 10.0, 0.0), Scale::new(20.0, 20.0));
+        let apply =
+            |m: [f32; 6], x: f32, y: f32| (m[0] * x + m[2] * y + m[4], m[1] * x + m[3] * y + m[5]);
+
+        // around the center
+        let m = Transform::new().scale(2.0).matrix(aabb, 1.0);
+        assert_eq!(apply(m, 10.0, 10.0), (0.0, 0.0));
+        assert_eq!(apply(m, 20.0, 20.0), (20.0, 20.0));
+
+        let m = Transform::new().translate(5.0, 0.0).matrix(aabb, 2.0);
+        assert_eq!(apply(m, 10.0, 10.0), (20.0, 10.0));
+
+        // clockwise around the top left
+        let m = Transform::new()
+            .rotate(std::f32::consts::FRAC_PI_2)
+            .origin(0.0, 0.0)
+            .matrix(aabb, 1.0);
+        let (x, y) = apply(m, 30.0, 10.0);
+        assert!((x - 10.0).abs() < 1e-4 && (y - 30.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_asset_variants() {
+        assert_eq!(variant_path("assets/icon.png", 2.0), "assets/icon@2x.png");
+        assert_eq!(variant_path("icon.png", 1.5), "icon@1.5x.png");
+        assert_eq!(variant_path("icon", 3.0), "icon@3x");
+
+        let variants = [
+            (1.0, "icon.png".to_string()),
+            (3.0, "icon@3x.png".to_string()),
+            (2.0, "icon@2x.png".to_string()),
+        ];
+        assert_eq!(closest_variant(&variants, 1.0), 0);
+        assert_eq!(closest_variant(&variants, 1.25), 0);
+        // Halfway goes to the bigger one
+        assert_eq!(closest_variant(&variants, 1.5), 2);
+        assert_eq!(closest_variant(&variants, 2.75), 1);
+        assert_eq!(closest_variant(&variants, 8.0), 1);
+
+        let params = AssetParams::new("icon.png".to_string()).variant(2.0, "icon-hd.png");
+        assert_eq!(params.select(1.0), (1.0, "icon.png".to_string()));
+        assert_eq!(params.select(2.0), (2.0, "icon-hd.png".to_string()));
+    }
+
     #[test]
     fn test_color_from() {
         // A float that is representable in 8 bits:
@@ -932,11 +1072,49 @@ pub enum ImgFilter {
     GRAY,
 }
 
+/// A step applied to an asset after it is decoded, see [`AssetParams#method.transform`]. Sizes are in
+/// pixels of the 1x variant of the asset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImgTransform {
+    /// Adds to the color channels, from -1 (black) to 1 (white)
+    Brightness(f32),
+    /// Scales the distance of the color channels from mid grey: 0 is flat grey, 1 keeps the image
+    Contrast(f32),
+    /// Mixes the colors with a color, from 0 (none) to 1 (only the color). Keeps the alpha
+    Tint(Color, f32),
+    /// Sepia tone, from 0 (none) to 1
+    Sepia(f32),
+    Crop {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    Resize {
+        width: u32,
+        height: u32,
+    },
+    /// Makes the corners outside of a rounded rectangle of this radius transparent
+    RoundedMask(f32),
+    /// Gaussian blur of this sigma
+    Blur(f32),
+}
+
+/// Scales of the variants found next to an asset when it lists none, like `icon@2x.png`.
+const VARIANT_SCALES: [f32; 4] = [1.5, 2.0, 3.0, 4.0];
+
 #[derive(Debug, Clone)]
 pub struct AssetParams {
+    /// The image for a scale factor of 1
     pub path: String,
     pub filter: ImgFilter,
     pub blur: Option<f32>,
+    /// Versions of the image for other scale factors, as `(scale, path)`
+    pub variants: Vec<(f32, String)>,
+    /// Applied in order, after `blur` and `filter`
+    pub transforms: Vec<ImgTransform>,
+    /// Decode every frame, see [`animated`][AssetParams#method.animated]
+    pub animated: bool,
 }
 
 impl AssetParams {
@@ -945,6 +1123,70 @@ impl AssetParams {
             path,
             filter: ImgFilter::default(),
             blur: None,
+            variants: vec![],
+            transforms: vec![],
+            animated: false,
+        }
+    }
+
+    /// Decode every frame of a GIF, APNG or animated WebP, to play it with
+    /// [`AnimatedImage`][crate::widgets::AnimatedImage]. `blur`, `filter` and the transforms don't apply.
+    pub fn animated(mut self) -> Self {
+        self.animated = true;
+        self
+    }
+
+    /// Adds a step to the transforms. The result is cached on disk, see
+    /// [`ImageLoader#set_disk_cache`][crate::renderer::image_loader::ImageLoader#method.set_disk_cache].
+    pub fn transform(mut self, transform: ImgTransform) -> Self {
+        self.transforms.push(transform);
+        self
+    }
+
+    /// Adds a version of the image for displays of `scale`. Without any, the versions named like
+    /// `icon@2x.png` next to `icon.png` are used.
+    pub fn variant<S: Into<String>>(mut self, scale: f32, path: S) -> Self {
+        self.variants.push((scale, path.into()));
+        self
+    }
+
+    /// The version of the image closest to `scale_factor`, as `(scale, path)`.
+    pub fn select(&self, scale_factor: f32) -> (f32, String) {
+        let mut candidates = vec![(1.0, self.path.clone())];
+        if self.variants.is_empty() {
+            candidates.extend(
+                VARIANT_SCALES
+                    .iter()
+                    .map(|scale| (*scale, variant_path(&self.path, *scale)))
+                    .filter(|(_, path)| std::path::Path::new(path).is_file()),
+            );
+        } else {
+            candidates.extend(self.variants.iter().cloned());
+        }
+        candidates.swap_remove(closest_variant(&candidates, scale_factor))
+    }
+}
+
+/// `icon.png` at 2x is `icon@2x.png`.
+fn variant_path(path: &str, scale: f32) -> String {
+    let path = std::path::Path::new(path);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(extension) => format!("{}@{}x.{}", stem, scale, extension.to_string_lossy()),
+        None => format!("{}@{}x", stem, scale),
+    };
+    path.with_file_name(name).to_string_lossy().into_owned()
+}
+
+/// Index of the variant closest to `scale_factor`. Ties go to the bigger one, which looks better scaled down.
+fn closest_variant(variants: &[(f32, String)], scale_factor: f32) -> usize {
+    let mut best = 0;
+    for (i, (scale, _)) in variants.iter().enumerate() {
+        let distance = (scale - scale_factor).abs();
+        let best_distance = (variants[best].0 - scale_factor).abs();
+        if distance < best_distance || (distance == best_distance && *scale > variants[best].0) {
+            best = i;
         }
     }
+    best
 }
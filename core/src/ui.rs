@@ -3,19 +3,24 @@ use crate::event::{self, Event, EventCache, EventInput};
 use crate::input::*;
 use crate::layout::*;
 use crate::raw_handle::RawWaylandHandle;
-use crate::renderer::canvas::{self, GlCanvasContext};
+use crate::renderer::canvas::GlCanvasContext;
 use crate::renderer::gl::{self};
 use crate::renderer::Renderer;
+use crate::timer::Timers;
 use crate::{component::Component, node::Node, types::PixelSize};
 use crate::{lay, node::Registration, size, types::*, window::Window};
 use crossbeam_channel::{unbounded, Receiver, Sender};
+use glutin::surface::GlSurface;
 use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
 use std::any::Any;
-use std::collections::HashMap;
+use std::num::NonZeroU32;
 use std::{
     cell::UnsafeCell,
     marker::PhantomData,
-    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    },
     thread::{self, JoinHandle},
     time::Instant,
 };
@@ -23,6 +28,10 @@ use std::{
 // This can become feature-dependant
 type ActiveRenderer = crate::renderer::canvas::CanvasRenderer;
 
+/// Puts the Node viewed by the root Component inside of the decorations of the window, like a
+/// client-side title bar and frame. See [`UI#set_decorations`][UI#method.set_decorations].
+pub type Decorate = Arc<dyn Fn(Node) -> Node + Send + Sync>;
+
 pub struct UI<W: Window, A: Component + Default + Send + Sync, B> {
     renderer: Arc<RwLock<Option<ActiveRenderer>>>,
     pub window: Arc<RwLock<W>>,
@@ -39,12 +48,19 @@ pub struct UI<W: Window, A: Component + Default + Send + Sync, B> {
     event_cache: EventCache,
     node_dirty: Arc<RwLock<bool>>,
     frame_dirty: Arc<RwLock<bool>>,
+    /// A frame callback was requested from the window and has not arrived yet
+    animation_frame_requested: Arc<AtomicBool>,
+    /// A style transition or layout animation was running in the last render
+    transitions_running: Arc<AtomicBool>,
+    timers: Arc<RwLock<Timers>>,
+    decorate: Arc<RwLock<Option<Decorate>>>,
     app_params: B,
 }
 
 #[derive(PartialEq)]
 enum RenderMessage {
     Render,
+    Resize { width: u32, height: u32 },
     Exit,
 }
 
@@ -146,6 +162,10 @@ impl<
             logical_size,
             event_cache,
             node_dirty,
+            animation_frame_requested: Default::default(),
+            transitions_running: Default::default(),
+            timers: Default::default(),
+            decorate: Default::default(),
         };
         n
     }
@@ -166,6 +186,10 @@ impl<
         frame_dirty: Arc<RwLock<bool>>,
         node_dirty: Arc<RwLock<bool>>,
         registrations: Arc<RwLock<Vec<Registration>>>,
+        timers: Arc<RwLock<Timers>>,
+        animation_frame_requested: Arc<AtomicBool>,
+        transitions_running: Arc<AtomicBool>,
+        decorate: Arc<RwLock<Option<Decorate>>>,
         window: Arc<RwLock<W>>,
     ) -> JoinHandle<()> {
         thread::spawn(move || {
@@ -197,7 +221,25 @@ impl<
                         // We need to acquire a lock on the node once we `view` it, because we remove its state at this point
                         let mut old = node.write().unwrap();
                         let mut new_registrations: Vec<Registration> = vec![];
-                        new.view(Some(&mut old), &mut new_registrations);
+                        new.view_decorated(
+                            Some(&mut old),
+                            &mut new_registrations,
+                            decorate.read().unwrap().as_ref(),
+                        );
+
+                        // Timers of unmounted nodes are cancelled here
+                        let deadline = {
+                            let mut timers = timers.write().unwrap();
+                            timers.sync(&new_registrations, Instant::now());
+                            timers.schedule()
+                        };
+                        if let Some(deadline) = deadline {
+                            window.read().unwrap().schedule_timer(deadline);
+                        }
+                        if Self::wants_animation_frame(&new_registrations) {
+                            Self::request_animation_frame(&animation_frame_requested, &window);
+                        }
+
                         *registrations.write().unwrap() = new_registrations;
 
                         let renderer = renderer.read().unwrap();
@@ -213,6 +255,13 @@ impl<
 
                         do_render = new.render(caches, Some(&mut old), scale_factor);
 
+                        // Keep redrawing while styles and layouts transition
+                        let running = new.transitions_running();
+                        transitions_running.store(running, Ordering::SeqCst);
+                        if running {
+                            Self::request_animation_frame(&animation_frame_requested, &window);
+                        }
+
                         *old = new;
                     }
                     {
@@ -241,8 +290,13 @@ impl<
         }
         // reconfigure the renderer
         let window = self.window.clone();
-        let assets = window.read().unwrap().assets();
-        let renderer = Arc::new(RwLock::new(Some(ActiveRenderer::new(window.clone()))));
+        let resources = window.read().unwrap().resources();
+        let active_renderer = ActiveRenderer::new(window.clone());
+        // decoded in the background, and uploaded as they finish
+        for (name, params) in resources.assets().iter() {
+            resources.images().load(name.clone(), params.clone());
+        }
+        let renderer = Arc::new(RwLock::new(Some(active_renderer)));
 
         self.renderer = renderer.clone();
 
@@ -267,6 +321,10 @@ impl<
             frame_dirty.clone(),
             node_dirty,
             registrations,
+            self.timers.clone(),
+            self.animation_frame_requested.clone(),
+            self.transitions_running.clone(),
+            self.decorate.clone(),
             window.clone(),
         );
 
@@ -274,12 +332,10 @@ impl<
             r_receiver,
             wayland_handle,
             scale_factor.clone(),
-            assets,
             renderer.clone(),
             node.clone(),
             self.logical_size.clone(),
             frame_dirty.clone(),
-            window.clone(),
         );
 
         self._draw_thread = Some(draw_thread);
@@ -293,52 +349,31 @@ impl<
     }
 
     pub fn resize(&mut self, width: u32, height: u32) {
-        let mut window = self.window.write().unwrap();
-        let wayland_handle =
-            RawWaylandHandle(window.raw_display_handle(), window.raw_window_handle());
-        let assets = window.assets();
-
-        // update the size for window, ui
-        window.set_size(width, height);
-        self.logical_size = Arc::new(RwLock::new(window.logical_size()));
-
-        // update the renderer canvas
-        let mut renderer = self.renderer.write().unwrap();
+        {
+            let mut window = self.window.write().unwrap();
 
-        if renderer.is_none() {
-            return;
+            // update the size for window, ui
+            window.set_size(width, height);
+            *self.logical_size.write().unwrap() = window.logical_size();
         }
 
-        // clear any caches stored with the renderer
-        renderer.as_mut().unwrap().resize(width, height);
-
-        // kill the existing thread
-        self.render_channel
-            .as_ref()
-            .unwrap()
-            .send(RenderMessage::Exit)
-            .unwrap();
+        {
+            let mut renderer = self.renderer.write().unwrap();
 
-        // create the render thread again, so that new gl_context is created
-        // Create a channel to speak to the renderer. Every time we send to this channel we want to trigger a render;
-        let (render_channel, r_receiver) = unbounded::<RenderMessage>();
+            if renderer.is_none() {
+                return;
+            }
 
-        let render_thread = Self::render_thread(
-            r_receiver,
-            wayland_handle,
-            self.scale_factor.clone(),
-            assets,
-            self.renderer.clone(),
-            self.node.clone(),
-            self.logical_size.clone(),
-            self.frame_dirty.clone(),
-            self.window.clone(),
-        );
+            renderer.as_mut().unwrap().resize(width, height);
+        }
 
-        self.render_thread = Some(render_thread);
-        self.render_channel = Some(render_channel);
+        // the render thread resizes its surface and canvas, keeping the
+        // gl context and everything uploaded to it
+        if let Some(render_channel) = self.render_channel.as_ref() {
+            let _ = render_channel.send(RenderMessage::Resize { width, height });
+        }
 
-        // // mark node dirty, so that we can redraw
+        // mark node dirty, so that we can redraw
         *self.node_dirty.write().unwrap() = true;
     }
 
@@ -363,16 +398,14 @@ impl<
         receiver: Receiver<RenderMessage>,
         raw_wayland_handle: RawWaylandHandle,
         scale_factor: Arc<RwLock<f32>>,
-        assets: HashMap<String, AssetParams>,
         renderer: Arc<RwLock<Option<ActiveRenderer>>>,
         node: Arc<RwLock<Node>>,
         logical_size: Arc<RwLock<PixelSize>>,
         frame_dirty: Arc<RwLock<bool>>,
-        window: Arc<RwLock<W>>,
     ) -> JoinHandle<()> {
-        let size = logical_size.read().unwrap();
-        let width = size.width;
-        let height = size.height;
+        let size = *logical_size.read().unwrap();
+        let mut width = size.width;
+        let mut height = size.height;
 
         thread::spawn(move || {
             // let scale_factor = window.scale_factor();
@@ -382,17 +415,14 @@ impl<
 
             let (gl_display, gl_surface, gl_context) =
                 gl::init_gl(raw_display_handle, raw_window_handle, (width, height));
-            let mut gl_canvas =
+            let gl_canvas =
                 gl::init_gl_canvas(&gl_display, (width, height), *scale_factor.read().unwrap());
 
-            // load assets
-            let images = canvas::load_assets_to_canvas(&mut gl_canvas, assets);
-
             let mut gl_context = GlCanvasContext {
                 gl_canvas,
                 gl_context,
                 gl_surface,
-                images,
+                images: Default::default(),
             };
 
             for msg in receiver.iter() {
@@ -401,6 +431,32 @@ impl<
                     break;
                 }
 
+                if let RenderMessage::Resize {
+                    width: new_width,
+                    height: new_height,
+                } = msg
+                {
+                    let (Some(surface_width), Some(surface_height)) =
+                        (NonZeroU32::new(new_width), NonZeroU32::new(new_height))
+                    else {
+                        continue;
+                    };
+                    width = new_width;
+                    height = new_height;
+
+                    gl_context.gl_surface.resize(
+                        &gl_context.gl_context,
+                        surface_width,
+                        surface_height,
+                    );
+                    gl_context
+                        .gl_canvas
+                        .set_size(width, height, *scale_factor.read().unwrap());
+
+                    // render with the new size once the draw thread is done
+                    continue;
+                }
+
                 if *frame_dirty.read().unwrap() {
                     let node = node.read().unwrap();
 
@@ -417,11 +473,6 @@ impl<
                     );
 
                     *frame_dirty.write().unwrap() = false;
-
-                    // request next frame
-                    let window = window.read();
-                    // println!("window::redraw start {:?}", do_render);
-                    window.unwrap().next_frame();
                 }
             }
         })
@@ -471,6 +522,57 @@ impl<
         }
     }
 
+    fn wants_animation_frame(registrations: &[Registration]) -> bool {
+        registrations
+            .iter()
+            .any(|(register, _)| matches!(register, event::Register::AnimationFrame))
+    }
+
+    /// Ask the window for a frame callback, which comes back as [`Input::Timer`].
+    /// Only done while some node registers [`Register::AnimationFrame`][event::Register::AnimationFrame].
+    fn request_animation_frame(requested: &AtomicBool, window: &RwLock<W>) {
+        if !requested.swap(true, Ordering::SeqCst) {
+            window.read().unwrap().next_frame();
+        }
+    }
+
+    /// Sends the images that finished decoding to the nodes that registered [`Register::ImageLoad`][event::Register::ImageLoad],
+    /// and renders a frame with them, or with the pixels pushed to image handles.
+    fn handle_image_loads(&mut self) {
+        let (finished, updated) = match self.renderer.read().unwrap().as_ref() {
+            Some(renderer) => {
+                let images = renderer.image_target();
+                // Changed SVGs are redrawn like updated images
+                let svgs_updated = renderer.svg_target().take_updated();
+                (
+                    images.take_finished(),
+                    images.take_updated() || svgs_updated,
+                )
+            }
+            None => return,
+        };
+        if finished.is_empty() && !updated {
+            return;
+        }
+
+        for load in finished {
+            let mut event = Event::new(load, &self.event_cache);
+            event.registrations = self.registrations.read().unwrap().clone();
+            self.node_mut().image_load(&mut event);
+            self.handle_dirty_event(&event);
+        }
+        // The render thread uploads them, even if no node changed
+        *self.frame_dirty.write().unwrap() = true;
+        self.render();
+    }
+
+    fn schedule_timers(&mut self) {
+        let deadline = self.timers.write().unwrap().schedule();
+        if let Some(deadline) = deadline {
+            self.window.read().unwrap().schedule_timer(deadline);
+        }
+    }
+
     fn handle_event<T: EventInput, F>(
         &mut self,
         handler: F,
@@ -516,6 +618,9 @@ impl<
                         self.window.read().unwrap().logical_size();
                     *self.scale_factor.write().unwrap() = scale_factor;
                     self.event_cache.scale_factor = scale_factor;
+                    if let Some(renderer) = self.renderer.read().unwrap().as_ref() {
+                        renderer.image_target().set_scale_factor(scale_factor);
+                    }
                     *self.node_dirty.write().unwrap() = true;
                 }
             }
@@ -847,9 +952,30 @@ impl<
                 self.handle_dirty_event(&event);
             }
             Input::Timer => {
+                self.animation_frame_requested
+                    .store(false, Ordering::SeqCst);
                 let mut event = Event::new(event::Tick, &self.event_cache);
+                event.registrations = self.registrations.read().unwrap().clone();
                 self.node_mut().tick(&mut event);
+                if self.transitions_running.load(Ordering::SeqCst) {
+                    event.dirty();
+                }
                 self.handle_dirty_event(&event);
+                // keep animating until the nodes stop registering for frames
+                if Self::wants_animation_frame(&event.registrations) {
+                    Self::request_animation_frame(&self.animation_frame_requested, &self.window);
+                }
+            }
+            Input::TimerExpired => {
+                let expired = self.timers.write().unwrap().expire(Instant::now());
+                self.schedule_timers();
+                for (node_id, name) in expired {
+                    let mut event = Event::new(event::Timer { name }, &self.event_cache);
+                    event.target = Some(node_id);
+                    self.node_mut().timer(&mut event);
+                    self.handle_dirty_event(&event);
+                }
+                self.handle_image_loads();
             }
             Input::MouseLeaveWindow => {
                 if self.event_cache.mouse_over.is_some() {
@@ -980,10 +1106,32 @@ impl<
         // send draw request, it will draw if node is dirty
     }
 
-    /// Calls [`Component#update`][Component#method.update] with `msg` on the root Node of the application. This will always trigger a redraw.
-    pub fn update(&mut self, msg: Message) {
-        self.node_mut().component.update(msg);
+    /// Draws the Node viewed by the root Component inside of the Node returned by `decorate`, which
+    /// is called with it on every view. The root Component keeps its Node, so
+    /// [`update`][UI#method.update] and [`state_mut`][UI#method.state_mut] still reach it, and
+    /// the Messages of its children go through the decorations before reaching it.
+    pub fn set_decorations(&mut self, decorate: Decorate) {
+        *self.decorate.write().unwrap() = Some(decorate);
+        self.decorations_changed();
+    }
+
+    /// Views the root Component again, for when what the decorations draw changed.
+    pub fn decorations_changed(&mut self) {
         *self.node_dirty.write().unwrap() = true;
+        self.draw();
+    }
+
+    /// Calls [`Component#update`][Component#method.update] with `msg` on the root Node of the application. This will trigger a redraw if the root Component changed its state.
+    pub fn update(&mut self, msg: Message) {
+        let dirty = {
+            let mut node = self.node_mut();
+            node.component.update(msg);
+            node.component.is_dirty()
+        };
+
+        if dirty {
+            *self.node_dirty.write().unwrap() = true;
+        }
     }
 
     /// Calls the equivalent of [`state_mut`][crate::state_component_impl] on the root Node of the application, and passes it as an arg to given closure `f`.
@@ -0,0 +1,222 @@
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+use mctk_macros::{component, state_component_impl};
+
+use crate::component::{Component, ComponentHasher, Message, RenderContext};
+
+use crate::event;
+use crate::renderables::image::{ImageFit, InstanceBuilder as ImageInstanceBuilder};
+use crate::renderables::rect::InstanceBuilder as RectInstanceBuilder;
+use crate::renderables::types::Point;
+use crate::renderables::{self, Rect, Renderable};
+use crate::renderer::image_animation::Playback;
+use crate::style::Styled;
+use crate::types::*;
+
+#[derive(Debug, Default)]
+struct AnimatedImageState {
+    /// Set once the frames finished decoding
+    loaded: Option<Result<(), String>>,
+    frame_delays: Vec<Duration>,
+    playback: Playback,
+    last_tick: Option<Instant>,
+}
+
+/// Plays a GIF, APNG or animated WebP, decoded in the background. Its `fit`, `align` and `radius`
+/// are style parameters, like for [`Image`][super::Image].
+///
+/// The frame drawn is uploaded in place to the texture of `name`, so `AnimatedImage`s of the same
+/// `name` in a window show the frame of whichever is drawn last: give each its own `name` to play
+/// them independently.
+#[component(State = "AnimatedImageState", Styled)]
+pub struct AnimatedImage {
+    pub name: String,
+    pub path: String,
+    pub playing: bool,
+    /// How many times to play the animation, forever when `None`
+    pub loop_count: Option<u32>,
+    /// Multiplies the delays of the frames by its inverse, `2.0` plays twice as fast
+    pub speed: f32,
+    pub on_finish: Option<Box<dyn Fn() -> Message + Send + Sync>>,
+    pub on_error: Option<Box<dyn Fn(String) -> Message + Send + Sync>>,
+}
+
+impl std::fmt::Debug for AnimatedImage {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("AnimatedImage")
+            .field("name", &self.name)
+            .field("path", &self.path)
+            .field("playing", &self.playing)
+            .finish()
+    }
+}
+
+impl AnimatedImage {
+    /// Decodes the animation at `path` as the image `name`, and plays it once it is loaded.
+    pub fn new<S: Into<String>, P: Into<String>>(name: S, path: P) -> Self {
+        Self {
+            name: name.into(),
+            path: path.into(),
+            playing: true,
+            loop_count: None,
+            speed: 1.0,
+            on_finish: None,
+            on_error: None,
+            state: Some(AnimatedImageState::default()),
+            dirty: false,
+            class: Default::default(),
+            style_overrides: Default::default(),
+        }
+    }
+
+    /// Pauses on the current frame when `false`.
+    pub fn playing(mut self, playing: bool) -> Self {
+        self.playing = playing;
+        self
+    }
+
+    pub fn loop_count(mut self, count: u32) -> Self {
+        self.loop_count = Some(count);
+        self
+    }
+
+    pub fn speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Called once the last of `loop_count` loops finished.
+    pub fn on_finish(mut self, f: Box<dyn Fn() -> Message + Send + Sync>) -> Self {
+        self.on_finish = Some(f);
+        self
+    }
+
+    pub fn on_error(mut self, f: Box<dyn Fn(String) -> Message + Send + Sync>) -> Self {
+        self.on_error = Some(f);
+        self
+    }
+
+    fn is_playing(&self) -> bool {
+        let state = self.state_ref();
+        self.playing
+            && self.speed > 0.0
+            && matches!(state.loaded, Some(Ok(())))
+            && state.frame_delays.len() > 1
+            && !state.playback.is_finished(self.loop_count)
+    }
+}
+
+#[state_component_impl(AnimatedImageState)]
+impl Component for AnimatedImage {
+    fn register(&mut self) -> Vec<event::Register> {
+        if self.state_ref().loaded.is_none() {
+            vec![event::Register::ImageLoad]
+        } else if self.is_playing() {
+            vec![event::Register::AnimationFrame]
+        } else {
+            // Paused, so that playing again starts from the next tick instead of jumping ahead
+            if self.state_ref().last_tick.is_some() {
+                self.state_mut().last_tick = None;
+            }
+            vec![]
+        }
+    }
+
+    fn on_image_load(&mut self, event: &mut event::Event<event::ImageLoad>) {
+        // Loads replayed for a new `AnimatedImage` may come more than once
+        if event.input.name != self.name || self.state_ref().loaded.is_some() {
+            return;
+        }
+        if let (Err(e), Some(f)) = (&event.input.result, &self.on_error) {
+            event.emit(f(e.clone()));
+        }
+        let state = self.state_mut();
+        state.loaded = Some(event.input.result.clone());
+        state.frame_delays = event.input.frame_delays.clone();
+        state.last_tick = None;
+    }
+
+    fn on_tick(&mut self, event: &mut event::Event<event::Tick>) {
+        if !self.is_playing() {
+            return;
+        }
+        let (speed, loop_count) = (self.speed, self.loop_count);
+        let now = Instant::now();
+        let state = self.state_mut();
+        let last_tick = state.last_tick.replace(now).unwrap_or(now);
+        // Frames shorter than a tick are skipped, to keep the pace of the animation
+        let dt = (now - last_tick).mul_f32(speed);
+
+        let finished = state.playback.advance(&state.frame_delays, dt, loop_count);
+        if finished {
+            state.last_tick = None;
+            if let Some(f) = &self.on_finish {
+                event.emit(f());
+            }
+        }
+    }
+
+    fn render_hash(&self, hasher: &mut ComponentHasher) {
+        self.name.hash(hasher);
+        self.path.hash(hasher);
+        self.state_ref().loaded.hash(hasher);
+        self.state_ref().playback.frame.hash(hasher);
+        self.style_val("radius")
+            .unwrap()
+            .f32()
+            .to_bits()
+            .hash(hasher);
+        self.style_val("fit").unwrap().str().hash(hasher);
+        let align = self.style_val("align").unwrap().point();
+        (align.x.to_bits(), align.y.to_bits()).hash(hasher);
+    }
+
+    fn render(&mut self, context: RenderContext) -> Option<Vec<Renderable>> {
+        // Made after the animation finished loading, so it missed the `ImageLoad` with the delays
+        if self.state_ref().loaded.is_none() {
+            context.caches.images.replay(&self.name);
+        }
+
+        let width = context.aabb.width();
+        let height = context.aabb.height();
+        let AABB { pos, .. } = context.aabb;
+        let radius = self.style_val("radius").unwrap().f32();
+        let fit = self.style_val("fit").unwrap().str();
+        let fit = ImageFit::from_name(fit).unwrap_or_else(|| {
+            println!("Unknown image fit {:?}", fit);
+            ImageFit::Fill
+        });
+        let align = self.style_val("align").unwrap().point();
+        let mut rs = vec![];
+
+        if !matches!(self.state_ref().loaded, Some(Ok(()))) {
+            let placeholder = RectInstanceBuilder::default()
+                .pos(pos)
+                .scale(Scale { width, height })
+                .color(self.style_val("placeholder_color").unwrap().color())
+                .radius((radius, radius, radius, radius))
+                .build()
+                .unwrap();
+            rs.push(Renderable::Rect(Rect::from_instance_data(placeholder)));
+        }
+
+        let instance = ImageInstanceBuilder::default()
+            .pos(pos)
+            .scale(Scale { width, height })
+            .name(self.name.clone())
+            .radius(radius)
+            .dynamic_load_from(Some(self.path.clone()))
+            .frame(Some(self.state_ref().playback.frame))
+            .fit(fit)
+            .align(Point::new(align.x, align.y))
+            .scale_factor(context.scale_factor)
+            .build()
+            .unwrap();
+        rs.push(Renderable::Image(renderables::Image::from_instance_data(
+            instance,
+        )));
+
+        Some(rs)
+    }
+}
@@ -81,21 +81,27 @@ impl Component for Button {
     fn view(&self) -> Option<Node> {
         let radius: f32 = self.style_val("radius").unwrap().f32();
         let padding: f64 = self.style_val("padding").unwrap().into();
-        let active_color: Color = self.style_val("active_color").into();
-        let highlight_color: Color = self.style_val("highlight_color").into();
-        let background_color: Color = self.style_val("background_color").into();
+        // the color shown is transitioned, rather than each of them
+        let active_color: Color = self.style_val_untransitioned("active_color").into();
+        let highlight_color: Color = self.style_val_untransitioned("highlight_color").into();
+        let background_color: Color = self.style_val_untransitioned("background_color").into();
         let border_color: Color = self.style_val("border_color").into();
         let border_width: f32 = self.style_val("border_width").unwrap().f32();
 
         let mut base = node!(
             super::RoundedRect {
-                background_color: if self.state_ref().pressed {
-                    active_color
-                } else if self.state_ref().hover {
-                    highlight_color
-                } else {
-                    background_color
-                },
+                background_color: self
+                    .transition_val(
+                        "background_color",
+                        if self.state_ref().pressed {
+                            active_color
+                        } else if self.state_ref().hover {
+                            highlight_color
+                        } else {
+                            background_color
+                        },
+                    )
+                    .into(),
                 border_color,
                 border_width,
                 radius: (radius, radius, radius, radius),
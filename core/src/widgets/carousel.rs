@@ -1,3 +1,4 @@
+use crate::animation::{Animated, Easing, Transition};
 use crate::component::{Component, ComponentHasher, RenderContext};
 use crate::event::{self, Event};
 use crate::layout::*;
@@ -7,25 +8,20 @@ use crate::types::*;
 use std::cmp;
 use std::hash::Hash;
 use std::ops::Neg;
+use std::time::Duration;
 
 use mctk_macros::{component, state_component_impl};
 
 #[derive(Debug, Default, Clone)]
 pub struct CarouselItem {}
 
-#[derive(Debug, Default)]
-pub struct TransitionPositions {
-    pub from: Point,
-    pub to: Point,
-    pub velocity: f32,
-}
+const SNAP_DURATION: u64 = 300; // millis
 
 #[derive(Debug, Default)]
 pub struct CarouselState {
-    scroll_position: Point,
+    scroll_position: Animated<Point>,
     drag_start_position: Point,
     dragged_over_child: Option<AABB>,
-    transition_positions: Option<TransitionPositions>,
 }
 
 #[component(State = "CarouselState", Styled = "Scroll", Internal)]
@@ -63,43 +59,18 @@ impl Carousel {
 }
 #[state_component_impl(CarouselState)]
 impl Component for Carousel {
-    fn on_tick(&mut self, event: &mut Event<event::Tick>) {
-        //Update scroll position based on velocity and frames per seconds
-        if let Some(TransitionPositions { from, to, velocity }) =
-            self.state_ref().transition_positions
-        {
-            let mut scroll_position = self.state_ref().scroll_position;
-            let distance = from.dist(to).round();
-            let distance_scrolled = scroll_position.dist(from).round();
-
-            // println!(
-            //     "{:?} {:?} {:?}",
-            //     distance,
-            //     scroll_position,
-            //     distance_scrolled != distance
-            // );
-            // println!(
-            //     "from {:?} to {:?} scolling position {:?}",
-            //     from, to, scroll_position
-            // );
-
-            if distance != 0. && scroll_position != to && distance_scrolled != distance {
-                if to.x >= from.x {
-                    scroll_position.x += distance * velocity;
-                } else {
-                    scroll_position.x -= distance * velocity;
-                }
-
-                self.state_mut().scroll_position = scroll_position;
-            } else {
-                self.state_mut().transition_positions = None;
-            }
+    fn register(&mut self) -> Vec<event::Register> {
+        // only redraw on every frame while snapping to a slide
+        if self.state_ref().scroll_position.is_animating() {
+            vec![event::Register::AnimationFrame]
+        } else {
+            vec![]
         }
     }
 
     fn scroll_position(&self) -> Option<ScrollPosition> {
         if self.scrollable() {
-            let p = self.state_ref().scroll_position;
+            let p = self.state_ref().scroll_position.get();
             Some(ScrollPosition {
                 x: if self.x_scrollable() { Some(p.x) } else { None },
                 y: if self.y_scrollable() { Some(p.y) } else { None },
@@ -122,17 +93,17 @@ impl Component for Carousel {
         //     event.over_child_n(),
         //     event.over_child_n_aabb()
         // );
-        let drag_start = self.state_ref().scroll_position;
+        self.state_mut().scroll_position.stop();
+        let drag_start = self.state_ref().scroll_position.get();
         self.state_mut().drag_start_position = drag_start;
         self.state_mut().dragged_over_child = event.over_child_n_aabb();
-        self.state_mut().transition_positions = None;
         event.stop_bubbling();
     }
     fn on_drag_end(&mut self, event: &mut Event<event::DragEnd>) {
         let dragged_on_child = self.state_ref().dragged_over_child.unwrap();
         let child_width = dragged_on_child.width();
 
-        let from_position = self.state_ref().scroll_position;
+        let from_position = self.state_ref().scroll_position.get();
         let prev_slide_x = (from_position.x / child_width).floor() * child_width;
         let next_slide_x = prev_slide_x + child_width;
         println!(
@@ -146,11 +117,13 @@ impl Component for Carousel {
         };
 
         //println!("to_position.x {:?}", to_position.x);
-        self.state_mut().transition_positions = Some(TransitionPositions {
-            from: from_position,
-            to: to_position,
-            velocity: 0.02,
-        });
+        self.state_mut().scroll_position.animate_to(
+            to_position,
+            Transition::Tween {
+                duration: Duration::from_millis(SNAP_DURATION),
+                easing: Easing::EaseOut,
+            },
+        );
         event.stop_bubbling();
     }
 
@@ -158,7 +131,7 @@ impl Component for Carousel {
         let start_position = self.state_ref().drag_start_position;
         let size = event.current_physical_aabb().size();
         let inner_scale = event.current_inner_scale().unwrap();
-        let mut scroll_position = self.state_ref().scroll_position;
+        let mut scroll_position = self.state_ref().scroll_position.get();
         let drag = event.physical_delta().x.neg();
 
         //println!("dragging delta {:?}", drag);
@@ -171,7 +144,7 @@ impl Component for Carousel {
             .min(max_position)
             .max(0.0);
 
-        self.state_mut().scroll_position = scroll_position;
+        self.state_mut().scroll_position.set(scroll_position);
 
         // event.stop_bubbling();
     }
@@ -0,0 +1,263 @@
+use std::hash::Hash;
+
+use crate::component::{Component, ComponentHasher, RenderContext};
+use crate::renderables::rect::InstanceBuilder;
+use crate::renderables::{Rect, Renderable};
+use crate::style::Styled;
+use crate::types::*;
+use crate::{event, lay, msg, rect, size, size_pct, txt};
+use crate::{node, node::Node};
+use mctk_macros::component;
+
+use super::{Button, Div, Text};
+
+/// Edge or corner of a window, used to start an interactive resize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResizeEdge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// Emitted by [`TitleBar`] and [`WindowFrame`], the window decorating them turns these
+/// into requests to the compositor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DecorationMessage {
+    Move,
+    Resize { edge: ResizeEdge },
+    Minimize,
+    ToggleMaximize,
+    Close,
+}
+
+/// Client-side window frame, starts an interactive resize when pressed on its edges.
+///
+/// Children are laid out in a column inside the resize handles.
+#[component(Styled, Internal)]
+#[derive(Debug)]
+pub struct WindowFrame {
+    pub resizable: bool,
+}
+
+impl Default for WindowFrame {
+    fn default() -> Self {
+        Self {
+            resizable: true,
+            class: Default::default(),
+            style_overrides: Default::default(),
+        }
+    }
+}
+
+impl WindowFrame {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Width of the resize handles, in logical pixels
+    pub fn handle_width(&self) -> f32 {
+        if self.resizable {
+            self.style_val("handle_width").unwrap().f32()
+        } else {
+            0.0
+        }
+    }
+
+    fn edge_at(&self, position: Point, size: Scale) -> Option<ResizeEdge> {
+        let handle_width = self.handle_width();
+        if handle_width <= 0.0 {
+            return None;
+        }
+
+        let left = position.x < handle_width;
+        let right = position.x > size.width - handle_width;
+        let top = position.y < handle_width;
+        let bottom = position.y > size.height - handle_width;
+
+        match (top, bottom, left, right) {
+            (true, _, true, _) => Some(ResizeEdge::TopLeft),
+            (true, _, _, true) => Some(ResizeEdge::TopRight),
+            (_, true, true, _) => Some(ResizeEdge::BottomLeft),
+            (_, true, _, true) => Some(ResizeEdge::BottomRight),
+            (true, _, _, _) => Some(ResizeEdge::Top),
+            (_, true, _, _) => Some(ResizeEdge::Bottom),
+            (_, _, true, _) => Some(ResizeEdge::Left),
+            (_, _, _, true) => Some(ResizeEdge::Right),
+            _ => None,
+        }
+    }
+}
+
+impl Component for WindowFrame {
+    fn render_hash(&self, hasher: &mut ComponentHasher) {
+        self.resizable.hash(hasher);
+    }
+
+    fn on_mouse_down(&mut self, event: &mut event::Event<event::MouseDown>) {
+        // presses over the children are not on the handles
+        if event.over_child_n().is_some() {
+            return;
+        }
+        let size = event.current_logical_aabb().size();
+        if let Some(edge) = self.edge_at(event.relative_logical_position(), size) {
+            event.emit(msg!(DecorationMessage::Resize { edge }));
+            event.stop_bubbling();
+        }
+    }
+
+    fn on_touch_down(&mut self, event: &mut event::Event<event::TouchDown>) {
+        if event.over_child_n().is_some() {
+            return;
+        }
+        let size = event.current_logical_aabb().size();
+        if let Some(edge) = self.edge_at(event.relative_logical_position_touch(), size) {
+            event.emit(msg!(DecorationMessage::Resize { edge }));
+            event.stop_bubbling();
+        }
+    }
+
+    fn render(&mut self, context: RenderContext) -> Option<Vec<Renderable>> {
+        let border_color: Color = self.style_val("border_color").into();
+        let border_width = self.style_val("border_width").unwrap().f32() * context.scale_factor;
+
+        if !self.resizable || border_width <= 0.0 {
+            return None;
+        }
+
+        let rect_instance = InstanceBuilder::default()
+            .pos(context.aabb.pos)
+            .scale(context.aabb.size())
+            .border_color(border_color)
+            .border_size(border_width)
+            .build()
+            .unwrap();
+
+        Some(vec![Renderable::Rect(Rect::from_instance_data(
+            rect_instance,
+        ))])
+    }
+}
+
+/// Draggable area of the [`TitleBar`] holding the title.
+#[derive(Debug, Default)]
+struct DragArea;
+
+impl Component for DragArea {
+    fn on_mouse_down(&mut self, event: &mut event::Event<event::MouseDown>) {
+        event.emit(msg!(DecorationMessage::Move));
+        event.stop_bubbling();
+    }
+
+    fn on_touch_down(&mut self, event: &mut event::Event<event::TouchDown>) {
+        event.emit(msg!(DecorationMessage::Move));
+        event.stop_bubbling();
+    }
+
+    fn on_double_click(&mut self, event: &mut event::Event<event::DoubleClick>) {
+        event.emit(msg!(DecorationMessage::ToggleMaximize));
+    }
+}
+
+/// Client-side title bar with the window title and minimize, maximize and close buttons.
+///
+/// Pressing the title starts an interactive move, double clicking it toggles maximize.
+#[component(Styled, Internal)]
+#[derive(Debug, Default)]
+pub struct TitleBar {
+    pub title: String,
+    pub maximized: bool,
+}
+
+impl TitleBar {
+    pub fn new<S: Into<String>>(title: S) -> Self {
+        Self {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn maximized(mut self, maximized: bool) -> Self {
+        self.maximized = maximized;
+        self
+    }
+
+    fn button(&self, label: &str, active_color: Color) -> Button {
+        Button::new(txt!(label.to_string()))
+            .style("font_size", self.style_val("button_font_size").unwrap())
+            .style("text_color", self.style_val("text_color").unwrap())
+            .style(
+                "background_color",
+                self.style_val("background_color").unwrap(),
+            )
+            .style(
+                "highlight_color",
+                self.style_val("button_highlight_color").unwrap(),
+            )
+            .style("active_color", active_color)
+            .style("border_color", self.style_val("background_color").unwrap())
+            .style("border_width", 0.0)
+            .style("radius", self.style_val("button_radius").unwrap())
+    }
+}
+
+impl Component for TitleBar {
+    fn props_hash(&self, hasher: &mut ComponentHasher) {
+        self.title.hash(hasher);
+        self.maximized.hash(hasher);
+    }
+
+    fn view(&self) -> Option<Node> {
+        let background_color: Color = self.style_val("background_color").into();
+        let button_active_color: Color = self.style_val("button_active_color").into();
+        let close_active_color: Color = self.style_val("close_active_color").into();
+        let button_width = self.style_val("button_width").unwrap().f32();
+        let padding = self.style_val("padding").unwrap().f32();
+
+        Some(
+            node!(
+                Div::new().bg(background_color),
+                lay![
+                    size_pct: [100.0],
+                    direction: Row,
+                    axis_alignment: Stretch,
+                    cross_alignment: Stretch,
+                    padding: [padding],
+                ]
+            )
+            .push(
+                node!(
+                    DragArea,
+                    lay![cross_alignment: Center, padding: [0., 8., 0., 8.]]
+                )
+                .push(node!(Text::new(txt!(self.title.clone()))
+                    .style("size", self.style_val("font_size").unwrap())
+                    .style("color", self.style_val("text_color").unwrap()))),
+            )
+            .push(node!(
+                self.button("–", button_active_color)
+                    .on_click(Box::new(|| msg!(DecorationMessage::Minimize))),
+                lay![size: size!(button_width, Auto)]
+            ))
+            .push(node!(
+                self.button(if self.maximized { "❐" } else { "□" }, button_active_color)
+                    .on_click(Box::new(|| msg!(DecorationMessage::ToggleMaximize))),
+                lay![size: size!(button_width, Auto)]
+            ))
+            .push(node!(
+                self.button("×", close_active_color)
+                    .on_click(Box::new(|| msg!(DecorationMessage::Close))),
+                lay![size: size!(button_width, Auto)]
+            )),
+        )
+    }
+}
@@ -4,8 +4,10 @@ use std::ops::Neg;
 use crate::component::{Component, ComponentHasher, RenderContext};
 use crate::event;
 use crate::layout::*;
+use crate::renderables::backdrop_blur::InstanceBuilder as BackdropBlurInstanceBuilder;
 use crate::renderables::rect::InstanceBuilder;
-use crate::renderables::{Rect, Renderable};
+use crate::renderables::shadow::InstanceBuilder as ShadowInstanceBuilder;
+use crate::renderables::{BackdropBlur, Rect, Renderable, Shadow};
 use crate::style::{HorizontalPosition, StyleVal, Styled, VerticalPosition};
 use crate::types::*;
 
@@ -26,13 +28,21 @@ pub struct DivState {
     scaled_scroll_bar_width: f32,
 }
 
+/// Whether what the children of a [`Div`] draw outside of it is shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    #[default]
+    Visible,
+    /// Clipped to the Div, and its rounded corners
+    Clip,
+}
+
 #[component(State = "DivState", Styled = "Scroll", Internal)]
 #[derive(Debug, Default)]
 pub struct Div {
-    pub background: Option<Color>,
     pub border_color: Option<Color>,
     pub border_width: Option<f32>,
-    pub radius: Option<(f32, f32, f32, f32)>,
+    pub overflow: Overflow,
 }
 
 impl Div {
@@ -40,9 +50,9 @@ impl Div {
         Self::default()
     }
 
-    pub fn bg<C: Into<Color>>(mut self, bg: C) -> Self {
-        self.background = Some(bg.into());
-        self
+    /// Sets the `"background_color"` style.
+    pub fn bg<C: Into<Color>>(self, bg: C) -> Self {
+        self.style("background_color", bg.into())
     }
 
     pub fn border<C: Into<Color>>(
@@ -53,7 +63,54 @@ impl Div {
     ) -> Self {
         self.border_color = Some(color.into());
         self.border_width = Some(width);
-        self.radius = Some(radius);
+        self.style("radius", radius)
+    }
+
+    fn background_color(&self) -> Color {
+        self.style_val("background_color").unwrap().color()
+    }
+
+    /// Radii of the corners, from the top left clockwise.
+    fn radius(&self) -> (f32, f32, f32, f32) {
+        self.style_val("radius").unwrap().corners()
+    }
+
+    /// Casts a shadow of `color`, offset by `x` and `y`, in logical pixels. Set the `"shadow_inset"`
+    /// style to cast it inside the Div.
+    pub fn shadow<C: Into<Color>>(self, color: C, x: f32, y: f32, blur: f32, spread: f32) -> Self {
+        self.style("shadow_color", color.into())
+            .style("shadow_offset", Point::new(x, y))
+            .style("shadow_blur", blur as f64)
+            .style("shadow_spread", spread as f64)
+    }
+
+    /// Blurs what is drawn behind the Div, with a gaussian blur of `sigma` logical pixels.
+    pub fn backdrop_blur(self, sigma: f32) -> Self {
+        self.style("backdrop_blur", sigma as f64)
+    }
+
+    fn shadow_renderable(&self, context: &RenderContext) -> Option<Renderable> {
+        let color = self.style_val("shadow_color").unwrap().color();
+        if color.a <= 0.0 {
+            return None;
+        }
+        let offset = self.style_val("shadow_offset").unwrap().point();
+        let instance = ShadowInstanceBuilder::default()
+            .pos(context.aabb.pos)
+            .scale(context.aabb.size())
+            .radius(self.radius())
+            .color(color)
+            .offset(offset * context.scale_factor)
+            .blur(self.style_val("shadow_blur").unwrap().f32() * context.scale_factor)
+            .spread(self.style_val("shadow_spread").unwrap().f32() * context.scale_factor)
+            .inset(self.style_val("shadow_inset").unwrap().bool())
+            .build()
+            .unwrap();
+        Some(Renderable::Shadow(Shadow::from_instance_data(instance)))
+    }
+
+    pub fn overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
         self
     }
 
@@ -169,9 +226,20 @@ impl Component for Div {
             self.state_ref().y_bar_pressed.hash(hasher);
             self.state_ref().x_bar_pressed.hash(hasher);
         }
-        if let Some(color) = self.background {
-            color.hash(hasher);
+        self.background_color().hash(hasher);
+        let (top_left, top_right, bottom_right, bottom_left) = self.radius();
+        [top_left, top_right, bottom_right, bottom_left]
+            .map(f32::to_bits)
+            .hash(hasher);
+        self.overflow.hash(hasher);
+        self.style_val("shadow_color").unwrap().color().hash(hasher);
+        let offset = self.style_val("shadow_offset").unwrap().point();
+        (offset.x.to_bits(), offset.y.to_bits()).hash(hasher);
+        // Fractions matter while these are animated
+        for param in ["shadow_blur", "shadow_spread", "backdrop_blur"] {
+            self.style_val(param).unwrap().f32().to_bits().hash(hasher);
         }
+        self.style_val("shadow_inset").unwrap().bool().hash(hasher);
         // Maybe TODO: Should hash scroll_descriptor
     }
 
@@ -317,6 +385,17 @@ impl Component for Div {
         }
     }
 
+    fn clip(&self, _aabb: AABB) -> Option<Clip> {
+        let radius = self.radius();
+        // Scroll frames are clipped to their rect by a scissor, but not to rounded corners
+        let rounded_scroll_frame = self.scrollable() && radius != (0., 0., 0., 0.);
+        if self.overflow == Overflow::Clip || rounded_scroll_frame {
+            Some(Clip::RoundedRect(radius))
+        } else {
+            None
+        }
+    }
+
     fn frame_bounds(&self, aabb: AABB, inner_scale: Option<Scale>) -> AABB {
         let mut aabb = aabb;
         if self.scrollable() {
@@ -355,9 +434,30 @@ impl Component for Div {
             .border_width
             .map_or(0.0, |x| (x * context.scale_factor.floor()).round());
 
-        if let Some(bg) = self.background {
+        let shadow = self.shadow_renderable(&context);
+        let inset_shadow = self.style_val("shadow_inset").unwrap().bool();
+        if !inset_shadow {
+            rs.extend(shadow.clone());
+        }
+
+        let backdrop_blur = self.style_val("backdrop_blur").unwrap().f32();
+        if backdrop_blur > 0.0 {
+            let instance = BackdropBlurInstanceBuilder::default()
+                .pos(context.aabb.pos)
+                .scale(context.aabb.size())
+                .radius(self.radius())
+                .sigma(backdrop_blur * context.scale_factor)
+                .build()
+                .unwrap();
+            rs.push(Renderable::BackdropBlur(BackdropBlur::from_instance_data(
+                instance,
+            )));
+        }
+
+        let bg = self.background_color();
+        if bg.a > 0.0 {
             // println!("Background color {:?} {:?}", bg, context.scissor);
-            let mut rect_instance = InstanceBuilder::default()
+            let rect_instance = InstanceBuilder::default()
                 .pos(Pos {
                     x: context.aabb.pos.x,
                     y: context.aabb.pos.y,
@@ -365,24 +465,24 @@ impl Component for Div {
                 })
                 .scale(context.aabb.size())
                 .color(bg)
+                .radius(self.radius())
                 .build()
                 .unwrap();
-            if let Some(radius) = self.radius {
-                rect_instance.radius = radius;
-            };
 
             rs.push(Renderable::Rect(Rect::from_instance_data(rect_instance)))
         }
 
-        if let (Some(color), Some(width), Some(radius)) =
-            (self.border_color, self.border_width, self.radius)
-        {
+        if inset_shadow {
+            rs.extend(shadow);
+        }
+
+        if let (Some(color), Some(width)) = (self.border_color, self.border_width) {
             let rect_instance = InstanceBuilder::default()
                 .pos(context.aabb.pos)
                 .scale(context.aabb.size())
                 .border_color(color)
                 .border_size(width)
-                .radius(radius)
+                .radius(self.radius())
                 .build()
                 .unwrap();
             rs.push(Renderable::Rect(Rect::from_instance_data(rect_instance)))
@@ -543,3 +643,39 @@ impl Component for Div {
         Some(rs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::Easing;
+    use crate::style::{StyleTransition, StyleTransitions};
+    use std::time::Duration;
+
+    #[test]
+    fn test_div_transition() {
+        let transition = StyleTransition::all(Duration::from_secs(10), Easing::Linear);
+        let div = |bg: Color, radius: f32| {
+            Div::new()
+                .bg(bg)
+                .border(Color::BLACK, 1.0, (radius, radius, radius, radius))
+                .style("transition", transition.clone())
+        };
+        let mut transitions = StyleTransitions::default();
+
+        // Nothing to animate from on the first render
+        let first = div(Color::BLACK, 0.0);
+        let values = transitions.with_active(|| (first.background_color(), first.radius()));
+        assert_eq!(values, (Color::BLACK, (0.0, 0.0, 0.0, 0.0)));
+
+        // Starts from the previous values, then moves towards the new ones
+        let second = div(Color::WHITE, 10.0);
+        let values = transitions.with_active(|| (second.background_color(), second.radius()));
+        assert_eq!(values, (Color::BLACK, (0.0, 0.0, 0.0, 0.0)));
+        std::thread::sleep(Duration::from_millis(10));
+        let (bg, (radius, ..)) =
+            transitions.with_active(|| (second.background_color(), second.radius()));
+        assert!(bg.r > 0.0 && bg.r < 1.0, "{:?}", bg);
+        assert!(radius > 0.0 && radius < 10.0, "{:?}", radius);
+        assert!(transitions.is_running());
+    }
+}
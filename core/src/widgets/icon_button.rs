@@ -101,10 +101,21 @@ impl Component for IconButton {
         let background_color: Color = self.style_val("background_color").into();
         let border_color: Color = self.style_val("border_color").into();
         let border_width: f32 = self.style_val("border_width").unwrap().f32();
+        let icon_color = if self.disabled {
+            self.style_val("icon_disabled_color")
+        } else if self.state_ref().pressed {
+            self.style_val("icon_active_color")
+        } else if self.state_ref().hover {
+            self.style_val("icon_highlight_color")
+        } else {
+            self.style_val("icon_color")
+        };
 
         let icon = match self.icon_type {
             IconType::Svg => node!(
-                super::Svg::new(self.icon.clone()),
+                super::Svg::new(self.icon.clone())
+                    .style("color", icon_color.unwrap())
+                    .style("tint", self.style_val("icon_tint").unwrap()),
                 lay![
                     size: size_pct!(100.0),
                 ],
@@ -153,14 +164,19 @@ impl Component for IconButton {
     }
 
     fn on_mouse_enter(&mut self, _event: &mut event::Event<event::MouseEnter>) {
-        // self.state_mut().hover = true;
+        if self.disabled {
+            return;
+        }
+        self.state_mut().hover = true;
         // if let Some(w) = current_window() {
         //     w.set_cursor("PointingHand");
         // }
     }
 
     fn on_mouse_leave(&mut self, _event: &mut event::Event<event::MouseLeave>) {
-        // *self.state_mut() = IconButtonState::default();
+        if self.state_ref().hover {
+            self.state_mut().hover = false;
+        }
         // if let Some(w) = current_window() {
         //     w.unset_cursor();
         // }
@@ -1,28 +1,46 @@
 use std::hash::Hash;
 
-use mctk_macros::component;
+use mctk_macros::{component, state_component_impl};
 
-use crate::component::{Component, ComponentHasher, RenderContext};
+use crate::component::{Component, ComponentHasher, Message, RenderContext};
 
-use crate::renderables::image::InstanceBuilder as ImageInstanceBuilder;
+use crate::event;
+use crate::renderables::image::{ImageFit, InstanceBuilder as ImageInstanceBuilder};
+use crate::renderables::rect::InstanceBuilder as RectInstanceBuilder;
 use crate::renderables::types::{Point, Size};
 use crate::renderables::{self, Rect, Renderable};
 use crate::style::{self, Styled};
 use crate::types::*;
 
-#[component(Styled)]
-#[derive(Debug)]
+#[derive(Debug, Default)]
+struct ImageState {
+    /// Set once an image loaded from `path` finished decoding
+    loaded: Option<Result<(), String>>,
+}
+
+/// Draws an image by name. Its `fit` (`"fill"`, `"contain"`, `"cover"`, `"none"` or `"scale_down"`),
+/// `align`, `repeat` and nine-patch `slice` are style parameters.
+#[component(State = "ImageState", Styled)]
 pub struct Image {
     pub name: String,
+    /// Decode the image from this path in the background, instead of using an asset of the window
+    pub path: Option<String>,
+    pub on_load: Option<Box<dyn Fn() -> Message + Send + Sync>>,
+    pub on_error: Option<Box<dyn Fn(String) -> Message + Send + Sync>>,
+}
+
+impl std::fmt::Debug for Image {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("Image")
+            .field("name", &self.name)
+            .field("path", &self.path)
+            .finish()
+    }
 }
 
 impl Default for Image {
     fn default() -> Self {
-        Self {
-            name: "".to_string(),
-            class: Default::default(),
-            style_overrides: Default::default(),
-        }
+        Self::new("")
     }
 }
 
@@ -30,33 +48,150 @@ impl Image {
     pub fn new<S: Into<String>>(name: S) -> Self {
         Self {
             name: name.into(),
+            path: None,
+            on_load: None,
+            on_error: None,
+            state: Some(ImageState::default()),
+            dirty: false,
             class: Default::default(),
             style_overrides: Default::default(),
         }
     }
+
+    /// Decode the image at `path` in the background. The `placeholder_color` style is drawn until it is loaded.
+    pub fn load_from<S: Into<String>>(mut self, path: S) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Called once the image at `path` is decoded, right away if it was decoded before the `Image` was made.
+    pub fn on_load(mut self, f: Box<dyn Fn() -> Message + Send + Sync>) -> Self {
+        self.on_load = Some(f);
+        self
+    }
+
+    /// Called once the image at `path` failed to decode, right away if it failed before the `Image` was made.
+    pub fn on_error(mut self, f: Box<dyn Fn(String) -> Message + Send + Sync>) -> Self {
+        self.on_error = Some(f);
+        self
+    }
+
+    /// The `"slice"` style, `None` when it is zero.
+    fn slice(&self) -> Option<(f32, f32, f32, f32)> {
+        let slice = self.style_val("slice").unwrap().rect();
+        let slice: (f32, f32, f32, f32) = (
+            slice.top.into(),
+            slice.right.into(),
+            slice.bottom.into(),
+            slice.left.into(),
+        );
+        if slice == (0.0, 0.0, 0.0, 0.0) {
+            None
+        } else {
+            Some(slice)
+        }
+    }
+
+    fn is_loading(&self) -> bool {
+        self.path.is_some() && !matches!(self.state_ref().loaded, Some(Ok(())))
+    }
 }
 
+#[state_component_impl(ImageState)]
 impl Component for Image {
+    fn register(&mut self) -> Vec<event::Register> {
+        if self.path.is_some() && self.state_ref().loaded.is_none() {
+            vec![event::Register::ImageLoad]
+        } else {
+            vec![]
+        }
+    }
+
+    fn on_image_load(&mut self, event: &mut event::Event<event::ImageLoad>) {
+        // Loads replayed for a new `Image` may come more than once
+        if event.input.name != self.name || self.path.is_none() || self.state_ref().loaded.is_some()
+        {
+            return;
+        }
+        match &event.input.result {
+            Ok(()) => {
+                if let Some(f) = &self.on_load {
+                    event.emit(f());
+                }
+            }
+            Err(e) => {
+                if let Some(f) = &self.on_error {
+                    event.emit(f(e.clone()));
+                }
+            }
+        }
+        self.state_mut().loaded = Some(event.input.result.clone());
+    }
+
     fn render_hash(&self, hasher: &mut ComponentHasher) {
         self.name.hash(hasher);
+        self.path.hash(hasher);
+        self.state_ref().loaded.hash(hasher);
+        self.style_val("radius")
+            .unwrap()
+            .f32()
+            .to_bits()
+            .hash(hasher);
+        self.style_val("fit").unwrap().str().hash(hasher);
+        let align = self.style_val("align").unwrap().point();
+        (align.x.to_bits(), align.y.to_bits()).hash(hasher);
+        self.style_val("repeat").unwrap().bool().hash(hasher);
+        self.slice()
+            .map(|(t, r, b, l)| [t, r, b, l].map(f32::to_bits))
+            .hash(hasher);
     }
 
     fn render(&mut self, context: RenderContext) -> Option<Vec<Renderable>> {
+        // Made after the image finished loading, so it missed the `ImageLoad`
+        if self.path.is_some() && self.state_ref().loaded.is_none() {
+            context.caches.images.replay(&self.name);
+        }
+
         let width = context.aabb.width();
         let height = context.aabb.height();
         let AABB { pos, .. } = context.aabb;
         let radius = self.style_val("radius").unwrap().f32();
+        let fit = self.style_val("fit").unwrap().str();
+        let fit = ImageFit::from_name(fit).unwrap_or_else(|| {
+            println!("Unknown image fit {:?}", fit);
+            ImageFit::Fill
+        });
+        let align = self.style_val("align").unwrap().point();
+        let mut rs = vec![];
+
+        if self.is_loading() {
+            let placeholder = RectInstanceBuilder::default()
+                .pos(pos)
+                .scale(Scale { width, height })
+                .color(self.style_val("placeholder_color").unwrap().color())
+                .radius((radius, radius, radius, radius))
+                .build()
+                .unwrap();
+            rs.push(Renderable::Rect(Rect::from_instance_data(placeholder)));
+        }
 
         let instance = ImageInstanceBuilder::default()
             .pos(pos)
             .scale(Scale { width, height })
             .name(self.name.clone())
             .radius(radius)
+            .dynamic_load_from(self.path.clone())
+            .fit(fit)
+            .align(Point::new(align.x, align.y))
+            .repeat(self.style_val("repeat").unwrap().bool())
+            .slice(self.slice())
+            .scale_factor(context.scale_factor)
             .build()
             .unwrap();
+        rs.push(Renderable::Image(renderables::Image::from_instance_data(
+            instance,
+        )));
 
-        Some(vec![Renderable::Image(
-            renderables::Image::from_instance_data(instance),
-        )])
+        Some(rs)
     }
 }
@@ -13,11 +13,14 @@ mod text;
 pub use text::Text;
 
 mod div;
-pub use div::Div;
+pub use div::{Div, Overflow};
 
 mod image;
 pub use image::Image;
 
+mod animated_image;
+pub use animated_image::AnimatedImage;
+
 mod svg;
 pub use svg::Svg;
 
@@ -25,10 +28,13 @@ mod slider;
 pub use slider::Slider;
 
 mod carousel;
-pub use carousel::{Carousel, TransitionPositions};
+pub use carousel::Carousel;
 
 mod textbox;
 pub use textbox::{TextBox, TextBoxAction, TextBoxVariant};
 
+mod decorations;
+pub use decorations::{DecorationMessage, ResizeEdge, TitleBar, WindowFrame};
+
 // mod slide_show;
 // pub use slide_show::SlideShow;
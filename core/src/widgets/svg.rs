@@ -2,10 +2,15 @@ use std::hash::Hash;
 
 use crate::component::{Component, ComponentHasher, RenderContext};
 
-use crate::renderables::types::{Point, Size};
-use crate::renderables::{self, Rect, Renderable};
+use crate::renderables::svg::InstanceBuilder as SvgInstanceBuilder;
+use crate::renderables::{self, Renderable};
+use crate::style::Styled;
 use crate::types::*;
+use mctk_macros::component;
 
+/// Draws the SVG `name`. Its fills and strokes that use `currentColor` take the `color` style
+/// parameter, and all of them do when `tint` is set.
+#[component(Styled, Internal)]
 #[derive(Debug)]
 pub struct Svg {
     pub name: String,
@@ -15,19 +20,26 @@ impl Default for Svg {
     fn default() -> Self {
         Self {
             name: "".to_string(),
+            class: Default::default(),
+            style_overrides: Default::default(),
         }
     }
 }
 
 impl Svg {
     pub fn new<S: Into<String>>(name: S) -> Self {
-        Self { name: name.into() }
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
     }
 }
 
 impl Component for Svg {
     fn render_hash(&self, hasher: &mut ComponentHasher) {
         self.name.hash(hasher);
+        self.style_val("color").unwrap().color().hash(hasher);
+        self.style_val("tint").unwrap().bool().hash(hasher);
     }
 
     fn render(&mut self, context: RenderContext) -> Option<Vec<Renderable>> {
@@ -35,10 +47,17 @@ impl Component for Svg {
         let height = context.aabb.height();
         let Pos { x, y, .. } = context.aabb.pos;
 
-        Some(vec![Renderable::Svg(renderables::Svg::new(
-            [x, y].into(),
-            [width, height].into(),
-            self.name.clone(),
+        let instance = SvgInstanceBuilder::default()
+            .pos([x, y].into())
+            .scale([width, height].into())
+            .name(self.name.clone())
+            .dynamic_load_from(None)
+            .color(self.style_val("color").unwrap().color())
+            .tint(self.style_val("tint").unwrap().bool())
+            .build()
+            .unwrap();
+        Some(vec![Renderable::Svg(renderables::Svg::from_instance_data(
+            instance,
         ))])
     }
 }
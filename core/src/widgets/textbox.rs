@@ -2,7 +2,7 @@ use std::cmp::Ordering;
 use std::default;
 use std::hash::Hash;
 use std::ops::Add;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::component::{Component, ComponentHasher, Message, RenderContext};
 use crate::font_cache::{FontCache, TextSegment};
@@ -20,7 +20,7 @@ use mctk_macros::{component, state_component_impl};
 
 use super::IconButton;
 
-const CURSOR_BLINK_PERIOD: u128 = 500; // millis
+const CURSOR_BLINK_PERIOD: u64 = 500; // millis
 
 #[derive(Debug)]
 enum TextBoxMessage {
@@ -559,14 +559,28 @@ impl Component for TextBoxText {
         // }
     }
 
-    fn on_tick(&mut self, _event: &mut event::Event<event::Tick>) {
+    fn register(&mut self) -> Vec<event::Register> {
+        // blink the cursor only while focused
         if self.state_ref().focused {
-            let visible =
-                (self.state_ref().activated_at.elapsed().as_millis() / CURSOR_BLINK_PERIOD) % 2
-                    == 0;
-            if visible != self.state_ref().cursor_visible {
-                self.state_mut().cursor_visible = visible;
-            }
+            vec![event::Register::interval(
+                "cursor_blink",
+                Duration::from_millis(CURSOR_BLINK_PERIOD),
+            )]
+        } else {
+            vec![]
+        }
+    }
+
+    fn on_timer(&mut self, event: &mut event::Event<event::Timer>) {
+        if event.input.name != "cursor_blink" || !self.state_ref().focused {
+            return;
+        }
+        // keep the cursor solid for a full period after it moved
+        let visible = self.state_ref().activated_at.elapsed().as_millis()
+            < CURSOR_BLINK_PERIOD as u128
+            || !self.state_ref().cursor_visible;
+        if visible != self.state_ref().cursor_visible {
+            self.state_mut().cursor_visible = visible;
         }
     }
 
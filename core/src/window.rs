@@ -1,10 +1,10 @@
 use crate::{
     raw_handle::RawWaylandHandle,
+    resources::Resources,
     types::{Data, PixelSize},
-    AssetParams,
 };
 use raw_window_handle::{HasRawDisplayHandle, HasRawWindowHandle};
-use std::{any::Any, collections::HashMap};
+use std::{any::Any, time::Instant};
 
 /// The trait that backends must implement. An instance is returned by [`current_window`][crate::current_window] so that an app may interact with the OS's windowing system.
 pub trait Window: HasRawWindowHandle + HasRawDisplayHandle + Send + Sync + Any {
@@ -23,6 +23,15 @@ pub trait Window: HasRawWindowHandle + HasRawDisplayHandle + Send + Sync + Any {
     /// Request next frame
     fn next_frame(&self) {}
 
+    /// Wake the UI up with [`Input::TimerExpired`][crate::input::Input::TimerExpired] once `deadline` is reached.
+    fn schedule_timer(&self, _deadline: Instant) {}
+
+    /// Wake the UI up with [`Input::TimerExpired`][crate::input::Input::TimerExpired] as soon as possible, for work
+    /// finished in the background like decoding images.
+    fn wake(&self) {
+        self.schedule_timer(Instant::now());
+    }
+
     /// Set the current cursor. Cursor names are backend-specific, but they should support the following:
     /// - "Arrow"
     /// - "None"
@@ -59,14 +68,8 @@ pub trait Window: HasRawWindowHandle + HasRawDisplayHandle + Send + Sync + Any {
     /// When responding to a Drag and Drop action, tell the window of origin whether the mouse is currently over a valid drop target.
     fn set_drop_target_valid(&self, _valid: bool) {}
 
-    // For fonts
-    fn fonts(&self) -> cosmic_text::fontdb::Database;
-
-    // For assets
-    fn assets(&self) -> HashMap<String, AssetParams>;
-
-    // For svgs
-    fn svgs(&self) -> HashMap<String, String>;
+    /// The fonts, assets and SVGs of the window, shared with the other windows of its application.
+    fn resources(&self) -> Resources;
 
     // used to reconfigure size
     fn set_size(&mut self, width: u32, height: u32) {}
@@ -74,6 +77,33 @@ pub trait Window: HasRawWindowHandle + HasRawDisplayHandle + Send + Sync + Any {
     // trigger exit
     fn exit(&mut self);
 
+    /// Set the title of the window. Only toplevel windows have a title.
+    fn set_title(&self, _title: String) {}
+
+    /// Set the app id of the window. Only toplevel windows have an app id.
+    fn set_app_id(&self, _app_id: String) {}
+
+    /// Ask the compositor to maximize the window.
+    fn maximize(&self) {}
+
+    /// Ask the compositor to unmaximize the window.
+    fn unmaximize(&self) {}
+
+    /// Ask the compositor to make the window fullscreen.
+    fn fullscreen(&self) {}
+
+    /// Ask the compositor to leave fullscreen.
+    fn unfullscreen(&self) {}
+
+    /// Ask the compositor to minimize the window.
+    fn minimize(&self) {}
+
+    /// Set the minimum size of the window, `None` removes the limit.
+    fn set_min_size(&self, _size: Option<(u32, u32)>) {}
+
+    /// Set the maximum size of the window, `None` removes the limit.
+    fn set_max_size(&self, _size: Option<(u32, u32)>) {}
+
     // used to reconfigure wayland_handle
     fn set_wayland_handle(&mut self, wayland_handle: RawWaylandHandle) {}
 
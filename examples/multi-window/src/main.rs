@@ -0,0 +1,146 @@
+use mctk_core::component::{Component, Message, RootComponent};
+use mctk_core::layout::Direction;
+use mctk_core::reexports::cosmic_text;
+use mctk_core::reexports::smithay_client_toolkit::reexports::calloop::channel::Sender;
+use mctk_core::style::Styled;
+use mctk_core::widgets::{Button, Div};
+use mctk_core::{lay, msg, size, size_pct, txt, Color};
+use mctk_core::{node, node::Node};
+use mctk_macros::{component, state_component_impl};
+use mctk_smithay::application::app_window::AppWindow;
+use mctk_smithay::application::sctk_application::{
+    AppMessage, ApplicationParams, SctkApplication, SurfaceKind, SurfaceParams,
+};
+use mctk_smithay::{WindowInfo, WindowOptions};
+use std::any::Any;
+
+#[derive(Debug, Clone)]
+pub struct AppParams {}
+
+#[derive(Debug, Default)]
+pub struct AppState {
+    window_id: String,
+    clicks: u32,
+    app_sender: Option<Sender<AppMessage>>,
+}
+
+#[derive(Debug, Clone)]
+enum WindowEvent {
+    ButtonPressed,
+    Clicked { from: String },
+    CloseAll,
+}
+
+#[component(State = "AppState")]
+#[derive(Debug, Default)]
+pub struct App {}
+
+#[state_component_impl(AppState)]
+impl Component for App {
+    fn init(&mut self) {
+        self.state = Some(AppState::default())
+    }
+
+    fn view(&self) -> Option<Node> {
+        let clicks = self.state_ref().clicks;
+
+        Some(
+            node!(
+                Div::new().bg(Color::rgb(30., 30., 30.)),
+                lay![
+                    size: size_pct!(100.0),
+                    direction: Direction::Column
+                ]
+            )
+            .push(node!(
+                Button::new(txt!(format!("Ping the other window ({})", clicks)))
+                    .on_click(Box::new(|| msg!(WindowEvent::ButtonPressed)))
+                    .style("font_size", 18.0),
+                lay![size: size!(280.0, 60.0), margin: [20., 20., 20., 20.]]
+            ))
+            .push(node!(
+                Button::new(txt!("Close all"))
+                    .on_click(Box::new(|| msg!(WindowEvent::CloseAll)))
+                    .style("font_size", 18.0),
+                lay![size: size!(280.0, 60.0), margin: [0., 20., 20., 20.]]
+            )),
+        )
+    }
+
+    fn update(&mut self, message: Message) -> Vec<Message> {
+        match message.downcast_ref::<WindowEvent>() {
+            Some(WindowEvent::ButtonPressed) => {
+                let window_id = self.state_ref().window_id.clone();
+                let to = if window_id == "main" {
+                    "settings"
+                } else {
+                    "main"
+                };
+                if let Some(app_sender) = self.state_ref().app_sender.as_ref() {
+                    let _ = app_sender.send(AppMessage::Send {
+                        window_id: to.to_string(),
+                        message: msg!(WindowEvent::Clicked { from: window_id }),
+                    });
+                }
+            }
+            Some(WindowEvent::Clicked { from }) => {
+                println!("{} clicked from {}", self.state_ref().window_id, from);
+                self.state_mut().clicks += 1;
+            }
+            Some(WindowEvent::CloseAll) => {
+                if let Some(app_sender) = self.state_ref().app_sender.as_ref() {
+                    let _ = app_sender.send(AppMessage::Exit);
+                }
+            }
+            _ => (),
+        }
+        vec![]
+    }
+}
+
+impl RootComponent<AppParams> for App {
+    fn root(&mut self, w: &dyn Any, _app_params: &dyn Any) {
+        let window = w.downcast_ref::<AppWindow>().unwrap();
+        self.state_mut().window_id = window.id().to_string();
+        self.state_mut().app_sender = Some(window.app_sender());
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let mut fonts = cosmic_text::fontdb::Database::new();
+    fonts.load_system_fonts();
+
+    let (mut app, mut event_loop) = SctkApplication::new(ApplicationParams {
+        fonts,
+        ..Default::default()
+    })?;
+
+    for (id, title) in [("main", "Main"), ("settings", "Settings")] {
+        app.open_surface::<App, AppParams>(
+            SurfaceParams {
+                window_info: WindowInfo {
+                    id: id.to_string(),
+                    title: format!("mctk.examples.multi-window {}", title),
+                    namespace: id.to_string(),
+                },
+                window_opts: WindowOptions {
+                    height: 200,
+                    width: 320,
+                    scale_factor: 1.0,
+                },
+                kind: SurfaceKind::Xdg,
+            },
+            AppParams {},
+        )?;
+    }
+
+    loop {
+        let _ = event_loop.dispatch(None, &mut app);
+
+        if app.is_exited {
+            break;
+        }
+    }
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use mctk_core::component::{Component, Message, RootComponent};
 use mctk_core::layout::Alignment;
 use mctk_core::reexports::cosmic_text;
+use mctk_core::style::Styled;
 use mctk_core::widgets::{self, Svg};
+use mctk_core::window::Window;
 use mctk_core::{lay, size, size_pct, AssetParams, Color};
 use mctk_core::{node, node::Node};
 use mctk_macros::{component, state_component_impl};
@@ -10,6 +12,7 @@ use mctk_smithay::layer_shell::layer_window;
 use mctk_smithay::layer_shell::layer_window::LayerWindowParams;
 use mctk_smithay::{WindowInfo, WindowOptions};
 use smithay_client_toolkit::shell::wlr_layer;
+use std::any::Any;
 use std::collections::HashMap;
 use tracing_subscriber::EnvFilter;
 
@@ -115,7 +118,11 @@ impl Component for App {
                     Svg::new(svg_name.clone()),
                     lay!(size: size!(300.0, 300.0), ),
                 )),
-            ),
+            )
+            .push(node!(
+                Svg::new("badge").style("color", Color::rgb(220., 40., 40.)),
+                lay!(size: size!(16.0, 16.0), ),
+            )),
         )
     }
 
@@ -243,4 +250,15 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-impl RootComponent<AppParams> for App {}
+impl RootComponent<AppParams> for App {
+    fn root(&mut self, w: &dyn Any, _app_params: &dyn Any) {
+        // SVGs can also be added at runtime, from text made by the app
+        let window = w.downcast_ref::<layer_window::LayerWindow>().unwrap();
+        let badge = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16">
+            <circle cx="8" cy="8" r="7" fill="currentColor"/>
+        </svg>"#;
+        if let Err(e) = window.resources().svgs().insert_str("badge", badge) {
+            println!("Error while adding svg badge error: {:?}", e);
+        }
+    }
+}